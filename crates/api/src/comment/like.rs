@@ -5,7 +5,12 @@ use lemmy_api_common::{
   comment::{CommentResponse, CreateCommentLike},
   context::LemmyContext,
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_bot_account, check_community_user_action, check_downvotes_enabled},
+  utils::{
+    check_bot_account,
+    check_community_user_action,
+    check_downvote_allowed,
+    check_downvotes_enabled,
+  },
 };
 use lemmy_db_schema::{
   newtypes::LocalUserId,
@@ -32,6 +37,7 @@ pub async fn like_comment(
 
   // Don't do a downvote if site has downvotes disabled
   check_downvotes_enabled(data.score, &local_site)?;
+  check_downvote_allowed(data.score, &local_user_view, &local_site)?;
   check_bot_account(&local_user_view.person)?;
 
   let comment_id = data.comment_id;
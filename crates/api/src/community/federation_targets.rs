@@ -0,0 +1,43 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  community::{GetFederationTargets, GetFederationTargetsResponse},
+  context::LemmyContext,
+  utils::is_mod_or_admin,
+};
+use lemmy_db_schema::{
+  source::{community::Community, instance::Instance},
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::CommunityFollowerView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn get_federation_targets(
+  data: Query<GetFederationTargets>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetFederationTargetsResponse>> {
+  let community_id = data.community_id;
+  is_mod_or_admin(&mut context.pool(), &local_user_view.person, community_id).await?;
+
+  let community = Community::read(&mut context.pool(), community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+
+  let instances = if community.local {
+    CommunityFollowerView::get_community_follower_instances(&mut context.pool(), community_id)
+      .await?
+  } else {
+    // Activities for remote communities are sent to their home instance, which forwards them
+    // to the followers.
+    let domain = community.actor_id.domain().unwrap_or_default();
+    Instance::read_from_domain(&mut context.pool(), domain)
+      .await?
+      .into_iter()
+      .collect()
+  };
+
+  Ok(Json(GetFederationTargetsResponse { instances }))
+}
@@ -0,0 +1,97 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{MergeCommunities, MergeCommunitiesResponse},
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::{
+    community::Community,
+    moderator::{ModRemoveCommunity, ModRemoveCommunityForm},
+  },
+  traits::Crud,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::{CommunityFollowerView, CommunityView};
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn merge_communities(
+  data: Json<MergeCommunities>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<MergeCommunitiesResponse>> {
+  is_admin(&local_user_view)?;
+
+  if data.from_community_id == data.into_community_id {
+    Err(LemmyErrorType::CantMergeCommunityIntoItself)?
+  }
+  let from_community = Community::read(&mut context.pool(), data.from_community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+  let into_community = Community::read(&mut context.pool(), data.into_community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+  if !from_community.local || !into_community.local {
+    Err(LemmyErrorType::CantMergeRemoteCommunity)?
+  }
+  // Moved posts and follows aren't federated, so instances which follow the community would keep
+  // the old state. Without remote followers, they only need to know that it was removed.
+  let remote_follower_inboxes =
+    CommunityFollowerView::get_community_follower_inboxes(&mut context.pool(), from_community.id)
+      .await?;
+  if !remote_follower_inboxes.is_empty() {
+    Err(LemmyErrorType::CantMergeCommunityWithRemoteFollowers)?
+  }
+
+  let (posts_moved, followers_moved, moderators_moved) =
+    Community::merge_into(&mut context.pool(), from_community.id, into_community.id)
+      .await
+      .with_lemmy_type(LemmyErrorType::CouldntUpdateCommunity)?;
+
+  // Mod tables
+  let merged_into = format!("Merged into !{}", into_community.name);
+  let reason = Some(match &data.reason {
+    Some(reason) => format!("{merged_into}: {reason}"),
+    None => merged_into,
+  });
+  let form = ModRemoveCommunityForm {
+    mod_person_id: local_user_view.person.id,
+    community_id: from_community.id,
+    removed: Some(true),
+    reason: reason.clone(),
+  };
+  ModRemoveCommunity::create(&mut context.pool(), &form).await?;
+
+  ActivityChannel::submit_activity(
+    SendActivityData::RemoveCommunity {
+      moderator: local_user_view.person.clone(),
+      community: Community {
+        removed: true,
+        ..from_community
+      },
+      reason,
+      removed: true,
+    },
+    &context,
+  )
+  .await?;
+
+  let community_view = CommunityView::read(
+    &mut context.pool(),
+    into_community.id,
+    Some(local_user_view.person.id),
+    true,
+  )
+  .await?
+  .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+
+  Ok(Json(MergeCommunitiesResponse {
+    community_view,
+    posts_moved: posts_moved as i64,
+    followers_moved: followers_moved as i64,
+    moderators_moved: moderators_moved as i64,
+  }))
+}
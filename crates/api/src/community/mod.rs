@@ -1,6 +1,9 @@
 pub mod add_mod;
 pub mod ban;
 pub mod block;
+pub mod federation_targets;
 pub mod follow;
 pub mod hide;
+pub mod merge;
 pub mod transfer;
+pub mod validate_name;
@@ -0,0 +1,56 @@
+use activitypub_federation::config::Data;
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  community::{ValidateCommunityName, ValidateCommunityNameResponse},
+  context::LemmyContext,
+  utils::local_site_to_slur_regex,
+};
+use lemmy_db_schema::{
+  source::{community::Community, local_site::LocalSite},
+  traits::ApubActor,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  utils::{slurs::check_slurs, validation::is_valid_actor_name},
+};
+
+/// Runs the same name checks as community creation, so that clients can give instant feedback.
+#[tracing::instrument(skip(context))]
+pub async fn validate_community_name(
+  data: Query<ValidateCommunityName>,
+  context: Data<LemmyContext>,
+  _local_user_view: LocalUserView,
+) -> LemmyResult<Json<ValidateCommunityNameResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let normalized = data.name.trim().to_string();
+
+  let error = check_community_name(&normalized, &local_site, &context)
+    .await
+    .err()
+    .map(|e| e.error_type);
+
+  Ok(Json(ValidateCommunityNameResponse {
+    valid: error.is_none(),
+    normalized,
+    error,
+  }))
+}
+
+async fn check_community_name(
+  name: &str,
+  local_site: &LocalSite,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  is_valid_actor_name(name, local_site.actor_name_max_length as usize)?;
+  check_slurs(name, &local_site_to_slur_regex(local_site))?;
+
+  // Names are unique among local communities, regardless of case and deletion
+  if Community::read_from_name(&mut context.pool(), name, true)
+    .await?
+    .is_some()
+  {
+    Err(LemmyErrorType::CommunityAlreadyExists)?
+  }
+  Ok(())
+}
@@ -2,7 +2,7 @@ use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
   person::{AddAdmin, AddAdminResponse},
-  utils::is_admin,
+  utils::{invalidate_site_cache, is_admin},
 };
 use lemmy_db_schema::{
   source::{
@@ -49,6 +49,7 @@ pub async fn add_admin(
 
   ModAdd::create(&mut context.pool(), &form).await?;
 
+  invalidate_site_cache().await;
   let admins = PersonView::admins(&mut context.pool()).await?;
 
   Ok(Json(AddAdminResponse { admins }))
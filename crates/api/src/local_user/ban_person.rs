@@ -5,7 +5,7 @@ use lemmy_api_common::{
   context::LemmyContext,
   person::{BanPerson, BanPersonResponse},
   send_activity::{ActivityChannel, SendActivityData},
-  utils::{check_expire_time, is_admin, remove_user_data},
+  utils::{check_expire_time, invalidate_site_cache, is_admin, remove_user_data},
 };
 use lemmy_db_schema::{
   source::{
@@ -74,6 +74,11 @@ pub async fn ban_from_site(
     .await?
     .ok_or(LemmyErrorType::CouldntFindPerson)?;
 
+  // The cached site response contains the admins, including their ban status
+  if person_view.is_admin {
+    invalidate_site_cache().await;
+  }
+
   ban_nonlocal_user_from_local_communities(
     &local_user_view,
     &person,
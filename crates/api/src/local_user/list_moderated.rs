@@ -0,0 +1,29 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{GetModeratedCommunities, GetModeratedCommunitiesResponse, ModeratedCommunity},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::CommunityModeratorView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_moderated_communities(
+  data: Query<GetModeratedCommunities>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetModeratedCommunitiesResponse>> {
+  let my_person_id = local_user_view.person.id;
+  let person_id = data.person_id.unwrap_or(my_person_id);
+  // Private communities are only shown for your own account, or to admins
+  let only_public = person_id != my_person_id && !local_user_view.local_user.admin;
+
+  let communities =
+    CommunityModeratorView::for_person_with_rank(&mut context.pool(), person_id, only_public)
+      .await?
+      .into_iter()
+      .map(|(community, rank)| ModeratedCommunity { community, rank })
+      .collect();
+
+  Ok(Json(GetModeratedCommunitiesResponse { communities }))
+}
@@ -11,6 +11,7 @@ pub mod get_captcha;
 pub mod list_banned;
 pub mod list_logins;
 pub mod list_media;
+pub mod list_moderated;
 pub mod login;
 pub mod logout;
 pub mod notifications;
@@ -18,6 +19,7 @@ pub mod report_count;
 pub mod reset_password;
 pub mod save_settings;
 pub mod update_totp;
+pub mod user_content_for_mod;
 pub mod validate_auth;
 pub mod verify_email;
 
@@ -1,34 +1,34 @@
 use actix_web::web::{Data, Json};
-use lemmy_api_common::{context::LemmyContext, person::GetRepliesResponse};
-use lemmy_db_schema::source::{
-  comment_reply::CommentReply,
-  person_mention::PersonMention,
-  private_message::PrivateMessage,
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{GetUnreadCountResponse, MarkAllAsRead},
 };
-use lemmy_db_views::structs::LocalUserView;
-use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
+use lemmy_db_schema::source::person::Person;
+use lemmy_db_views::structs::{LocalUserView, PrivateMessageView};
+use lemmy_db_views_actor::structs::{CommentReplyView, PersonMentionView};
+use lemmy_utils::error::LemmyResult;
 
 #[tracing::instrument(skip(context))]
 pub async fn mark_all_notifications_read(
+  data: Json<MarkAllAsRead>,
   context: Data<LemmyContext>,
   local_user_view: LocalUserView,
-) -> LemmyResult<Json<GetRepliesResponse>> {
+) -> LemmyResult<Json<GetUnreadCountResponse>> {
   let person_id = local_user_view.person.id;
 
-  // Mark all comment_replies as read
-  CommentReply::mark_all_as_read(&mut context.pool(), person_id)
-    .await
-    .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
+  Person::mark_notifications_as_read(&mut context.pool(), person_id, data.type_).await?;
 
-  // Mark all user mentions as read
-  PersonMention::mark_all_as_read(&mut context.pool(), person_id)
-    .await
-    .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
+  // Notifications of other types may still be unread
+  let replies = CommentReplyView::get_unread_replies(&mut context.pool(), person_id).await?;
 
-  // Mark all private_messages as read
-  PrivateMessage::mark_all_as_read(&mut context.pool(), person_id)
-    .await
-    .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
+  let mentions = PersonMentionView::get_unread_mentions(&mut context.pool(), person_id).await?;
 
-  Ok(Json(GetRepliesResponse { replies: vec![] }))
+  let private_messages =
+    PrivateMessageView::get_unread_messages(&mut context.pool(), person_id).await?;
+
+  Ok(Json(GetUnreadCountResponse {
+    replies,
+    mentions,
+    private_messages,
+  }))
 }
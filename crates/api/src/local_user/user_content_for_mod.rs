@@ -0,0 +1,72 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  person::{GetUserContentForMod, GetUserContentForModResponse},
+  utils::{is_admin, is_mod_or_admin},
+};
+use lemmy_db_views::{
+  comment_view::CommentQuery,
+  post_view::PostQuery,
+  structs::{LocalUserView, SiteView},
+};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn get_user_content_for_mod(
+  data: Query<GetUserContentForMod>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetUserContentForModResponse>> {
+  // Mods can only review content within their own community, admins can also search site-wide
+  match data.community_id {
+    Some(community_id) => {
+      is_mod_or_admin(&mut context.pool(), &local_user_view.person, community_id).await?
+    }
+    None => is_admin(&local_user_view)?,
+  }
+
+  let local_site = SiteView::read_local(&mut context.pool())
+    .await?
+    .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
+
+  // The content is listed regardless of the settings and blocks of the mod
+  let include_removed = data.include_removed.unwrap_or_default();
+  let creator_id = Some(data.person_id);
+  let community_id = data.community_id;
+  let search_term = data.search_term.clone();
+  let page = data.page;
+  let limit = data.limit;
+
+  let posts = PostQuery {
+    local_user: Some(&local_user_view),
+    creator_id,
+    community_id,
+    search_term: search_term.clone(),
+    include_removed,
+    include_deleted: true,
+    show_hidden: true,
+    skip_viewer_filters: true,
+    page,
+    limit,
+    ..Default::default()
+  }
+  .list(&local_site.site, &mut context.pool())
+  .await?;
+
+  let comments = CommentQuery {
+    local_user: Some(&local_user_view),
+    creator_id,
+    community_id,
+    search_term,
+    include_removed,
+    include_deleted: true,
+    skip_viewer_filters: true,
+    page,
+    limit,
+    ..Default::default()
+  }
+  .list(&mut context.pool())
+  .await?;
+
+  Ok(Json(GetUserContentForModResponse { posts, comments }))
+}
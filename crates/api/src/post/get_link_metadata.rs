@@ -2,9 +2,10 @@ use actix_web::web::{Data, Json, Query};
 use lemmy_api_common::{
   context::LemmyContext,
   post::{GetSiteMetadata, GetSiteMetadataResponse},
-  request::fetch_link_metadata,
+  request::{fetch_link_metadata, fetch_oembed_data},
 };
 use lemmy_utils::error::LemmyResult;
+use tracing::info;
 
 #[tracing::instrument(skip(context))]
 pub async fn get_link_metadata(
@@ -13,5 +14,14 @@ pub async fn get_link_metadata(
 ) -> LemmyResult<Json<GetSiteMetadataResponse>> {
   let metadata = fetch_link_metadata(&data.url, false, &context).await?;
 
-  Ok(Json(GetSiteMetadataResponse { metadata }))
+  // oEmbed data is optional, so fall back to the opengraph metadata if it can't be fetched
+  let oembed = match &metadata.oembed_url {
+    Some(oembed_url) => fetch_oembed_data(oembed_url)
+      .await
+      .map_err(|e| info!("{e}"))
+      .ok(),
+    None => None,
+  };
+
+  Ok(Json(GetSiteMetadataResponse { metadata, oembed }))
 }
@@ -8,6 +8,7 @@ use lemmy_api_common::{
   utils::{
     check_bot_account,
     check_community_user_action,
+    check_downvote_allowed,
     check_downvotes_enabled,
     mark_post_as_read,
   },
@@ -34,6 +35,7 @@ pub async fn like_post(
 
   // Don't do a downvote if site has downvotes disabled
   check_downvotes_enabled(data.score, &local_site)?;
+  check_downvote_allowed(data.score, &local_user_view, &local_site)?;
   check_bot_account(&local_user_view.person)?;
 
   // Check for a community ban
@@ -51,7 +51,7 @@ pub async fn lock_post(
 
   // Mod tables
   let form = ModLockPostForm {
-    mod_person_id: local_user_view.person.id,
+    mod_person_id: Some(local_user_view.person.id),
     post_id: data.post_id,
     locked: Some(locked),
   };
@@ -1,5 +1,9 @@
 use actix_web::web::{Data, Json};
-use lemmy_api_common::{context::LemmyContext, site::GetSiteResponse, utils::is_admin};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{BuildInfo, GetSiteResponse},
+  utils::{invalidate_site_cache, is_admin, supported_activity_types},
+};
 use lemmy_db_schema::{
   source::{
     actor_language::SiteLanguage,
@@ -53,6 +57,7 @@ pub async fn leave_admin(
   };
 
   ModAdd::create(&mut context.pool(), &form).await?;
+  invalidate_site_cache().await;
 
   // Reread site and admins
   let site_view = SiteView::read_local(&mut context.pool())
@@ -66,16 +71,21 @@ pub async fn leave_admin(
   let custom_emojis =
     CustomEmojiView::get_all(&mut context.pool(), site_view.local_site.id).await?;
   let blocked_urls = LocalSiteUrlBlocklist::get_all(&mut context.pool()).await?;
+  let supported_activities = supported_activity_types(&site_view.local_site);
+  let federation_enabled = site_view.local_site.federation_enabled;
 
   Ok(Json(GetSiteResponse {
     site_view,
     admins,
     version: VERSION.to_string(),
+    build_info: BuildInfo::default(),
     my_user: None,
     all_languages,
     discussion_languages,
     taglines,
     custom_emojis,
     blocked_urls,
+    federation_enabled,
+    supported_activities,
   }))
 }
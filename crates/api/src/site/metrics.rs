@@ -0,0 +1,31 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{context::LemmyContext, site::GetSiteMetricsResponse, utils::is_admin};
+use lemmy_db_schema::source::instance::Instance;
+use lemmy_db_views::structs::{LocalUserView, SiteView};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn get_site_metrics(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetSiteMetricsResponse>> {
+  is_admin(&local_user_view)?;
+
+  let site_view = SiteView::read_local(&mut context.pool())
+    .await?
+    .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
+  let federated_instances =
+    Instance::count_federated(&mut context.pool(), site_view.site.instance_id).await?;
+
+  let counts = site_view.counts;
+  Ok(Json(GetSiteMetricsResponse {
+    users: counts.users,
+    posts: counts.posts,
+    comments: counts.comments,
+    communities: counts.communities,
+    users_active_day: counts.users_active_day,
+    users_active_week: counts.users_active_week,
+    users_active_month: counts.users_active_month,
+    federated_instances,
+  }))
+}
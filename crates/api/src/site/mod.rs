@@ -2,6 +2,14 @@ pub mod block;
 pub mod federated_instances;
 pub mod leave_admin;
 pub mod list_all_media;
+pub mod metrics;
 pub mod mod_log;
+pub mod mod_log_entry;
+mod mod_log_feed;
+pub mod moderation_team;
 pub mod purge;
+pub mod random_tagline;
 pub mod registration_applications;
+pub mod rotate_federation_key;
+pub mod slow_queries;
+pub mod stats_history;
@@ -1,16 +1,22 @@
-use actix_web::web::{Data, Json, Query};
+use crate::site::mod_log_feed::modlog_channel;
+use actix_web::{
+  web::{Data, Query},
+  HttpResponse,
+};
 use lemmy_api_common::{
   context::LemmyContext,
   site::{GetModlog, GetModlogResponse},
-  utils::{check_community_mod_of_any_or_admin_action, check_private_instance},
+  utils::{check_community_mod_of_any_or_admin_action, check_private_instance, is_mod_or_admin},
 };
-use lemmy_db_schema::{source::local_site::LocalSite, ModlogActionType};
-use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_schema::{ModlogActionType, ModlogFormat, ModlogOrder};
+use lemmy_db_views::structs::{LocalUserView, SiteView};
 use lemmy_db_views_moderator::structs::{
+  AdminExportUserDataView,
   AdminPurgeCommentView,
   AdminPurgeCommunityView,
   AdminPurgePersonView,
   AdminPurgePostView,
+  AdminRotateFederationKeyView,
   ModAddCommunityView,
   ModAddView,
   ModBanFromCommunityView,
@@ -24,7 +30,7 @@ use lemmy_db_views_moderator::structs::{
   ModTransferCommunityView,
   ModlogListParams,
 };
-use lemmy_utils::error::LemmyResult;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
 use ModlogActionType::*;
 
 #[tracing::instrument(skip(context))]
@@ -32,21 +38,53 @@ pub async fn get_mod_log(
   data: Query<GetModlog>,
   context: Data<LemmyContext>,
   local_user_view: Option<LocalUserView>,
-) -> LemmyResult<Json<GetModlogResponse>> {
-  let local_site = LocalSite::read(&mut context.pool()).await?;
+) -> LemmyResult<HttpResponse> {
+  let site_view = SiteView::read_local(&mut context.pool())
+    .await?
+    .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
+  let local_site = &site_view.local_site;
 
-  check_private_instance(&local_user_view, &local_site)?;
+  check_private_instance(&local_user_view, local_site)?;
 
   let type_ = data.type_.unwrap_or(All);
+  // Each modlog table has its own id sequence, so a single cursor can only page through one type
+  if data.page_after.is_some() && type_ == All {
+    Err(LemmyErrorType::ModlogCursorRequiresType)?
+  }
   let community_id = data.community_id;
 
-  let is_mod_or_admin = if let Some(local_user_view) = local_user_view {
-    check_community_mod_of_any_or_admin_action(&local_user_view, &mut context.pool())
-      .await
-      .is_ok()
-  } else {
-    false
+  // Access to the modlog:
+  //
+  // | Caller                       | Without community           | With community_id            |
+  // |------------------------------|-----------------------------|------------------------------|
+  // | Anonymous                    | Public communities only     | Rejected                     |
+  // | Logged in user               | Everything                  | Entries about themselves     |
+  // | Moderator of the community   | Everything                  | Everything                   |
+  // | Admin                        | Everything                  | Everything                   |
+  //
+  // Entries about the caller, eg bans, are never hidden from them. Users who don't moderate the
+  // community can read those by setting other_person_id to their own id.
+  //
+  // If the site hides moderator names, they are only shown to admins, and to moderators of the
+  // requested community. Without a community, moderators of any community can see them.
+  let my_person_id = local_user_view.as_ref().map(|l| l.person.id);
+  let is_mod_or_admin = match (&local_user_view, community_id) {
+    (Some(local_user_view), Some(community_id)) => {
+      is_mod_or_admin(&mut context.pool(), &local_user_view.person, community_id)
+        .await
+        .is_ok()
+    }
+    (Some(local_user_view), None) => {
+      check_community_mod_of_any_or_admin_action(local_user_view, &mut context.pool())
+        .await
+        .is_ok()
+    }
+    (None, _) => false,
   };
+  let about_myself = my_person_id.is_some() && data.other_person_id == my_person_id;
+  if community_id.is_some() && !is_mod_or_admin && !about_myself {
+    Err(LemmyErrorType::NotAModOrAdmin)?
+  }
   let hide_modlog_names = local_site.hide_modlog_mod_names && !is_mod_or_admin;
 
   let mod_person_id = if hide_modlog_names {
@@ -58,6 +96,8 @@ pub async fn get_mod_log(
   let post_id = data.post_id;
   let comment_id = data.comment_id;
 
+  let ascending = data.order.unwrap_or_default() == ModlogOrder::Asc;
+
   let params = ModlogListParams {
     community_id,
     mod_person_id,
@@ -66,7 +106,12 @@ pub async fn get_mod_log(
     comment_id,
     page: data.page,
     limit: data.limit,
+    page_after: data.page_after,
+    ascending,
+    since: data.since,
+    id: None,
     hide_modlog_names,
+    hide_local_only_communities: local_user_view.is_none(),
   };
   let removed_posts = match type_ {
     All | ModRemovePost => ModRemovePostView::list(&mut context.pool(), params).await?,
@@ -121,6 +166,8 @@ pub async fn get_mod_log(
     admin_purged_communities,
     admin_purged_posts,
     admin_purged_comments,
+    admin_rotated_federation_keys,
+    admin_exported_user_data,
   ) = if data.community_id.is_none() {
     (
       match type_ {
@@ -161,13 +208,78 @@ pub async fn get_mod_log(
         }
         _ => Default::default(),
       },
+      match type_ {
+        All | AdminRotateFederationKey if other_person_id.is_none() => {
+          AdminRotateFederationKeyView::list(&mut context.pool(), params).await?
+        }
+        _ => Default::default(),
+      },
+      match type_ {
+        All | AdminExportUserData => {
+          AdminExportUserDataView::list(&mut context.pool(), params).await?
+        }
+        _ => Default::default(),
+      },
     )
   } else {
     Default::default()
   };
 
-  // Return the jwt
-  Ok(Json(GetModlogResponse {
+  let ids = removed_posts
+    .iter()
+    .map(|v| v.mod_remove_post.id)
+    .chain(locked_posts.iter().map(|v| v.mod_lock_post.id))
+    .chain(featured_posts.iter().map(|v| v.mod_feature_post.id))
+    .chain(removed_comments.iter().map(|v| v.mod_remove_comment.id))
+    .chain(
+      removed_communities
+        .iter()
+        .map(|v| v.mod_remove_community.id),
+    )
+    .chain(
+      banned_from_community
+        .iter()
+        .map(|v| v.mod_ban_from_community.id),
+    )
+    .chain(banned.iter().map(|v| v.mod_ban.id))
+    .chain(added_to_community.iter().map(|v| v.mod_add_community.id))
+    .chain(
+      transferred_to_community
+        .iter()
+        .map(|v| v.mod_transfer_community.id),
+    )
+    .chain(added.iter().map(|v| v.mod_add.id))
+    .chain(admin_purged_persons.iter().map(|v| v.admin_purge_person.id))
+    .chain(
+      admin_purged_communities
+        .iter()
+        .map(|v| v.admin_purge_community.id),
+    )
+    .chain(admin_purged_posts.iter().map(|v| v.admin_purge_post.id))
+    .chain(
+      admin_purged_comments
+        .iter()
+        .map(|v| v.admin_purge_comment.id),
+    )
+    .chain(
+      admin_rotated_federation_keys
+        .iter()
+        .map(|v| v.admin_rotate_federation_key.id),
+    )
+    .chain(
+      admin_exported_user_data
+        .iter()
+        .map(|v| v.admin_export_user_data.id),
+    )
+    .chain(hidden_communities.iter().map(|v| v.mod_hide_community.id));
+  let next_page = match type_ {
+    // The ids of different types can't be combined into a single cursor
+    All => None,
+    _ if ascending => ids.max(),
+    _ => ids.min(),
+  };
+
+  let res = GetModlogResponse {
     removed_posts,
     locked_posts,
     featured_posts,
@@ -182,6 +294,130 @@ pub async fn get_mod_log(
     admin_purged_communities,
     admin_purged_posts,
     admin_purged_comments,
+    admin_rotated_federation_keys,
+    admin_exported_user_data,
     hidden_communities,
-  }))
+    next_page,
+  };
+  match data.format.unwrap_or_default() {
+    ModlogFormat::Json => Ok(HttpResponse::Ok().json(res)),
+    ModlogFormat::Rss => {
+      let protocol_and_hostname = context.settings().get_protocol_and_hostname();
+      let channel = modlog_channel(&res, &site_view.site, &protocol_and_hostname);
+      Ok(
+        HttpResponse::Ok()
+          .content_type("application/rss+xml")
+          .body(channel.to_string()),
+      )
+    }
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+
+  use super::get_mod_log;
+  use actix_web::{
+    body::to_bytes,
+    http::header::CONTENT_TYPE,
+    web::{Data, Query},
+  };
+  use lemmy_api_common::{context::LemmyContext, site::GetModlog};
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      local_site::{LocalSite, LocalSiteInsertForm},
+      local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitInsertForm},
+      moderator::{ModRemovePost, ModRemovePostForm},
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      site::{Site, SiteInsertForm},
+    },
+    traits::Crud,
+    ModlogFormat,
+  };
+  use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_modlog_rss() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(instance.id)
+      .build();
+    let site = Site::create(pool, &site_form).await?;
+    let local_site_form = LocalSiteInsertForm::builder().site_id(site.id).build();
+    let local_site = LocalSite::create(pool, &local_site_form).await?;
+    let rate_limit_form = LocalSiteRateLimitInsertForm::builder()
+      .local_site_id(local_site.id)
+      .build();
+    LocalSiteRateLimit::create(pool, &rate_limit_form).await?;
+
+    let moderator = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "modlog_rss_mod"),
+    )
+    .await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("modlog_rss".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("spam post".into())
+      .creator_id(moderator.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+    let remove_form = ModRemovePostForm {
+      mod_person_id: moderator.id,
+      post_id: post.id,
+      reason: Some("spam".to_string()),
+      removed: Some(true),
+    };
+    ModRemovePost::create(pool, &remove_form).await?;
+
+    let context = Data::new((*context).clone());
+    let rss_query = |community_id| {
+      Query(GetModlog {
+        community_id,
+        format: Some(ModlogFormat::Rss),
+        ..Default::default()
+      })
+    };
+    let res = get_mod_log(rss_query(None), context.clone(), None).await?;
+    assert_eq!(
+      Some("application/rss+xml"),
+      res
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+    );
+    let body = to_bytes(res.into_body()).await.unwrap();
+    let body = String::from_utf8(body.to_vec())?;
+    assert!(body.contains("<title>test site - Modlog</title>"));
+    assert!(body.contains("<title>Removed post spam post</title>"));
+    assert!(body.contains("reason: spam"));
+
+    // The feed has the same permission checks as the json modlog
+    let res = get_mod_log(rss_query(Some(community.id)), context.clone(), None).await;
+    assert_eq!(
+      Some(LemmyErrorType::NotAModOrAdmin),
+      res.err().map(|e| e.error_type)
+    );
+
+    Site::delete(pool, site.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
 }
@@ -0,0 +1,105 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{GetModlogEntry, GetModlogEntryResponse, ModlogEntry},
+  utils::{check_community_mod_of_any_or_admin_action, check_private_instance},
+};
+use lemmy_db_schema::{source::local_site::LocalSite, ModlogActionType};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_moderator::structs::{
+  AdminExportUserDataView,
+  AdminPurgeCommentView,
+  AdminPurgeCommunityView,
+  AdminPurgePersonView,
+  AdminPurgePostView,
+  AdminRotateFederationKeyView,
+  ModAddCommunityView,
+  ModAddView,
+  ModBanFromCommunityView,
+  ModBanView,
+  ModFeaturePostView,
+  ModHideCommunityView,
+  ModLockPostView,
+  ModRemoveCommentView,
+  ModRemoveCommunityView,
+  ModRemovePostView,
+  ModTransferCommunityView,
+  ModlogListParams,
+};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use ModlogActionType::*;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_mod_log_entry(
+  data: Query<GetModlogEntry>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<GetModlogEntryResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  check_private_instance(&local_user_view, &local_site)?;
+
+  // The community of the entry isn't known before reading it, so moderator names are shown under
+  // the same conditions as in the modlog without a community filter.
+  let is_mod_or_admin = match &local_user_view {
+    Some(local_user_view) => {
+      check_community_mod_of_any_or_admin_action(local_user_view, &mut context.pool())
+        .await
+        .is_ok()
+    }
+    None => false,
+  };
+
+  let params = ModlogListParams {
+    community_id: None,
+    mod_person_id: None,
+    other_person_id: None,
+    post_id: None,
+    comment_id: None,
+    page: None,
+    limit: Some(1),
+    page_after: None,
+    ascending: false,
+    since: None,
+    id: Some(data.id),
+    hide_modlog_names: local_site.hide_modlog_mod_names && !is_mod_or_admin,
+    hide_local_only_communities: local_user_view.is_none(),
+  };
+  let pool = &mut context.pool();
+  // Reads the entry with the view of the requested type. `into()` boxes the large views.
+  macro_rules! read_entry {
+    ($($action_type:ident => $view:ident,)*) => {
+      match data.action_type {
+        $($action_type => $view::list(pool, params)
+          .await?
+          .into_iter()
+          .next()
+          .map(|view| ModlogEntry::$action_type(view.into())),)*
+        // Ids of different entry types overlap
+        All => None,
+      }
+    };
+  }
+  let entry = read_entry! {
+    ModRemovePost => ModRemovePostView,
+    ModLockPost => ModLockPostView,
+    ModFeaturePost => ModFeaturePostView,
+    ModRemoveComment => ModRemoveCommentView,
+    ModRemoveCommunity => ModRemoveCommunityView,
+    ModBanFromCommunity => ModBanFromCommunityView,
+    ModAddCommunity => ModAddCommunityView,
+    ModTransferCommunity => ModTransferCommunityView,
+    ModAdd => ModAddView,
+    ModBan => ModBanView,
+    ModHideCommunity => ModHideCommunityView,
+    AdminPurgePerson => AdminPurgePersonView,
+    AdminPurgeCommunity => AdminPurgeCommunityView,
+    AdminPurgePost => AdminPurgePostView,
+    AdminPurgeComment => AdminPurgeCommentView,
+    AdminRotateFederationKey => AdminRotateFederationKeyView,
+    AdminExportUserData => AdminExportUserDataView,
+  }
+  .ok_or(LemmyErrorType::CouldntFindModlogEntry)?;
+
+  Ok(Json(GetModlogEntryResponse { entry }))
+}
@@ -0,0 +1,182 @@
+use chrono::{DateTime, Utc};
+use lemmy_api_common::site::GetModlogResponse;
+use lemmy_db_schema::source::{person::Person, site::Site};
+use lemmy_db_views_moderator::structs::{
+  ModBanFromCommunityView,
+  ModBanView,
+  ModLockPostView,
+  ModRemoveCommentView,
+  ModRemoveCommunityView,
+  ModRemovePostView,
+};
+use lemmy_utils::utils::markdown::{sanitize_html, sanitize_xml};
+use rss::{Channel, Guid, Item};
+
+/// Renders the modlog entries as an RSS feed, newest first. Only the entry types which are
+/// interesting for the public are included.
+pub(crate) fn modlog_channel(
+  res: &GetModlogResponse,
+  site: &Site,
+  protocol_and_hostname: &str,
+) -> Channel {
+  let host = protocol_and_hostname;
+  let mut items: Vec<ModlogItem> = vec![];
+  items.extend(
+    res
+      .removed_posts
+      .iter()
+      .map(|v| mod_remove_post_item(v, host)),
+  );
+  items.extend(res.locked_posts.iter().map(|v| mod_lock_post_item(v, host)));
+  items.extend(
+    res
+      .removed_comments
+      .iter()
+      .map(|v| mod_remove_comment_item(v, host)),
+  );
+  items.extend(
+    res
+      .banned_from_community
+      .iter()
+      .map(mod_ban_from_community_item),
+  );
+  items.extend(res.banned.iter().map(mod_ban_item));
+  items.extend(
+    res
+      .removed_communities
+      .iter()
+      .map(mod_remove_community_item),
+  );
+
+  // Merge the different action types into a single timeline
+  items.sort_by(|a, b| b.0.cmp(&a.0));
+
+  Channel {
+    title: format!("{} - Modlog", sanitize_xml(site.name.clone())),
+    link: format!("{protocol_and_hostname}/modlog"),
+    items: items.into_iter().map(|(_, i)| i).collect(),
+    ..Default::default()
+  }
+}
+
+/// A modlog feed item, together with the time of the action so that the types can be merged.
+type ModlogItem = (DateTime<Utc>, Item);
+
+fn mod_remove_post_item(v: &ModRemovePostView, protocol_and_hostname: &str) -> ModlogItem {
+  let action = if v.mod_remove_post.removed {
+    "Removed post"
+  } else {
+    "Restored post"
+  };
+  build_modlog_item(
+    format!("{action} {}", v.post.name),
+    v.moderator.as_ref(),
+    v.mod_remove_post.when_,
+    v.mod_remove_post.reason.as_deref(),
+    format!("{protocol_and_hostname}/post/{}", v.post.id),
+  )
+}
+
+fn mod_lock_post_item(v: &ModLockPostView, protocol_and_hostname: &str) -> ModlogItem {
+  let action = if v.mod_lock_post.locked {
+    "Locked post"
+  } else {
+    "Unlocked post"
+  };
+  build_modlog_item(
+    format!("{action} {}", v.post.name),
+    v.moderator.as_ref(),
+    v.mod_lock_post.when_,
+    None,
+    format!("{protocol_and_hostname}/post/{}", v.post.id),
+  )
+}
+
+fn mod_remove_comment_item(v: &ModRemoveCommentView, protocol_and_hostname: &str) -> ModlogItem {
+  let action = if v.mod_remove_comment.removed {
+    "Removed comment"
+  } else {
+    "Restored comment"
+  };
+  build_modlog_item(
+    format!("{action} by {} in {}", v.commenter.name, v.post.name),
+    v.moderator.as_ref(),
+    v.mod_remove_comment.when_,
+    v.mod_remove_comment.reason.as_deref(),
+    format!("{protocol_and_hostname}/comment/{}", v.comment.id),
+  )
+}
+
+fn mod_ban_from_community_item(v: &ModBanFromCommunityView) -> ModlogItem {
+  let action = if v.mod_ban_from_community.banned {
+    "Banned"
+  } else {
+    "Unbanned"
+  };
+  build_modlog_item(
+    format!(
+      "{action} {} from {}",
+      v.banned_person.name, v.community.name
+    ),
+    v.moderator.as_ref(),
+    v.mod_ban_from_community.when_,
+    v.mod_ban_from_community.reason.as_deref(),
+    v.banned_person.actor_id.to_string(),
+  )
+}
+
+fn mod_ban_item(v: &ModBanView) -> ModlogItem {
+  let action = if v.mod_ban.banned {
+    "Banned"
+  } else {
+    "Unbanned"
+  };
+  build_modlog_item(
+    format!("{action} {} from the site", v.banned_person.name),
+    v.moderator.as_ref(),
+    v.mod_ban.when_,
+    v.mod_ban.reason.as_deref(),
+    v.banned_person.actor_id.to_string(),
+  )
+}
+
+fn mod_remove_community_item(v: &ModRemoveCommunityView) -> ModlogItem {
+  let action = if v.mod_remove_community.removed {
+    "Removed community"
+  } else {
+    "Restored community"
+  };
+  build_modlog_item(
+    format!("{action} {}", v.community.name),
+    v.moderator.as_ref(),
+    v.mod_remove_community.when_,
+    v.mod_remove_community.reason.as_deref(),
+    v.community.actor_id.to_string(),
+  )
+}
+
+fn build_modlog_item(
+  title: String,
+  moderator: Option<&Person>,
+  when: DateTime<Utc>,
+  reason: Option<&str>,
+  link: String,
+) -> ModlogItem {
+  let mod_name = moderator.map_or("a moderator".to_string(), |m| sanitize_html(&m.name));
+  let mut description = format!("by {mod_name}");
+  if let Some(reason) = reason {
+    description.push_str(&format!("<br>reason: {}", sanitize_html(reason)));
+  }
+  let item = Item {
+    title: Some(sanitize_html(&sanitize_xml(title))),
+    pub_date: Some(when.to_rfc2822()),
+    guid: Some(Guid {
+      permalink: false,
+      value: format!("{link}#{}", when.timestamp_millis()),
+    }),
+    description: Some(sanitize_xml(description)),
+    link: Some(link),
+    ..Default::default()
+  };
+  (when, item)
+}
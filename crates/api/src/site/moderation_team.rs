@@ -0,0 +1,30 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{GetModerationTeam, GetModerationTeamResponse},
+  utils::check_private_instance,
+};
+use lemmy_db_schema::source::local_site::LocalSite;
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_db_views_actor::structs::{CommunityModeratorView, PersonView};
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_moderation_team(
+  data: Query<GetModerationTeam>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<GetModerationTeamResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let admins = PersonView::admins(&mut context.pool()).await?;
+  let community_moderators =
+    CommunityModeratorView::for_local_communities(&mut context.pool(), data.page, data.limit)
+      .await?;
+
+  Ok(Json(GetModerationTeamResponse {
+    admins,
+    community_moderators,
+  }))
+}
@@ -2,3 +2,4 @@ pub mod comment;
 pub mod community;
 pub mod person;
 pub mod post;
+pub mod search;
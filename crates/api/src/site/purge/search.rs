@@ -0,0 +1,351 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  site::{PurgeBySearch, PurgeBySearchResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::{
+  source::{
+    comment::{Comment, CommentUpdateForm},
+    comment_report::CommentReport,
+    moderator::{ModRemoveComment, ModRemoveCommentForm, ModRemovePost, ModRemovePostForm},
+    post::{Post, PostUpdateForm},
+    post_report::PostReport,
+  },
+  traits::{Crud, Reportable},
+  utils::{get_conn, FETCH_LIMIT_MAX},
+  SearchType,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult};
+
+/// Shorter search terms would match nearly everything.
+const PURGE_SEARCH_TERM_MIN_LENGTH: usize = 3;
+
+#[tracing::instrument(skip(context))]
+pub async fn purge_by_search(
+  data: Json<PurgeBySearch>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<PurgeBySearchResponse>> {
+  // Only let admin purge items
+  is_admin(&local_user_view)?;
+
+  if !data.confirm {
+    Err(LemmyErrorType::PurgeNotConfirmed)?
+  }
+  let search_term = data.q.trim();
+  if search_term.chars().count() < PURGE_SEARCH_TERM_MIN_LENGTH {
+    Err(LemmyErrorType::SearchQueryTooShort)?
+  }
+  let search_type = data.type_.unwrap_or(SearchType::All);
+  if !matches!(
+    search_type,
+    SearchType::All | SearchType::Posts | SearchType::Comments
+  ) {
+    Err(LemmyErrorType::InvalidSearchType)?
+  }
+
+  let moderator = local_user_view.person;
+  let reason = data.reason.clone();
+  let search_term = search_term.to_string();
+
+  let pool = &mut context.pool();
+  let conn = &mut get_conn(pool).await?;
+  let (res, activities) = conn
+    .build_transaction()
+    .run(|conn| {
+      Box::pin(async move {
+        let pool = &mut conn.into();
+        let mut res = PurgeBySearchResponse::default();
+        let mut activities = vec![];
+
+        // Search without any filters for the viewer, so that private and deleted communities,
+        // blocks or nsfw settings don't leave any items out. Removed items are left out of the
+        // search results, so keep reading the first page until nothing matches anymore.
+        if search_type != SearchType::Comments {
+          loop {
+            let posts = Post::list_unremoved_matching(pool, &search_term, FETCH_LIMIT_MAX).await?;
+            if posts.is_empty() {
+              break;
+            }
+
+            for post in posts {
+              let post_id = post.id;
+              let post = Post::update(
+                pool,
+                post_id,
+                &PostUpdateForm {
+                  removed: Some(true),
+                  ..Default::default()
+                },
+              )
+              .await?;
+              PostReport::resolve_all_for_object(pool, post_id, moderator.id).await?;
+
+              let form = ModRemovePostForm {
+                mod_person_id: moderator.id,
+                post_id,
+                removed: Some(true),
+                reason: reason.clone(),
+              };
+              ModRemovePost::create(pool, &form).await?;
+
+              activities.push(SendActivityData::RemovePost {
+                post,
+                moderator: moderator.clone(),
+                reason: reason.clone(),
+                removed: true,
+              });
+              res.removed_posts += 1;
+            }
+          }
+        }
+
+        if search_type != SearchType::Posts {
+          loop {
+            let comments =
+              Comment::list_unremoved_matching(pool, &search_term, FETCH_LIMIT_MAX).await?;
+            if comments.is_empty() {
+              break;
+            }
+
+            for (comment, community) in comments {
+              let comment_id = comment.id;
+              let comment = Comment::update(
+                pool,
+                comment_id,
+                &CommentUpdateForm {
+                  removed: Some(true),
+                  ..Default::default()
+                },
+              )
+              .await
+              .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
+              CommentReport::resolve_all_for_object(pool, comment_id, moderator.id).await?;
+
+              let form = ModRemoveCommentForm {
+                mod_person_id: moderator.id,
+                comment_id,
+                removed: Some(true),
+                reason: reason.clone(),
+              };
+              ModRemoveComment::create(pool, &form).await?;
+
+              activities.push(SendActivityData::RemoveComment {
+                comment,
+                moderator: moderator.clone(),
+                community,
+                reason: reason.clone(),
+              });
+              res.removed_comments += 1;
+            }
+          }
+        }
+
+        Ok::<_, LemmyError>((res, activities))
+      }) as _
+    })
+    .await?;
+
+  // Only federate the removals once all of them are saved
+  for activity in activities {
+    ActivityChannel::submit_activity(activity, &context).await?;
+  }
+
+  Ok(Json(res))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+
+  use super::purge_by_search;
+  use actix_web::web::Json;
+  use lemmy_api_common::{
+    context::LemmyContext,
+    site::{PurgeBySearch, PurgeBySearchResponse},
+  };
+  use lemmy_db_schema::{
+    source::{
+      comment::{Comment, CommentInsertForm},
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      local_site::{LocalSite, LocalSiteInsertForm},
+      local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitInsertForm},
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      post::{Post, PostHide, PostInsertForm},
+      site::{Site, SiteInsertForm},
+    },
+    traits::Crud,
+    CommunityVisibility,
+  };
+  use lemmy_db_views::structs::LocalUserView;
+  use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use std::collections::HashSet;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_purge_by_search() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(instance.id)
+      .build();
+    let site = Site::create(pool, &site_form).await?;
+    let local_site_form = LocalSiteInsertForm::builder().site_id(site.id).build();
+    let local_site = LocalSite::create(pool, &local_site_form).await?;
+    let rate_limit_form = LocalSiteRateLimitInsertForm::builder()
+      .local_site_id(local_site.id)
+      .build();
+    LocalSiteRateLimit::create(pool, &rate_limit_form).await?;
+
+    let admin = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "purge_admin"),
+    )
+    .await?;
+    let admin_local_user_form = LocalUserInsertForm::builder()
+      .person_id(admin.id)
+      .admin(Some(true))
+      .password_encrypted(String::new())
+      .build();
+    let admin_local_user = LocalUser::create(pool, &admin_local_user_form, vec![]).await?;
+    let admin_view = LocalUserView::read(pool, admin_local_user.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindLocalUser)?;
+    let spammer =
+      Person::create(pool, &PersonInsertForm::test_form(instance.id, "spammer")).await?;
+
+    let community_form = CommunityInsertForm::builder()
+      .name("purge_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let local_only_community_form = CommunityInsertForm::builder()
+      .name("purge_local_only_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .visibility(Some(CommunityVisibility::LocalOnly))
+      .build();
+    let local_only_community = Community::create(pool, &local_only_community_form).await?;
+
+    let post_form = |name: &str, nsfw: bool, community_id| {
+      PostInsertForm::builder()
+        .name(name.to_string())
+        .creator_id(spammer.id)
+        .community_id(community_id)
+        .nsfw(Some(nsfw))
+        .build()
+    };
+    let spam_post = Post::create(pool, &post_form("buy spam.example", false, community.id)).await?;
+    let nsfw_spam_post =
+      Post::create(pool, &post_form("nsfw spam.example", true, community.id)).await?;
+    let hidden_spam_post =
+      Post::create(pool, &post_form("hidden spam.example", false, community.id)).await?;
+    let local_only_spam_post = Post::create(
+      pool,
+      &post_form("local spam.example", false, local_only_community.id),
+    )
+    .await?;
+    let other_post = Post::create(pool, &post_form("a regular post", false, community.id)).await?;
+    let local_only_post = Post::create(
+      pool,
+      &post_form("a local post", false, local_only_community.id),
+    )
+    .await?;
+    // The admin doesn't show nsfw content and has hidden one of the posts, neither of which
+    // must keep them from being removed
+    PostHide::hide(pool, HashSet::from([hidden_spam_post.id]), admin.id).await?;
+
+    let comment_form = |content: &str, post_id| {
+      CommentInsertForm::builder()
+        .creator_id(spammer.id)
+        .post_id(post_id)
+        .content(content.to_string())
+        .build()
+    };
+    let spam_comment = Comment::create(
+      pool,
+      &comment_form("visit spam.example", other_post.id),
+      None,
+    )
+    .await?;
+    let local_only_spam_comment = Comment::create(
+      pool,
+      &comment_form("visit local spam.example", local_only_post.id),
+      None,
+    )
+    .await?;
+    let other_comment = Comment::create(
+      pool,
+      &comment_form("a regular comment", other_post.id),
+      None,
+    )
+    .await?;
+
+    let purge = |confirm: bool| {
+      Json(PurgeBySearch {
+        q: "spam.example".to_string(),
+        type_: None,
+        reason: Some("spam wave".to_string()),
+        confirm,
+      })
+    };
+    let unconfirmed = purge_by_search(
+      purge(false),
+      context.reset_request_count(),
+      admin_view.clone(),
+    )
+    .await;
+    assert_eq!(
+      LemmyErrorType::PurgeNotConfirmed,
+      unconfirmed.err().unwrap().error_type
+    );
+
+    let res = purge_by_search(purge(true), context.reset_request_count(), admin_view).await?;
+    assert_eq!(
+      PurgeBySearchResponse {
+        removed_posts: 4,
+        removed_comments: 2,
+      },
+      res.0
+    );
+
+    for post in [
+      &spam_post,
+      &nsfw_spam_post,
+      &hidden_spam_post,
+      &local_only_spam_post,
+    ] {
+      assert!(Post::read(pool, post.id).await?.unwrap().removed);
+    }
+    assert!(!Post::read(pool, other_post.id).await?.unwrap().removed);
+    for comment in [&spam_comment, &local_only_spam_comment] {
+      assert!(Comment::read(pool, comment.id).await?.unwrap().removed);
+    }
+    assert!(
+      !Comment::read(pool, other_comment.id)
+        .await?
+        .unwrap()
+        .removed
+    );
+
+    Community::delete(pool, community.id).await?;
+    Community::delete(pool, local_only_community.id).await?;
+    Site::delete(pool, site.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
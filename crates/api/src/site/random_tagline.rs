@@ -0,0 +1,17 @@
+use actix_web::web::{Data, Json};
+use lemmy_api_common::{context::LemmyContext, site::GetRandomTaglineResponse};
+use lemmy_db_schema::source::tagline::Tagline;
+use lemmy_db_views::structs::SiteView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn get_random_tagline(
+  context: Data<LemmyContext>,
+) -> LemmyResult<Json<GetRandomTaglineResponse>> {
+  let site_view = SiteView::read_local(&mut context.pool())
+    .await?
+    .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
+  let tagline = Tagline::get_random(&mut context.pool(), site_view.local_site.id).await?;
+
+  Ok(Json(GetRandomTaglineResponse { tagline }))
+}
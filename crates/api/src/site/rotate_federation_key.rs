@@ -0,0 +1,56 @@
+use activitypub_federation::{config::Data, http_signatures::generate_actor_keypair};
+use actix_web::web::Json;
+use lemmy_api_common::{
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::is_admin,
+  SuccessResponse,
+};
+use lemmy_db_schema::{
+  source::{
+    moderator::{AdminRotateFederationKey, AdminRotateFederationKeyForm},
+    site::{Site, SiteUpdateForm},
+  },
+  traits::Crud,
+  utils::naive_now,
+};
+use lemmy_db_views::structs::{LocalUserView, SiteView};
+use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+
+/// Replaces the keypair of the instance actor, eg if the private key was leaked.
+///
+/// The updated instance actor is sent to all known instances. It is signed with the new key, so
+/// they refetch the actor to get the new public key once the signature check with the old one
+/// fails.
+/// Other Lemmy instances keep accepting the old key for a short time, for activities which were
+/// signed before the rotation. Fetches are signed with the new key once the cached site expires.
+#[tracing::instrument(skip(context))]
+pub async fn rotate_federation_key(
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  is_admin(&local_user_view)?;
+
+  let site_view = SiteView::read_local(&mut context.pool())
+    .await?
+    .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
+
+  let keypair = generate_actor_keypair()?;
+  let form = SiteUpdateForm {
+    private_key: Some(Some(keypair.private_key)),
+    public_key: Some(keypair.public_key),
+    updated: Some(Some(naive_now())),
+    ..Default::default()
+  };
+  let site = Site::update(&mut context.pool(), site_view.site.id, &form).await?;
+
+  // Mod tables
+  let form = AdminRotateFederationKeyForm {
+    admin_person_id: local_user_view.person.id,
+  };
+  AdminRotateFederationKey::create(&mut context.pool(), &form).await?;
+
+  ActivityChannel::submit_activity(SendActivityData::UpdateInstance(site), &context).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
@@ -0,0 +1,26 @@
+use actix_web::web::{Json, Query};
+use lemmy_api_common::{
+  site::{GetSlowQueries, GetSlowQueriesResponse},
+  slow_query_log::recent_slow_queries,
+  utils::is_admin,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+const DEFAULT_SLOW_QUERIES_LIMIT: usize = 20;
+
+#[tracing::instrument]
+pub async fn get_slow_queries(
+  data: Query<GetSlowQueries>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetSlowQueriesResponse>> {
+  // Only let admins see request timings
+  is_admin(&local_user_view)?;
+
+  let limit = data
+    .limit
+    .and_then(|l| usize::try_from(l).ok())
+    .unwrap_or(DEFAULT_SLOW_QUERIES_LIMIT);
+  let slow_queries = recent_slow_queries(limit);
+  Ok(Json(GetSlowQueriesResponse { slow_queries }))
+}
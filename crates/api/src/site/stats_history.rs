@@ -0,0 +1,90 @@
+use actix_web::web::{Data, Json, Query};
+use chrono::{Datelike, TimeDelta, Utc};
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{GetSiteStatsHistory, GetSiteStatsHistoryResponse},
+  utils::is_admin,
+};
+use lemmy_db_schema::{source::site_stats_history::SiteStatsHistory, StatsGranularity};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+const DEFAULT_STATS_HISTORY_DAYS: i64 = 30;
+
+/// Limits the number of rows which are read for a single request.
+const MAX_STATS_HISTORY_DAYS: i64 = 366;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_site_stats_history(
+  data: Query<GetSiteStatsHistory>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<GetSiteStatsHistoryResponse>> {
+  is_admin(&local_user_view)?;
+
+  let to = data.to.unwrap_or_else(Utc::now);
+  let from = data.from.unwrap_or(
+    to - TimeDelta::try_days(DEFAULT_STATS_HISTORY_DAYS).expect("TimeDelta out of bounds"),
+  );
+  if from > to
+    || to - from > TimeDelta::try_days(MAX_STATS_HISTORY_DAYS).expect("TimeDelta out of bounds")
+  {
+    Err(LemmyErrorType::InvalidTimeRange)?
+  }
+
+  let days = SiteStatsHistory::list(&mut context.pool(), from, to).await?;
+  let history = match data.granularity.unwrap_or_default() {
+    StatsGranularity::Day => days,
+    StatsGranularity::Week => group_by_week(days),
+  };
+
+  Ok(Json(GetSiteStatsHistoryResponse { history }))
+}
+
+/// Sums up daily counts into weeks starting on Monday. Expects the days to be sorted.
+fn group_by_week(days: Vec<SiteStatsHistory>) -> Vec<SiteStatsHistory> {
+  let mut weeks: Vec<SiteStatsHistory> = vec![];
+  for day in days {
+    let days_since_monday = i64::from(day.day.weekday().num_days_from_monday());
+    let week_start =
+      day.day - TimeDelta::try_days(days_since_monday).expect("TimeDelta out of bounds");
+    match weeks.last_mut() {
+      Some(week) if week.day == week_start => {
+        week.users += day.users;
+        week.posts += day.posts;
+        week.comments += day.comments;
+      }
+      _ => weeks.push(SiteStatsHistory {
+        day: week_start,
+        ..day
+      }),
+    }
+  }
+  weeks
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+
+  use super::group_by_week;
+  use chrono::{TimeZone, Utc};
+  use lemmy_db_schema::source::site_stats_history::SiteStatsHistory;
+  use pretty_assertions::assert_eq;
+
+  fn stats(day: u32, users: i64, posts: i64) -> SiteStatsHistory {
+    SiteStatsHistory {
+      day: Utc.with_ymd_and_hms(2024, 4, day, 0, 0, 0).unwrap(),
+      users,
+      posts,
+      comments: 0,
+    }
+  }
+
+  #[test]
+  fn test_group_by_week() {
+    // 2024-04-01 and 2024-04-08 are Mondays
+    let weeks = group_by_week(vec![stats(2, 1, 1), stats(7, 1, 2), stats(8, 1, 3)]);
+    assert_eq!(vec![stats(1, 2, 3), stats(8, 1, 3)], weeks);
+  }
+}
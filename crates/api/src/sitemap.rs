@@ -1,17 +1,38 @@
 use actix_web::{
   http::header::{self, CacheDirective},
-  web::Data,
+  web::{Data, Query},
   HttpResponse,
 };
+use chrono::{DateTime, Utc};
 use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::{newtypes::DbUrl, source::post::Post};
-use lemmy_utils::error::LemmyResult;
-use sitemap_rs::{url::Url, url_set::UrlSet};
+use lemmy_db_schema::{
+  newtypes::DbUrl,
+  source::{community::Community, post::Post},
+};
+use lemmy_utils::error::{LemmyError, LemmyResult};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use serde::Deserialize;
+use sitemap_rs::{sitemap::Sitemap, sitemap_index::SitemapIndex, url::Url, url_set::UrlSet};
+use std::{sync::Arc, time::Duration};
 use tracing::info;
 
-async fn generate_urlset(
-  posts: Vec<(DbUrl, chrono::DateTime<chrono::Utc>)>,
-) -> LemmyResult<UrlSet> {
+/// Maximum number of urls in a single sitemap file, as defined by the sitemap protocol.
+const SITEMAP_PAGE_SIZE: usize = 50_000;
+
+/// The sitemap changes only gradually, so it is generated at most once per hour.
+const SITEMAP_CACHE_DURATION: Duration = Duration::from_secs(3_600);
+
+#[derive(Deserialize, Debug)]
+pub struct SitemapQuery {
+  /// Page of the sitemap, starting at 1. If omitted, a sitemap index is returned when the urls
+  /// don't fit into a single file.
+  page: Option<usize>,
+}
+
+type SitemapEntries = Arc<Vec<(DbUrl, DateTime<Utc>)>>;
+
+async fn generate_urlset(posts: Vec<(DbUrl, DateTime<Utc>)>) -> LemmyResult<UrlSet> {
   let urls = posts
     .into_iter()
     .map_while(|post| {
@@ -25,13 +46,54 @@ async fn generate_urlset(
   Ok(UrlSet::new(urls)?)
 }
 
-pub async fn get_sitemap(context: Data<LemmyContext>) -> LemmyResult<HttpResponse> {
+fn generate_sitemap_index(base_url: &str, page_count: usize) -> LemmyResult<SitemapIndex> {
+  let sitemaps = (1..=page_count)
+    .map(|page| Sitemap::new(format!("{base_url}/sitemap.xml?page={page}"), None))
+    .collect();
+
+  Ok(SitemapIndex::new(sitemaps)?)
+}
+
+/// Loads urls of public local communities and recent posts, communities first.
+async fn load_entries(context: &LemmyContext) -> LemmyResult<SitemapEntries> {
   info!("Generating sitemap...",);
+  let mut entries = Community::list_for_sitemap(&mut context.pool()).await?;
+  info!("Loaded {} communities", entries.len());
   let posts = Post::list_for_sitemap(&mut context.pool()).await?;
   info!("Loaded latest {} posts", posts.len());
+  entries.extend(posts);
+  Ok(Arc::new(entries))
+}
+
+pub async fn get_sitemap(
+  query: Query<SitemapQuery>,
+  context: Data<LemmyContext>,
+) -> LemmyResult<HttpResponse> {
+  static CACHE: Lazy<Cache<(), SitemapEntries>> = Lazy::new(|| {
+    Cache::builder()
+      .max_capacity(1)
+      .time_to_live(SITEMAP_CACHE_DURATION)
+      .build()
+  });
+  let entries = CACHE
+    .try_get_with::<_, LemmyError>((), load_entries(&context))
+    .await
+    .map_err(|e| anyhow::anyhow!("Failed to generate sitemap: {e}"))?;
 
+  let mut pages = entries.chunks(SITEMAP_PAGE_SIZE);
+  let page_count = pages.len();
   let mut buf = Vec::<u8>::new();
-  generate_urlset(posts).await?.write(&mut buf)?;
+  match query.page {
+    None if page_count > 1 => {
+      let base_url = context.settings().get_protocol_and_hostname();
+      generate_sitemap_index(&base_url, page_count)?.write(&mut buf)?;
+    }
+    page => {
+      let index = page.unwrap_or(1).saturating_sub(1);
+      let urls = pages.nth(index).map(<[_]>::to_vec).unwrap_or_default();
+      generate_urlset(urls).await?.write(&mut buf)?;
+    }
+  }
 
   Ok(
     HttpResponse::Ok()
@@ -45,7 +107,7 @@ pub async fn get_sitemap(context: Data<LemmyContext>) -> LemmyResult<HttpRespons
 #[allow(clippy::unwrap_used)]
 pub(crate) mod tests {
 
-  use crate::sitemap::generate_urlset;
+  use crate::sitemap::{generate_sitemap_index, generate_urlset};
   use chrono::{DateTime, NaiveDate, Utc};
   use elementtree::Element;
   use lemmy_db_schema::newtypes::DbUrl;
@@ -140,4 +202,28 @@ pub(crate) mod tests {
       "2023-01-01T01:02:03+00:00"
     );
   }
+
+  #[test]
+  fn test_generate_sitemap_index() {
+    let mut buf = Vec::<u8>::new();
+    generate_sitemap_index("https://example.com", 2)
+      .unwrap()
+      .write(&mut buf)
+      .unwrap();
+    let root = Element::from_reader(buf.as_slice()).unwrap();
+
+    assert_eq!(root.tag().name(), "sitemapindex");
+    assert_eq!(root.child_count(), 2);
+    assert_eq!(
+      root
+        .children()
+        .nth(1)
+        .unwrap()
+        .children()
+        .find(|element| element.tag().name() == "loc")
+        .unwrap()
+        .text(),
+      "https://example.com/sitemap.xml?page=2"
+    );
+  }
 }
@@ -8,6 +8,7 @@ use lemmy_db_schema::{
 };
 use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
 pub struct Claims {
@@ -69,6 +70,22 @@ impl Claims {
     LoginToken::create(&mut context.pool(), form).await?;
     Ok(Sensitive::new(token))
   }
+
+  /// Generates a token which looks like a real one, but can't be used to log in. It is signed with
+  /// a random key and isn't stored as a login token.
+  pub fn generate_unusable(
+    user_id: LocalUserId,
+    context: &LemmyContext,
+  ) -> LemmyResult<Sensitive<String>> {
+    let my_claims = Claims {
+      sub: user_id.0.to_string(),
+      iss: context.settings().hostname.clone(),
+      iat: Utc::now().timestamp(),
+    };
+    let key = EncodingKey::from_secret(Uuid::new_v4().as_bytes());
+    let token = encode(&Header::default(), &my_claims, &key)?;
+    Ok(Sensitive::new(token))
+  }
 }
 
 #[cfg(test)]
@@ -136,6 +153,10 @@ mod tests {
     let valid = Claims::validate(&jwt, &context).await;
     assert!(valid.is_ok());
 
+    let unusable_jwt = Claims::generate_unusable(inserted_local_user.id, &context).unwrap();
+    let valid = Claims::validate(&unusable_jwt, &context).await;
+    assert!(valid.is_err());
+
     let num_deleted = Person::delete(pool, inserted_person.id).await.unwrap();
     assert_eq!(1, num_deleted);
   }
@@ -30,6 +30,29 @@ pub struct GetComment {
   pub id: CommentId,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetch a comment together with the comments above it and its direct replies, eg for a permalink.
+pub struct GetCommentContext {
+  pub comment_id: CommentId,
+  /// How many parent comments to return. Defaults to 3, at most 20.
+  pub context_depth: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A comment with its context.
+pub struct GetCommentContextResponse {
+  /// The parent comments, starting with the one furthest up in the tree.
+  pub ancestors: Vec<CommentView>,
+  pub comment_view: CommentView,
+  /// Direct replies to the comment.
+  pub replies: Vec<CommentView>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
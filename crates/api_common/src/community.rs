@@ -1,11 +1,12 @@
 use lemmy_db_schema::{
-  newtypes::{CommunityId, LanguageId, PersonId},
-  source::site::Site,
+  newtypes::{CommunityFlairId, CommunityId, LanguageId, PersonId},
+  source::{community_flair::CommunityFlair, instance::Instance, site::Site},
   CommunityVisibility,
   ListingType,
   SortType,
 };
 use lemmy_db_views_actor::structs::{CommunityModeratorView, CommunityView, PersonView};
+use lemmy_utils::error::LemmyErrorType;
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -56,6 +57,11 @@ pub struct CreateCommunity {
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Sort type for posts in the community, if the user doesn't specify one.
+  pub default_sort_type: Option<SortType>,
+  /// Only show scores of posts and comments to mods until they are this many hours old. 0 shows
+  /// them right away.
+  pub hide_scores_until_hours: Option<i32>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -153,6 +159,13 @@ pub struct EditCommunity {
   pub posting_restricted_to_mods: Option<bool>,
   pub discussion_languages: Option<Vec<LanguageId>>,
   pub visibility: Option<CommunityVisibility>,
+  /// Sort type for posts in the community, if the user doesn't specify one.
+  pub default_sort_type: Option<SortType>,
+  /// Remove the default sort type, so that the default of each user applies again.
+  pub clear_default_sort_type: Option<bool>,
+  /// Only show scores of posts and comments to mods until they are this many hours old. 0 shows
+  /// them right away.
+  pub hide_scores_until_hours: Option<i32>,
 }
 
 #[skip_serializing_none]
@@ -196,6 +209,34 @@ pub struct FollowCommunity {
   pub follow: bool,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Resolve a list of community handles and subscribe to each of them.
+pub struct ImportSubscriptions {
+  /// Example: !star_trek@xyz.tld
+  pub handles: Vec<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The outcome of subscribing to a single handle.
+pub struct ImportSubscriptionResult {
+  pub handle: String,
+  pub community_id: Option<CommunityId>,
+  pub error: Option<LemmyErrorType>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The import subscriptions response, in the same order as the submitted handles.
+pub struct ImportSubscriptionsResponse {
+  pub results: Vec<ImportSubscriptionResult>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -223,3 +264,113 @@ pub struct TransferCommunity {
   pub community_id: CommunityId,
   pub person_id: PersonId,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Move the posts, followers and moderators of a duplicate community into another one, and
+/// remove it. Both communities must be local. Only admins can do this.
+pub struct MergeCommunities {
+  pub from_community_id: CommunityId,
+  pub into_community_id: CommunityId,
+  pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The community which was merged into, and how much was moved.
+pub struct MergeCommunitiesResponse {
+  pub community_view: CommunityView,
+  pub posts_moved: i64,
+  pub followers_moved: i64,
+  pub moderators_moved: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the remote instances which receive activities for a community. Only for mods and admins.
+pub struct GetFederationTargets {
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The remote instances which receive activities for a community.
+pub struct GetFederationTargetsResponse {
+  pub instances: Vec<Instance>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Create a flair for the posts of a community. Only for mods and admins.
+pub struct CreateCommunityFlair {
+  pub community_id: CommunityId,
+  pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Rename a community flair. Only for mods and admins.
+pub struct EditCommunityFlair {
+  pub flair_id: CommunityFlairId,
+  pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Delete a community flair. Posts with this flair keep existing without flair.
+pub struct DeleteCommunityFlair {
+  pub flair_id: CommunityFlairId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A community flair response.
+pub struct CommunityFlairResponse {
+  pub flair: CommunityFlair,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the flairs of a community.
+pub struct ListCommunityFlairs {
+  pub community_id: CommunityId,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The flairs of a community, sorted by name.
+pub struct ListCommunityFlairsResponse {
+  pub flairs: Vec<CommunityFlair>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Check if a community with this name could be created, without creating it.
+pub struct ValidateCommunityName {
+  pub name: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The result of validating a community name.
+pub struct ValidateCommunityNameResponse {
+  pub valid: bool,
+  /// The name with surrounding whitespace removed, as it would be used for the community.
+  pub normalized: String,
+  /// Why the name can't be used. None if it is valid.
+  pub error: Option<LemmyErrorType>,
+}
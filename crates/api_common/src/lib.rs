@@ -17,6 +17,8 @@ pub mod send_activity;
 pub mod sensitive;
 pub mod site;
 #[cfg(feature = "full")]
+pub mod slow_query_log;
+#[cfg(feature = "full")]
 pub mod utils;
 
 pub extern crate lemmy_db_schema;
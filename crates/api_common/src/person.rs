@@ -1,9 +1,10 @@
 use crate::sensitive::Sensitive;
 use lemmy_db_schema::{
   newtypes::{CommentReplyId, CommunityId, LanguageId, PersonId, PersonMentionId},
-  source::{images::LocalImage, site::Site},
+  source::{community::Community, images::LocalImage, site::Site},
   CommentSortType,
   ListingType,
+  NotificationType,
   PostListingMode,
   SortType,
 };
@@ -47,7 +48,8 @@ pub struct Register {
   pub captcha_uuid: Option<String>,
   /// Your captcha answer.
   pub captcha_answer: Option<String>,
-  /// A form field to trick signup bots. Should be None.
+  /// A form field to trick signup bots. Clients should render it as a hidden input and leave it
+  /// empty. If it is filled, the registration is silently discarded.
   pub honeypot: Option<String>,
   /// An answer is mandatory if require application is enabled on the server
   pub answer: Option<String>,
@@ -362,6 +364,18 @@ pub struct PasswordChangeAfterReset {
   pub password_verify: Sensitive<String>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Export all data of a user as a JSON file, with their profile, follows, posts, comments and
+/// private messages.
+///
+/// Admins can export the data of other local users by passing a person_id.
+pub struct ExportUserData {
+  pub person_id: Option<PersonId>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -383,6 +397,16 @@ pub struct GetReportCountResponse {
   pub private_message_reports: Option<i64>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Mark your notifications as read.
+pub struct MarkAllAsRead {
+  /// Only mark notifications of this type as read. If not given, all notifications are marked.
+  pub type_: Option<NotificationType>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -439,3 +463,56 @@ pub struct ListMedia {
 pub struct ListMediaResponse {
   pub images: Vec<LocalImage>,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List the communities a user moderates. Defaults to your own account.
+pub struct GetModeratedCommunities {
+  pub person_id: Option<PersonId>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A community moderated by the user.
+pub struct ModeratedCommunity {
+  pub community: Community,
+  /// Position in the moderator list of the community, starting with 0 for the top mod.
+  pub rank: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The communities a user moderates.
+pub struct GetModeratedCommunitiesResponse {
+  pub communities: Vec<ModeratedCommunity>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get a user's posts and comments for moderation review.
+///
+/// Mods must give a community_id they moderate, only admins can search site-wide.
+pub struct GetUserContentForMod {
+  pub person_id: PersonId,
+  pub community_id: Option<CommunityId>,
+  /// Also return removed posts and comments, with their original content.
+  pub include_removed: Option<bool>,
+  pub search_term: Option<String>,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A user's posts and comments, for moderation review.
+pub struct GetUserContentForModResponse {
+  pub posts: Vec<PostView>,
+  pub comments: Vec<CommentView>,
+}
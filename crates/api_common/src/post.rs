@@ -1,5 +1,5 @@
 use lemmy_db_schema::{
-  newtypes::{CommentId, CommunityId, DbUrl, LanguageId, PostId, PostReportId},
+  newtypes::{CommentId, CommunityFlairId, CommunityId, DbUrl, LanguageId, PostId, PostReportId},
   ListingType,
   PostFeatureType,
   SortType,
@@ -33,6 +33,10 @@ pub struct CreatePost {
   #[cfg_attr(feature = "full", ts(type = "string"))]
   /// Instead of fetching a thumbnail, use a custom one.
   pub custom_thumbnail: Option<Url>,
+  /// Allow posting a url which was already posted to the community recently.
+  pub allow_duplicate: Option<bool>,
+  /// A flair of the community.
+  pub flair_id: Option<CommunityFlairId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -83,6 +87,8 @@ pub struct GetPosts {
   pub disliked_only: Option<bool>,
   pub show_hidden: Option<bool>,
   pub page_cursor: Option<PaginationCursor>,
+  /// Only return posts published since your previous visit.
+  pub since_last_visit: Option<bool>,
 }
 
 #[skip_serializing_none]
@@ -125,6 +131,10 @@ pub struct EditPost {
   #[cfg_attr(feature = "full", ts(type = "string"))]
   /// Instead of fetching a thumbnail, use a custom one.
   pub custom_thumbnail: Option<Url>,
+  /// A flair of the community.
+  pub flair_id: Option<CommunityFlairId>,
+  /// Remove the flair from the post.
+  pub clear_flair: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -253,12 +263,15 @@ pub struct GetSiteMetadata {
   pub url: Url,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
 /// The site metadata response.
 pub struct GetSiteMetadataResponse {
   pub metadata: LinkMetadata,
+  /// Only returned if the site provides oEmbed data.
+  pub oembed: Option<OembedData>,
 }
 
 #[skip_serializing_none]
@@ -272,6 +285,8 @@ pub struct LinkMetadata {
   pub content_type: Option<String>,
   #[serde(skip)]
   pub thumbnail: Option<DbUrl>,
+  #[serde(skip)]
+  pub oembed_url: Option<Url>,
 }
 
 #[skip_serializing_none]
@@ -286,6 +301,26 @@ pub struct OpenGraphData {
   pub embed_video_url: Option<DbUrl>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone, Default, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Rich embed data for a site, from its oEmbed provider.
+pub struct OembedData {
+  /// One of `photo`, `video`, `link` or `rich`.
+  pub type_: String,
+  pub title: Option<String>,
+  pub author_name: Option<String>,
+  pub provider_name: Option<String>,
+  /// The embed html. Only iframes are allowed, everything else is dropped.
+  pub html: Option<String>,
+  /// The image url for the `photo` type.
+  pub url: Option<DbUrl>,
+  pub thumbnail_url: Option<DbUrl>,
+  pub width: Option<i32>,
+  pub height: Option<i32>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -1,12 +1,13 @@
 use crate::{
   context::LemmyContext,
   lemmy_db_schema::traits::Crud,
-  post::{LinkMetadata, OpenGraphData},
+  post::{LinkMetadata, OembedData, OpenGraphData},
   send_activity::{ActivityChannel, SendActivityData},
   utils::{local_site_opt_to_sensitive, proxy_image_link, proxy_image_link_opt_apub},
 };
 use activitypub_federation::config::Data;
 use encoding::{all::encodings, DecoderTrap};
+use hyper::client::connect::dns::Name;
 use lemmy_db_schema::{
   newtypes::DbUrl,
   source::{
@@ -17,18 +18,37 @@ use lemmy_db_schema::{
 };
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorType, LemmyResult},
-  settings::structs::{PictrsImageMode, Settings},
+  settings::{
+    structs::{PictrsConfig, PictrsImageMode, Settings},
+    SETTINGS,
+  },
   spawn_try_task,
   REQWEST_TIMEOUT,
   VERSION,
 };
 use mime::Mime;
-use reqwest::{header::CONTENT_TYPE, Client, ClientBuilder};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{
+  dns::{Addrs, Resolve, Resolving},
+  header::CONTENT_TYPE,
+  multipart::{Form, Part},
+  redirect::Policy,
+  Client,
+  ClientBuilder,
+  Response,
+};
 use reqwest_middleware::ClientWithMiddleware;
 use serde::Deserialize;
+use std::{
+  net::{IpAddr, SocketAddr},
+  sync::Arc,
+  time::Duration,
+};
+use tokio::net::lookup_host;
 use tracing::info;
-use url::Url;
-use urlencoding::encode;
+use url::{Host, Url};
 use webpage::HTML;
 
 pub fn client_builder(settings: &Settings) -> ClientBuilder {
@@ -40,6 +60,57 @@ pub fn client_builder(settings: &Settings) -> ClientBuilder {
     .connect_timeout(REQWEST_TIMEOUT)
 }
 
+/// Client for urls which come from users or remote servers, like oEmbed endpoints and thumbnail
+/// images. Lemmy usually runs in the same network as other services, so this client refuses to
+/// connect to non-public addresses. The check happens for every connection, so that redirects and
+/// DNS records which change after a first lookup can't be used to get around it.
+pub fn public_client_builder(settings: &Settings) -> ClientBuilder {
+  client_builder(settings)
+    .dns_resolver(Arc::new(PublicAddressResolver))
+    .redirect(Policy::custom(|attempt| {
+      if attempt.previous().len() >= MAX_REDIRECTS {
+        attempt.error("too many redirects")
+      } else if check_url_is_public(attempt.url()).is_err() {
+        attempt.error("redirect to a non-public address")
+      } else {
+        attempt.follow()
+      }
+    }))
+}
+
+/// Same as the default of reqwest
+const MAX_REDIRECTS: usize = 10;
+
+static PUBLIC_CLIENT: Lazy<Client> = Lazy::new(|| {
+  public_client_builder(&SETTINGS)
+    .build()
+    .expect("build client")
+});
+
+/// Resolves domains like the default resolver, but fails if any of their addresses isn't public.
+struct PublicAddressResolver;
+
+impl Resolve for PublicAddressResolver {
+  fn resolve(&self, name: Name) -> Resolving {
+    Box::pin(async move {
+      let addrs: Vec<SocketAddr> = lookup_host((name.as_str(), 0)).await?.collect();
+      if addrs.iter().all(|addr| is_public_ip(&addr.ip())) {
+        let addrs: Addrs = Box::new(addrs.into_iter());
+        Ok(addrs)
+      } else {
+        Err(format!("{} resolves to a non-public address", name.as_str()).into())
+      }
+    })
+  }
+}
+
+/// Fetches the url with [PUBLIC_CLIENT].
+async fn get_public(url: &Url) -> LemmyResult<Response> {
+  // Urls with an ip address don't go through the resolver
+  check_url_is_public(url)?;
+  Ok(PUBLIC_CLIENT.get(url.as_str()).send().await?)
+}
+
 /// Fetches metadata for the given link and optionally generates thumbnail.
 #[tracing::instrument(skip_all)]
 pub async fn fetch_link_metadata(
@@ -48,7 +119,7 @@ pub async fn fetch_link_metadata(
   context: &LemmyContext,
 ) -> LemmyResult<LinkMetadata> {
   info!("Fetching site metadata for url: {}", url);
-  let response = context.client().get(url.as_str()).send().await?;
+  let mut response = context.client().get(url.as_str()).send().await?;
 
   let content_type: Option<Mime> = response
     .headers()
@@ -58,18 +129,21 @@ pub async fn fetch_link_metadata(
 
   // Can't use .text() here, because it only checks the content header, not the actual bytes
   // https://github.com/LemmyNet/lemmy/issues/1964
-  let html_bytes = response.bytes().await.map_err(LemmyError::from)?.to_vec();
+  // The metadata is at the start of the page, so the rest of huge responses can be skipped.
+  let (html_bytes, _) = read_body_limited(&mut response, METADATA_MAX_RESPONSE_SIZE).await?;
 
   let opengraph_data = extract_opengraph_data(&html_bytes, url)
     .map_err(|e| info!("{e}"))
     .unwrap_or_default();
   let thumbnail =
     extract_thumbnail_from_opengraph_data(url, &opengraph_data, generate_thumbnail, context).await;
+  let oembed_url = extract_oembed_url(&html_bytes, url);
 
   Ok(LinkMetadata {
     opengraph_data,
     content_type: content_type.map(|c| c.to_string()),
     thumbnail,
+    oembed_url,
   })
 }
 
@@ -112,13 +186,14 @@ pub fn generate_post_link_metadata(
     )
     .await;
 
-    // If its an image post, it needs to overwrite the thumbnail, and take precedence
+    // If its an image post, it needs to overwrite the thumbnail, and take precedence. Use the
+    // generated thumbnail if possible, and fall back to the original image otherwise.
     let image_url = if metadata
       .content_type
       .as_ref()
       .is_some_and(|content_type| content_type.starts_with("image"))
     {
-      post.url.map(Into::into)
+      metadata.thumbnail.clone().or(post.url).map(Into::into)
     } else {
       None
     };
@@ -213,6 +288,156 @@ fn extract_opengraph_data(html_bytes: &[u8], url: &Url) -> LemmyResult<OpenGraph
   })
 }
 
+static OEMBED_LINK_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r#"(?i)<link\s[^>]*type\s*=\s*["']application/json\+oembed["'][^>]*>"#)
+    .expect("compile regex")
+});
+static HREF_REGEX: Lazy<Regex> =
+  Lazy::new(|| Regex::new(r#"(?i)\shref\s*=\s*["']([^"']+)["']"#).expect("compile regex"));
+static IFRAME_REGEX: Lazy<Regex> = Lazy::new(|| {
+  Regex::new(r#"(?is)^\s*<iframe\b[^<>]*\ssrc\s*=\s*["']([^"'<>]+)["'][^<>]*>\s*</iframe>\s*$"#)
+    .expect("compile regex")
+});
+
+/// How long fetched oEmbed data is cached.
+const OEMBED_CACHE_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// Maximum size of an oEmbed response in bytes. Real responses are only a few kilobytes.
+const OEMBED_MAX_RESPONSE_SIZE: usize = 64 * 1024;
+
+/// How much of a linked page is read to extract its metadata, in bytes.
+const METADATA_MAX_RESPONSE_SIZE: usize = 2 * 1024 * 1024;
+
+/// Reads the response body, but stops after `max_size` bytes. Returns the bytes which were read,
+/// and whether that is the complete body. Unlike the content length header, this can't be faked
+/// by the server.
+async fn read_body_limited(
+  response: &mut Response,
+  max_size: usize,
+) -> LemmyResult<(Vec<u8>, bool)> {
+  let mut bytes = Vec::new();
+  while let Some(chunk) = response.chunk().await? {
+    let remaining = max_size.saturating_sub(bytes.len());
+    if chunk.len() > remaining {
+      bytes.extend_from_slice(chunk.get(..remaining).unwrap_or_default());
+      return Ok((bytes, false));
+    }
+    bytes.extend_from_slice(&chunk);
+  }
+  Ok((bytes, true))
+}
+
+/// Discover the oEmbed endpoint of a page, from its `<link type="application/json+oembed">` tag.
+fn extract_oembed_url(html_bytes: &[u8], url: &Url) -> Option<Url> {
+  let html = String::from_utf8_lossy(html_bytes);
+  let link = OEMBED_LINK_REGEX.find(&html)?;
+  let href = HREF_REGEX.captures(link.as_str())?.get(1)?.as_str();
+  // join also works if the target URL is absolute
+  let oembed_url = url.join(&href.replace("&amp;", "&")).ok()?;
+  matches!(oembed_url.scheme(), "http" | "https").then_some(oembed_url)
+}
+
+/// The response of an oEmbed provider.
+#[derive(Deserialize, Debug)]
+struct OembedResponse {
+  #[serde(rename = "type")]
+  type_: String,
+  title: Option<String>,
+  author_name: Option<String>,
+  provider_name: Option<String>,
+  html: Option<String>,
+  url: Option<Url>,
+  thumbnail_url: Option<Url>,
+  width: Option<OembedDimension>,
+  height: Option<OembedDimension>,
+}
+
+/// Some providers send the dimensions as strings instead of numbers.
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum OembedDimension {
+  Number(i64),
+  Text(String),
+}
+
+impl OembedDimension {
+  fn to_i32(&self) -> Option<i32> {
+    match self {
+      OembedDimension::Number(n) => i32::try_from(*n).ok(),
+      OembedDimension::Text(s) => s.parse().ok(),
+    }
+  }
+}
+
+/// Fetches the data from an oEmbed endpoint, which was discovered with [fetch_link_metadata].
+/// Results are cached, and any returned html is sanitized.
+#[tracing::instrument(skip_all)]
+pub async fn fetch_oembed_data(oembed_url: &Url) -> LemmyResult<OembedData> {
+  static CACHE: Lazy<Cache<Url, OembedData>> = Lazy::new(|| {
+    Cache::builder()
+      .max_capacity(1000)
+      .time_to_live(OEMBED_CACHE_DURATION)
+      .build()
+  });
+  Ok(
+    CACHE
+      .try_get_with::<_, LemmyError>(oembed_url.clone(), async {
+        info!("Fetching oembed data for url: {}", oembed_url);
+        // The endpoint comes from the linked page, so it must not be used to access internal
+        // services
+        let mut response = get_public(oembed_url).await?;
+        if response
+          .content_length()
+          .is_some_and(|len| len > OEMBED_MAX_RESPONSE_SIZE as u64)
+        {
+          Err(anyhow::anyhow!("oEmbed response is too large"))?
+        }
+        // The content length header is optional, so also check the actual size
+        let (bytes, complete) = read_body_limited(&mut response, OEMBED_MAX_RESPONSE_SIZE).await?;
+        if !complete {
+          Err(anyhow::anyhow!("oEmbed response is too large"))?
+        }
+        let res: OembedResponse = serde_json::from_slice(&bytes)?;
+        let width = res.width.as_ref().and_then(OembedDimension::to_i32);
+        let height = res.height.as_ref().and_then(OembedDimension::to_i32);
+        Ok(OembedData {
+          type_: res.type_,
+          title: res.title,
+          author_name: res.author_name,
+          provider_name: res.provider_name,
+          html: res
+            .html
+            .and_then(|html| sanitize_oembed_html(&html, width, height)),
+          url: res.url.map(Into::into),
+          thumbnail_url: res.thumbnail_url.map(Into::into),
+          width,
+          height,
+        })
+      })
+      .await
+      .map_err(|e| anyhow::anyhow!("Failed to fetch oembed data due to `{}`", e))?,
+  )
+}
+
+/// Only a single iframe with https source is allowed in oEmbed html. It is rebuilt from scratch,
+/// so that no other attributes or scripts from the provider end up in the page.
+fn sanitize_oembed_html(html: &str, width: Option<i32>, height: Option<i32>) -> Option<String> {
+  let src = IFRAME_REGEX.captures(html)?.get(1)?.as_str();
+  let src = Url::parse(&src.replace("&amp;", "&")).ok()?;
+  if src.scheme() != "https" {
+    return None;
+  }
+  let mut iframe = format!("<iframe src=\"{}\"", src.as_str().replace('&', "&amp;"));
+  if let Some(width) = width {
+    iframe.push_str(&format!(" width=\"{width}\""));
+  }
+  if let Some(height) = height {
+    iframe.push_str(&format!(" height=\"{height}\""));
+  }
+  iframe.push_str(" allowfullscreen></iframe>");
+  Some(iframe)
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn extract_thumbnail_from_opengraph_data(
   url: &Url,
@@ -321,29 +546,41 @@ async fn generate_pictrs_thumbnail(image_url: &Url, context: &LemmyContext) -> L
     _ => {}
   };
 
-  // fetch remote non-pictrs images for persistent thumbnail link
-  // TODO: should limit size once supported by pictrs
-  let fetch_url = format!(
-    "{}image/download?url={}",
-    pictrs_config.url,
-    encode(image_url.as_str())
-  );
+  // fetch remote non-pictrs images for persistent thumbnail link. This is done here and not with
+  // the pictrs download endpoint, so that the size limit also applies while reading the image,
+  // and so that internal services can't be accessed through pictrs.
+  let mut image_response = get_public(image_url).await?;
+  let max_size = usize::try_from(pictrs_config.thumbnail_max_fetch_size).unwrap_or(usize::MAX);
+  let (image, complete) = read_body_limited(&mut image_response, max_size).await?;
+  if !complete {
+    Err(anyhow::anyhow!(
+      "Image is too large to generate a thumbnail"
+    ))?
+  }
 
+  let file_name = image_url
+    .path_segments()
+    .and_then(Iterator::last)
+    .filter(|s| !s.is_empty())
+    .unwrap_or("image")
+    .to_string();
+  let form = Form::new().part("images[]", Part::bytes(image).file_name(file_name));
   let response = context
     .client()
-    .get(&fetch_url)
-    .timeout(REQWEST_TIMEOUT)
+    .post(format!("{}image", pictrs_config.url))
+    .timeout(Duration::from_secs(pictrs_config.upload_timeout))
+    .multipart(form)
     .send()
     .await?;
 
   let response: PictrsResponse = response.json().await?;
 
   if response.msg == "ok" {
-    let thumbnail_url = Url::parse(&format!(
-      "{}/pictrs/image/{}",
-      context.settings().get_protocol_and_hostname(),
-      response.files.first().expect("missing pictrs file").file
-    ))?;
+    let thumbnail_url = pictrs_thumbnail_url(
+      &context.settings().get_protocol_and_hostname(),
+      &response.files.first().expect("missing pictrs file").file,
+      &pictrs_config,
+    )?;
     for uploaded_image in response.files {
       let form = LocalImageForm {
         local_user_id: None,
@@ -358,6 +595,70 @@ async fn generate_pictrs_thumbnail(image_url: &Url, context: &LemmyContext) -> L
   }
 }
 
+/// The url under which a thumbnail of the uploaded pictrs image is served. pictrs resizes and
+/// converts the image on the first request, if configured.
+fn pictrs_thumbnail_url(
+  protocol_and_hostname: &str,
+  file: &str,
+  pictrs_config: &PictrsConfig,
+) -> LemmyResult<Url> {
+  let mut thumbnail_url = Url::parse(&format!("{protocol_and_hostname}/pictrs/image/{file}"))?;
+  if pictrs_config.thumbnail_size > 0 {
+    thumbnail_url
+      .query_pairs_mut()
+      .append_pair("thumbnail", &pictrs_config.thumbnail_size.to_string());
+  }
+  if let Some(format) = &pictrs_config.thumbnail_format {
+    thumbnail_url
+      .query_pairs_mut()
+      .append_pair("format", format);
+  }
+  Ok(thumbnail_url)
+}
+
+/// Make sure that the url doesn't point to a loopback, private or otherwise non-public ip address.
+/// Domains are checked by [PublicAddressResolver] when connecting.
+fn check_url_is_public(url: &Url) -> LemmyResult<()> {
+  let is_public = match url.host().ok_or(LemmyErrorType::InvalidUrl)? {
+    Host::Ipv4(ip) => is_public_ip(&ip.into()),
+    Host::Ipv6(ip) => is_public_ip(&ip.into()),
+    Host::Domain(_) => true,
+  };
+  if is_public {
+    Ok(())
+  } else {
+    Err(LemmyErrorType::BlockedUrl)?
+  }
+}
+
+fn is_public_ip(ip: &IpAddr) -> bool {
+  match ip {
+    IpAddr::V4(ip) => {
+      !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast())
+    }
+    IpAddr::V6(ip) => {
+      if let Some(ip) = ip.to_ipv4_mapped() {
+        return is_public_ip(&ip.into());
+      }
+      let [first_segment, ..] = ip.segments();
+      // Unique local (fc00::/7) and link local (fe80::/10) addresses
+      let is_unique_local = first_segment & 0xfe00 == 0xfc00;
+      let is_link_local = first_segment & 0xffc0 == 0xfe80;
+      !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        || is_unique_local
+        || is_link_local)
+    }
+  }
+}
+
 // TODO: get rid of this by reading content type from db
 #[tracing::instrument(skip_all)]
 async fn is_image_content_type(client: &ClientWithMiddleware, url: &Url) -> LemmyResult<()> {
@@ -402,10 +703,26 @@ mod tests {
 
   use crate::{
     context::LemmyContext,
-    request::{extract_opengraph_data, fetch_link_metadata},
+    request::{
+      check_url_is_public,
+      extract_oembed_url,
+      extract_opengraph_data,
+      fetch_link_metadata,
+      pictrs_thumbnail_url,
+      public_client_builder,
+      read_body_limited,
+      sanitize_oembed_html,
+      PublicAddressResolver,
+    },
   };
+  use lemmy_utils::settings::{structs::PictrsConfig, SETTINGS};
   use pretty_assertions::assert_eq;
+  use reqwest::dns::Resolve;
   use serial_test::serial;
+  use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+  };
   use url::Url;
 
   // These helped with testing
@@ -490,4 +807,163 @@ mod tests {
       Some(Url::parse("https://example.com/image.jpg").unwrap().into())
     );
   }
+
+  #[test]
+  fn test_extract_oembed_url() {
+    let url = Url::parse("https://example.com/watch?v=1").unwrap();
+
+    let html_bytes = b"<!DOCTYPE html><html><head><link rel='alternate' type='application/json+oembed' href='/oembed?url=https%3A%2F%2Fexample.com%2Fwatch%3Fv%3D1&amp;format=json'></head><body></body></html>";
+    assert_eq!(
+      Some(
+        Url::parse(
+          "https://example.com/oembed?url=https%3A%2F%2Fexample.com%2Fwatch%3Fv%3D1&format=json"
+        )
+        .unwrap()
+      ),
+      extract_oembed_url(html_bytes, &url)
+    );
+
+    let html_bytes = b"<!DOCTYPE html><html><head><link rel='alternate' type='application/rss+xml' href='/feed'></head><body></body></html>";
+    assert_eq!(None, extract_oembed_url(html_bytes, &url));
+  }
+
+  #[test]
+  fn test_sanitize_oembed_html() {
+    let html = r#"<iframe width="200" onload="alert(1)" src="https://www.example.com/embed/1?a=1&amp;b=2" frameborder="0"></iframe>"#;
+    assert_eq!(
+      Some(
+        r#"<iframe src="https://www.example.com/embed/1?a=1&amp;b=2" width="200" height="100" allowfullscreen></iframe>"#
+          .to_string()
+      ),
+      sanitize_oembed_html(html, Some(200), Some(100))
+    );
+
+    // Only https iframes are allowed
+    let html = r#"<iframe src="http://www.example.com/embed/1"></iframe>"#;
+    assert_eq!(None, sanitize_oembed_html(html, None, None));
+    let html =
+      r#"<blockquote>Quote</blockquote><script src="https://example.com/widgets.js"></script>"#;
+    assert_eq!(None, sanitize_oembed_html(html, None, None));
+    let html = r#"<iframe src="https://example.com/1"></iframe><script>alert(1)</script>"#;
+    assert_eq!(None, sanitize_oembed_html(html, None, None));
+  }
+
+  #[test]
+  fn test_check_url_is_public() {
+    for url in [
+      "http://127.0.0.1/image.png",
+      "http://192.168.1.10/image.png",
+      "http://10.0.0.1/image.png",
+      "http://169.254.169.254/latest/meta-data",
+      "http://[::1]/image.png",
+      "http://[fd00::1]/image.png",
+      "http://[::ffff:127.0.0.1]/image.png",
+    ] {
+      assert!(check_url_is_public(&Url::parse(url).unwrap()).is_err());
+    }
+    for url in [
+      "http://93.184.215.14/image.png",
+      "http://[2606:4700::1]/image.png",
+      // Checked when connecting
+      "http://localhost:8080/image.png",
+    ] {
+      assert!(check_url_is_public(&Url::parse(url).unwrap()).is_ok());
+    }
+  }
+
+  #[tokio::test]
+  async fn test_public_client() {
+    assert!(PublicAddressResolver
+      .resolve("localhost".parse().unwrap())
+      .await
+      .is_err());
+
+    // Serves redirects to the path, eg `/127.0.0.1/ok` redirects to `http://127.0.0.1/ok`
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+      while let Ok((stream, _)) = listener.accept().await {
+        let mut stream = BufReader::new(stream);
+        let mut request_line = String::new();
+        stream.read_line(&mut request_line).await.unwrap();
+        let path = request_line.split(' ').nth(1).unwrap_or_default();
+        let response = match path.strip_prefix('/').and_then(|p| p.split_once('/')) {
+          Some((host, path)) => format!(
+            "HTTP/1.1 302 Found\r\nLocation: http://{host}:{}/{path}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            addr.port()
+          ),
+          None => "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok"
+            .to_string(),
+        };
+        stream.write_all(response.as_bytes()).await.unwrap();
+      }
+    });
+
+    // Pretend that the server has a public domain, which the resolver would allow
+    let client = public_client_builder(&SETTINGS)
+      .resolve("public.example", addr)
+      .build()
+      .unwrap();
+    let get = |path: &str| {
+      client
+        .get(format!("http://public.example:{}{path}", addr.port()))
+        .send()
+    };
+    assert_eq!("ok", get("/ok").await.unwrap().text().await.unwrap());
+    assert_eq!(
+      "ok",
+      get("/public.example/ok")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap()
+    );
+    // Redirects to non-public addresses are refused, also if they only resolve to one
+    assert!(get("/127.0.0.1/ok").await.is_err());
+    assert!(get("/localhost/ok").await.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_read_body_limited() {
+    // Serves a chunked response of 100 bytes, which doesn't specify the content length
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+      while let Ok((mut stream, _)) = listener.accept().await {
+        let mut response = "HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n".to_string();
+        for _ in 0..10 {
+          response.push_str("a\r\n0123456789\r\n");
+        }
+        response.push_str("0\r\n\r\n");
+        stream.write_all(response.as_bytes()).await.unwrap();
+      }
+    });
+
+    let mut response = reqwest::get(&url).await.unwrap();
+    let (bytes, complete) = read_body_limited(&mut response, 25).await.unwrap();
+    assert_eq!(b"0123456789012345678901234".to_vec(), bytes);
+    assert!(!complete);
+
+    let mut response = reqwest::get(&url).await.unwrap();
+    let (bytes, complete) = read_body_limited(&mut response, 100).await.unwrap();
+    assert_eq!(100, bytes.len());
+    assert!(complete);
+  }
+
+  #[test]
+  fn test_pictrs_thumbnail_url() {
+    // By default the original image is served
+    let mut pictrs_config = PictrsConfig::default();
+    let url = pictrs_thumbnail_url("https://example.com", "abc.jpg", &pictrs_config).unwrap();
+    assert_eq!("https://example.com/pictrs/image/abc.jpg", url.as_str());
+
+    pictrs_config.thumbnail_size = 256;
+    pictrs_config.thumbnail_format = Some("webp".to_string());
+    let url = pictrs_thumbnail_url("https://example.com", "abc.jpg", &pictrs_config).unwrap();
+    assert_eq!(
+      "https://example.com/pictrs/image/abc.jpg?thumbnail=256&format=webp",
+      url.as_str()
+    );
+  }
 }
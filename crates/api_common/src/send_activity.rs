@@ -9,6 +9,7 @@ use lemmy_db_schema::{
     person::Person,
     post::Post,
     private_message::PrivateMessage,
+    site::Site,
   },
 };
 use lemmy_db_views::structs::PrivateMessageView;
@@ -97,6 +98,7 @@ pub enum SendActivityData {
     community: Community,
     reason: String,
   },
+  UpdateInstance(Site),
 }
 
 // TODO: instead of static, move this into LemmyContext. make sure that stopping the process with
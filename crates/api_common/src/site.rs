@@ -3,18 +3,23 @@ use chrono::{DateTime, Utc};
 use lemmy_db_schema::{
   newtypes::{CommentId, CommunityId, InstanceId, LanguageId, PersonId, PostId},
   source::{
+    community::Community,
     federation_queue_state::FederationQueueState,
     instance::Instance,
     language::Language,
     local_site_url_blocklist::LocalSiteUrlBlocklist,
+    site_stats_history::SiteStatsHistory,
     tagline::Tagline,
   },
   ListingType,
   ModlogActionType,
+  ModlogFormat,
+  ModlogOrder,
   PostListingMode,
   RegistrationMode,
   SearchType,
   SortType,
+  StatsGranularity,
 };
 use lemmy_db_views::structs::{
   CommentView,
@@ -34,10 +39,12 @@ use lemmy_db_views_actor::structs::{
   PersonView,
 };
 use lemmy_db_views_moderator::structs::{
+  AdminExportUserDataView,
   AdminPurgeCommentView,
   AdminPurgeCommunityView,
   AdminPurgePersonView,
   AdminPurgePostView,
+  AdminRotateFederationKeyView,
   ModAddCommunityView,
   ModAddView,
   ModBanFromCommunityView,
@@ -50,7 +57,8 @@ use lemmy_db_views_moderator::structs::{
   ModRemovePostView,
   ModTransferCommunityView,
 };
-use serde::{Deserialize, Serialize};
+use lemmy_utils::{LemmyErrorType, BUILD_TIMESTAMP, GIT_COMMIT, VERSION};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
 use ts_rs::TS;
@@ -70,8 +78,86 @@ pub struct Search {
   pub listing_type: Option<ListingType>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
+  /// Override the limit for posts when searching all types.
+  pub limit_posts: Option<i64>,
+  /// Override the limit for comments when searching all types.
+  pub limit_comments: Option<i64>,
+  /// Override the limit for communities when searching all types.
+  pub limit_communities: Option<i64>,
+  /// Override the limit for users when searching all types.
+  pub limit_users: Option<i64>,
+  /// Merge posts linking to the same url into a single result. This is done for each page after
+  /// it is read, so a page can contain fewer posts than the limit, and a later page can contain
+  /// another post with the same url. [SearchResponse.counts] includes the merged posts.
+  pub collapse_crossposts: Option<bool>,
+  /// If the search takes too long, return the results found so far instead of an error.
+  pub partial_on_timeout: Option<bool>,
+  /// Only return posts and comments with at least this score.
+  pub min_score: Option<i64>,
+  /// Only return posts and comments with at most this score.
+  pub max_score: Option<i64>,
+  /// Return posts grouped by their community, in [SearchResponse.community_post_groups].
+  pub group_by_community: Option<bool>,
+  /// Don't return posts or comments from these communities. In query strings, the ids are comma
+  /// separated.
+  #[serde(default, deserialize_with = "deserialize_community_ids")]
+  pub exclude_community_ids: Option<Vec<CommunityId>>,
+  /// If true, only return posts linking to an image or video. If false, only return other posts.
+  pub has_media: Option<bool>,
+  /// Also find posts whose title or body matched the search before they were edited.
+  pub search_history: Option<bool>,
+  /// Search posts and comments with full text search instead of by substring, which also matches
+  /// words in a different order. Needed for the Relevance sort.
+  pub use_fts: Option<bool>,
+  /// Only return posts and comments published since your previous visit.
+  pub since_last_visit: Option<bool>,
+  /// Only return posts and comments published after this time.
+  pub published_after: Option<DateTime<Utc>>,
+  /// Only return posts and comments published before this time.
+  pub published_before: Option<DateTime<Utc>>,
+  /// If there are few results, suggest similar community names in
+  /// [SearchResponse.suggestions].
+  pub suggestions: Option<bool>,
+  /// Also return all results in a single list, in [SearchResponse.merged].
+  pub merge: Option<bool>,
+  /// Include posts and comments removed by a moderator. Only allowed for admins, ignored
+  /// otherwise.
+  pub include_removed: Option<bool>,
+  /// Include posts and comments deleted by their creator. Only allowed for admins, ignored
+  /// otherwise.
+  pub include_deleted: Option<bool>,
 }
 
+/// Query strings can't contain lists, so clients send them comma separated instead.
+fn deserialize_community_ids<'de, D>(deserializer: D) -> Result<Option<Vec<CommunityId>>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum CommunityIds {
+    List(Vec<CommunityId>),
+    CommaSeparated(String),
+  }
+
+  match Option::<CommunityIds>::deserialize(deserializer)? {
+    Some(CommunityIds::List(ids)) => Ok(Some(ids)),
+    Some(CommunityIds::CommaSeparated(ids)) => ids
+      .split(',')
+      .map(str::trim)
+      .filter(|id| !id.is_empty())
+      .map(|id| {
+        id.parse()
+          .map(CommunityId)
+          .map_err(serde::de::Error::custom)
+      })
+      .collect::<Result<_, _>>()
+      .map(Some),
+    None => Ok(None),
+  }
+}
+
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -83,6 +169,65 @@ pub struct SearchResponse {
   pub posts: Vec<PostView>,
   pub communities: Vec<CommunityView>,
   pub users: Vec<PersonView>,
+  /// Only returned if [Search.collapse_crossposts] is set.
+  pub crosspost_counts: Option<Vec<CrosspostCount>>,
+  /// Set if the search hit its deadline and some result types are missing or empty. Only
+  /// possible with [Search.partial_on_timeout].
+  pub partial: Option<bool>,
+  /// Only returned if [Search.group_by_community] is set. The posts are then moved into these
+  /// groups, and the `posts` list is empty.
+  pub community_post_groups: Option<Vec<CommunityPostGroup>>,
+  /// Only returned if [Search.suggestions] is set and the search found few results.
+  pub suggestions: Option<Vec<String>>,
+  /// The total number of results, ignoring pagination. Only the searched types are counted.
+  pub counts: SearchCounts,
+  /// Only returned if [Search.merge] is set. Contains the results of all types, ordered by the
+  /// requested sort type.
+  pub merged: Option<Vec<SearchResultItem>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The total number of search results for each type.
+pub struct SearchCounts {
+  pub posts: i64,
+  pub comments: i64,
+  pub communities: i64,
+  pub users: i64,
+  /// Set if some of the counts hit the deadline of [Search.partial_on_timeout]. These are
+  /// reported as 0.
+  pub incomplete: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+#[serde(tag = "type_")]
+/// A single search result of any type.
+pub enum SearchResultItem {
+  Post(PostView),
+  Comment(CommentView),
+  Community(CommunityView),
+  Person(PersonView),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The number of other posts with the same url which were merged into a search result.
+pub struct CrosspostCount {
+  pub post_id: PostId,
+  pub crosspost_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Post search results from a single community.
+pub struct CommunityPostGroup {
+  pub community: Community,
+  pub posts: Vec<PostView>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
@@ -107,6 +252,34 @@ pub struct ResolveObjectResponse {
   pub person: Option<PersonView>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Does several apub fetches at once.
+pub struct ResolveObjects {
+  /// Each can be the full url, or a shortened version like: !fediverse@lemmy.ml
+  pub ids: Vec<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The outcome of fetching a single id.
+pub struct ResolveObjectResult {
+  pub id: String,
+  pub object: Option<ResolveObjectResponse>,
+  pub error: Option<LemmyErrorType>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The response of several apub fetches, in the same order as the submitted ids.
+pub struct ResolveObjectsResponse {
+  pub results: Vec<ResolveObjectResult>,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -121,8 +294,20 @@ pub struct GetModlog {
   pub other_person_id: Option<PersonId>,
   pub post_id: Option<PostId>,
   pub comment_id: Option<CommentId>,
+  /// Cursor for keyset pagination, use [GetModlogResponse.next_page] from the previous page.
+  /// Replaces the page offset when given. The ids of the different modlog tables are
+  /// unrelated, so this requires a `type_` other than `All`.
+  pub page_after: Option<i32>,
+  /// Defaults to newest first.
+  pub order: Option<ModlogOrder>,
+  /// Only return entries which were created after this time.
+  pub since: Option<DateTime<Utc>>,
+  /// Return the entries as an RSS feed instead of JSON, so that anyone can subscribe to the
+  /// modlog. The same permission checks and filters apply.
+  pub format: Option<ModlogFormat>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -143,7 +328,56 @@ pub struct GetModlogResponse {
   pub admin_purged_communities: Vec<AdminPurgeCommunityView>,
   pub admin_purged_posts: Vec<AdminPurgePostView>,
   pub admin_purged_comments: Vec<AdminPurgeCommentView>,
+  pub admin_rotated_federation_keys: Vec<AdminRotateFederationKeyView>,
+  pub admin_exported_user_data: Vec<AdminExportUserDataView>,
   pub hidden_communities: Vec<ModHideCommunityView>,
+  /// The smallest id of the returned entries (largest if ascending), to be passed as
+  /// [GetModlog.page_after] for the next page. Only set when a single `type_` was requested.
+  pub next_page: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetches a single modlog entry, eg for a permalink.
+pub struct GetModlogEntry {
+  /// The type of the entry. Ids are only unique within a type, so All isn't allowed.
+  pub action_type: ModlogActionType,
+  pub id: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+#[serde(tag = "type_")]
+/// A modlog entry of any type. The variants with a post or comment are boxed, as they are much
+/// larger than the others.
+pub enum ModlogEntry {
+  ModRemovePost(Box<ModRemovePostView>),
+  ModLockPost(Box<ModLockPostView>),
+  ModFeaturePost(Box<ModFeaturePostView>),
+  ModRemoveComment(Box<ModRemoveCommentView>),
+  ModRemoveCommunity(ModRemoveCommunityView),
+  ModBanFromCommunity(ModBanFromCommunityView),
+  ModAddCommunity(ModAddCommunityView),
+  ModTransferCommunity(ModTransferCommunityView),
+  ModAdd(ModAddView),
+  ModBan(ModBanView),
+  ModHideCommunity(ModHideCommunityView),
+  AdminPurgePerson(AdminPurgePersonView),
+  AdminPurgeCommunity(AdminPurgeCommunityView),
+  AdminPurgePost(AdminPurgePostView),
+  AdminPurgeComment(Box<AdminPurgeCommentView>),
+  AdminRotateFederationKey(AdminRotateFederationKeyView),
+  AdminExportUserData(AdminExportUserDataView),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A single modlog entry.
+pub struct GetModlogEntryResponse {
+  pub entry: ModlogEntry,
 }
 
 #[skip_serializing_none]
@@ -194,6 +428,21 @@ pub struct CreateSite {
   pub registration_mode: Option<RegistrationMode>,
   pub content_warning: Option<String>,
   pub default_post_listing_mode: Option<PostListingMode>,
+  pub new_user_badge_days: Option<i32>,
+  pub duplicate_post_window_hours: Option<i32>,
+  pub auto_lock_posts_after_days: Option<i32>,
+  pub daily_post_limit: Option<i32>,
+  pub daily_comment_limit: Option<i32>,
+  pub trusted_account_days: Option<i32>,
+  pub trusted_account_min_score: Option<i32>,
+  pub downvote_min_score: Option<i32>,
+  pub downvote_min_account_days: Option<i32>,
+  pub post_title_max_length: Option<i32>,
+  pub post_body_max_length: Option<i32>,
+  pub comment_max_length: Option<i32>,
+  /// Also return the full site data in [SiteResponse.site_response], so that it doesn't need to
+  /// be fetched separately after setup.
+  pub include_site_response: Option<bool>,
 }
 
 #[skip_serializing_none]
@@ -281,8 +530,35 @@ pub struct EditSite {
   pub content_warning: Option<String>,
   /// Default value for [LocalUser.post_listing_mode]
   pub default_post_listing_mode: Option<PostListingMode>,
+  /// Accounts younger than this many days are marked as new. Zero disables the marker.
+  pub new_user_badge_days: Option<i32>,
+  /// Reject posts with the same url in the same community within this many hours. Zero disables
+  /// the check.
+  pub duplicate_post_window_hours: Option<i32>,
+  /// Automatically lock posts in local communities after this many days. Zero disables it.
+  pub auto_lock_posts_after_days: Option<i32>,
+  /// Maximum number of posts a user can create within 24 hours. Zero disables the limit.
+  pub daily_post_limit: Option<i32>,
+  /// Maximum number of comments a user can create within 24 hours. Zero disables the limit.
+  pub daily_comment_limit: Option<i32>,
+  /// Accounts at least this many days old, and with at least `trusted_account_min_score`, get
+  /// higher daily post and comment limits. Zero disables it.
+  pub trusted_account_days: Option<i32>,
+  /// Minimum combined post and comment score for trusted accounts.
+  pub trusted_account_min_score: Option<i32>,
+  /// Minimum combined post and comment score a user needs to downvote. Zero disables the check.
+  pub downvote_min_score: Option<i32>,
+  /// Minimum account age in days a user needs to downvote. Zero disables the check.
+  pub downvote_min_account_days: Option<i32>,
+  /// Maximum length of post titles, between 3 and 200.
+  pub post_title_max_length: Option<i32>,
+  /// Maximum length of post bodies, up to 100000.
+  pub post_body_max_length: Option<i32>,
+  /// Maximum length of comments, up to 100000.
+  pub comment_max_length: Option<i32>,
 }
 
+#[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[cfg_attr(feature = "full", derive(TS))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -290,6 +566,11 @@ pub struct EditSite {
 pub struct SiteResponse {
   pub site_view: SiteView,
   pub taglines: Vec<Tagline>,
+  /// The names of the settings which were modified by an edit. Only returned by [EditSite].
+  pub changed_fields: Option<Vec<String>>,
+  /// The same data as returned by [GetSite]. Only returned by [CreateSite] if
+  /// [CreateSite.include_site_response] is set.
+  pub site_response: Option<Box<GetSiteResponse>>,
 }
 
 #[skip_serializing_none]
@@ -301,6 +582,8 @@ pub struct GetSiteResponse {
   pub site_view: SiteView,
   pub admins: Vec<PersonView>,
   pub version: String,
+  /// Version details of the server build, for feature detection.
+  pub build_info: BuildInfo,
   pub my_user: Option<MyUserInfo>,
   pub all_languages: Vec<Language>,
   pub discussion_languages: Vec<LanguageId>,
@@ -309,6 +592,34 @@ pub struct GetSiteResponse {
   /// A list of custom emojis your site supports.
   pub custom_emojis: Vec<CustomEmojiView>,
   pub blocked_urls: Vec<LocalSiteUrlBlocklist>,
+  pub federation_enabled: bool,
+  /// ActivityPub activity types which this instance sends and receives. Empty if federation is
+  /// disabled.
+  pub supported_activities: Vec<String>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Details about the build of the server.
+pub struct BuildInfo {
+  pub version: String,
+  /// Only known if the server was built from a git checkout.
+  pub git_commit: Option<String>,
+  /// Unix timestamp (in seconds) of the build.
+  pub build_timestamp: Option<String>,
+}
+
+impl Default for BuildInfo {
+  /// The build info of the running server.
+  fn default() -> Self {
+    Self {
+      version: VERSION.to_string(),
+      git_commit: GIT_COMMIT.map(ToString::to_string),
+      build_timestamp: BUILD_TIMESTAMP.map(ToString::to_string),
+    }
+  }
 }
 
 #[skip_serializing_none]
@@ -419,6 +730,29 @@ pub struct PurgeComment {
   pub reason: Option<String>,
 }
 
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Removes all posts and comments which match a search term, for cleaning up after spam waves.
+pub struct PurgeBySearch {
+  pub q: String,
+  /// Only Posts, Comments and All are supported. Defaults to All.
+  pub type_: Option<SearchType>,
+  pub reason: Option<String>,
+  /// Must be true, to prevent accidental mass removals.
+  pub confirm: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The number of items removed by a purge by search.
+pub struct PurgeBySearchResponse {
+  pub removed_posts: i64,
+  pub removed_comments: i64,
+}
+
 #[skip_serializing_none]
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "full", derive(TS))]
@@ -482,3 +816,126 @@ pub struct BlockInstance {
 pub struct BlockInstanceResponse {
   pub blocked: bool,
 }
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// List recent API requests which took longer than the slow request threshold. Admin only.
+pub struct GetSlowQueries {
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A single slow API request.
+pub struct SlowQuery {
+  /// The route pattern, eg `/api/v3/search`
+  pub path: String,
+  pub duration_ms: i64,
+  pub published: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The slow queries response, newest first.
+pub struct GetSlowQueriesResponse {
+  pub slow_queries: Vec<SlowQuery>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Get the number of new users, posts and comments over time. Admin only.
+///
+/// Defaults to daily counts for the last 30 days. The range can be at most one year.
+pub struct GetSiteStatsHistory {
+  pub granularity: Option<StatsGranularity>,
+  pub from: Option<DateTime<Utc>>,
+  pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The site stats history, oldest first. Periods without data are omitted.
+pub struct GetSiteStatsHistoryResponse {
+  pub history: Vec<SiteStatsHistory>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetches the admins and community moderators of the instance, for a staff page.
+pub struct GetModerationTeam {
+  /// Pages through the communities, not the moderators.
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The moderation team of the instance.
+pub struct GetModerationTeamResponse {
+  pub admins: Vec<PersonView>,
+  /// The moderators of a page of local communities, ordered by community.
+  pub community_moderators: Vec<CommunityModeratorView>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A random tagline of the site, for rotating it on the frontend.
+pub struct GetRandomTaglineResponse {
+  /// None if the site has no taglines.
+  pub tagline: Option<Tagline>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Current statistics of the instance. Admin only.
+pub struct GetSiteMetricsResponse {
+  pub users: i64,
+  pub posts: i64,
+  pub comments: i64,
+  pub communities: i64,
+  pub users_active_day: i64,
+  pub users_active_week: i64,
+  pub users_active_month: i64,
+  /// The number of known instances, not including this one.
+  pub federated_instances: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Fetch the nodeinfo of a known federated instance.
+pub struct GetInstanceInfo {
+  /// Example: lemmy.ml
+  pub domain: String,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// Nodeinfo of a federated instance. All fields besides the domain are optional, as nodeinfo is
+/// implemented differently by each fediverse software.
+pub struct GetInstanceInfoResponse {
+  pub domain: String,
+  pub software: Option<String>,
+  pub version: Option<String>,
+  pub users: Option<i64>,
+  pub users_active_month: Option<i64>,
+  pub users_active_half_year: Option<i64>,
+  pub local_posts: Option<i64>,
+  pub local_comments: Option<i64>,
+  pub open_registrations: Option<bool>,
+}
@@ -0,0 +1,64 @@
+use crate::site::SlowQuery;
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use std::{collections::VecDeque, sync::Mutex, time::Duration};
+
+/// Requests which take at least this long are recorded.
+const SLOW_QUERY_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// Maximum number of slow requests to keep in memory. Older entries are dropped first.
+const SLOW_QUERY_CAPACITY: usize = 100;
+
+static SLOW_QUERIES: Lazy<Mutex<VecDeque<SlowQuery>>> =
+  Lazy::new(|| Mutex::new(VecDeque::with_capacity(SLOW_QUERY_CAPACITY)));
+
+/// Records the duration of a request, if it exceeds the slow query threshold.
+pub fn record_request_duration(path: &str, duration: Duration) {
+  if duration < SLOW_QUERY_THRESHOLD {
+    return;
+  }
+  let Ok(mut slow_queries) = SLOW_QUERIES.lock() else {
+    return;
+  };
+  if slow_queries.len() >= SLOW_QUERY_CAPACITY {
+    slow_queries.pop_front();
+  }
+  slow_queries.push_back(SlowQuery {
+    path: path.to_string(),
+    duration_ms: i64::try_from(duration.as_millis()).unwrap_or(i64::MAX),
+    published: Utc::now(),
+  });
+}
+
+/// Returns the most recent slow requests, newest first.
+pub fn recent_slow_queries(limit: usize) -> Vec<SlowQuery> {
+  SLOW_QUERIES
+    .lock()
+    .map(|s| s.iter().rev().take(limit).cloned().collect())
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+  #![allow(clippy::indexing_slicing)]
+
+  use super::*;
+  use pretty_assertions::assert_eq;
+
+  #[test]
+  fn test_slow_query_log() {
+    record_request_duration("/api/v3/fast", Duration::from_millis(10));
+    for i in 0..SLOW_QUERY_CAPACITY + 5 {
+      record_request_duration(&format!("/api/v3/slow/{i}"), SLOW_QUERY_THRESHOLD);
+    }
+
+    let slow_queries = recent_slow_queries(SLOW_QUERY_CAPACITY * 2);
+    assert_eq!(SLOW_QUERY_CAPACITY, slow_queries.len());
+    assert_eq!(
+      format!("/api/v3/slow/{}", SLOW_QUERY_CAPACITY + 4),
+      slow_queries[0].path
+    );
+    assert!(slow_queries.iter().all(|s| s.path != "/api/v3/fast"));
+    assert_eq!(2, recent_slow_queries(2).len());
+  }
+}
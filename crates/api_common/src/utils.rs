@@ -1,16 +1,17 @@
 use crate::{
   context::LemmyContext,
   request::{delete_image_from_pictrs, purge_image_from_pictrs},
-  site::{FederatedInstances, InstanceWithFederationState},
+  site::{FederatedInstances, GetSiteResponse, InstanceWithFederationState},
 };
 use chrono::{DateTime, Days, Local, TimeZone, Utc};
 use enum_map::{enum_map, EnumMap};
 use lemmy_db_schema::{
-  newtypes::{CommunityId, DbUrl, InstanceId, PersonId, PostId},
+  newtypes::{CommunityFlairId, CommunityId, DbUrl, InstanceId, PersonId, PostId},
   source::{
     comment::{Comment, CommentUpdateForm},
     community::{Community, CommunityModerator, CommunityUpdateForm},
     community_block::CommunityBlock,
+    community_flair::CommunityFlair,
     email_verification::{EmailVerification, EmailVerificationForm},
     images::{LocalImage, RemoteImage},
     instance::Instance,
@@ -37,7 +38,10 @@ use lemmy_utils::{
   email::{send_email, translations::Lang},
   error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
   rate_limit::{ActionType, BucketConfig},
-  settings::structs::{PictrsImageMode, Settings},
+  settings::{
+    structs::{PictrsImageMode, Settings},
+    SETTINGS,
+  },
   utils::{
     markdown::{markdown_check_for_blocked_urls, markdown_rewrite_image_links},
     slurs::{build_slur_regex, remove_slurs},
@@ -48,13 +52,27 @@ use moka::future::Cache;
 use once_cell::sync::Lazy;
 use regex::{escape, Regex, RegexSet};
 use rosetta_i18n::{Language, LanguageId};
-use std::collections::HashSet;
+use std::{collections::HashSet, future::Future, time::Duration};
 use tracing::warn;
 use url::{ParseError, Url};
 use urlencoding::encode;
 
 pub static AUTH_COOKIE_NAME: &str = "jwt";
 
+/// The part of the GetSite response which is independent of the user account.
+pub static SITE_CACHE: Lazy<Cache<(), GetSiteResponse>> = Lazy::new(|| {
+  Cache::builder()
+    .max_capacity(1)
+    .time_to_live(Duration::from_secs(SETTINGS.site_cache_duration))
+    .build()
+});
+
+/// Drops the cached site data, so that the next GetSite request reads it from the database. Needs
+/// to be called after changing the site or its admins.
+pub async fn invalidate_site_cache() {
+  SITE_CACHE.invalidate(&()).await;
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn is_mod_or_admin(
   pool: &mut DbPool<'_>,
@@ -314,6 +332,28 @@ pub fn check_downvotes_enabled(score: i16, local_site: &LocalSite) -> LemmyResul
   }
 }
 
+/// Only allow downvotes from accounts which reached the minimum score and age of the site. A
+/// threshold of 0 disables that check, and admins are exempt.
+pub fn check_downvote_allowed(
+  score: i16,
+  local_user_view: &LocalUserView,
+  local_site: &LocalSite,
+) -> LemmyResult<()> {
+  if score != -1 || local_user_view.local_user.admin {
+    return Ok(());
+  }
+  let counts = &local_user_view.counts;
+  let below_min_score = local_site.downvote_min_score != 0
+    && counts.post_score + counts.comment_score < i64::from(local_site.downvote_min_score);
+  let account_age = Utc::now() - local_user_view.person.published;
+  let below_min_age = local_site.downvote_min_account_days != 0
+    && account_age.num_days() < i64::from(local_site.downvote_min_account_days);
+  if below_min_score || below_min_age {
+    Err(LemmyErrorType::InsufficientKarmaToDownvote)?
+  }
+  Ok(())
+}
+
 /// Dont allow bots to do certain actions, like voting
 #[tracing::instrument(skip_all)]
 pub fn check_bot_account(person: &Person) -> LemmyResult<()> {
@@ -336,6 +376,129 @@ pub fn check_private_instance(
   }
 }
 
+/// Rejects content which is marked as NSFW, if NSFW is disabled for the site.
+pub fn check_nsfw_allowed(nsfw: Option<bool>, local_site: &LocalSite) -> LemmyResult<()> {
+  if nsfw.unwrap_or_default() && !local_site.enable_nsfw {
+    Err(LemmyErrorType::NsfwNotAllowed)?
+  } else {
+    Ok(())
+  }
+}
+
+/// Makes sure that a flair which is assigned to a post belongs to the post's community.
+pub async fn check_flair_in_community(
+  flair_id: Option<CommunityFlairId>,
+  community_id: CommunityId,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()> {
+  if let Some(flair_id) = flair_id {
+    let flair = CommunityFlair::read(pool, flair_id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindFlair)?;
+    if flair.community_id != community_id {
+      Err(LemmyErrorType::FlairNotInCommunity)?
+    }
+  }
+  Ok(())
+}
+
+/// Trusted accounts get this many times the regular daily post and comment limits.
+const TRUSTED_ACCOUNT_QUOTA_MULTIPLIER: i32 = 5;
+
+/// Rejects new posts or comments from users who already reached the daily limit of the site.
+/// Admins and mods of the community are exempt, and trusted accounts get a higher limit.
+///
+/// `count_last_day` returns the number of items created in the last 24 hours, and the time of the
+/// oldest one. It is only awaited if the limit applies to the user.
+#[tracing::instrument(skip_all)]
+pub async fn check_daily_quota<E>(
+  daily_limit: i32,
+  count_last_day: impl Future<Output = Result<(i64, Option<DateTime<Utc>>), E>>,
+  local_user_view: &LocalUserView,
+  community_id: CommunityId,
+  local_site: &LocalSite,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<()>
+where
+  LemmyError: From<E>,
+{
+  if daily_limit <= 0
+    || CommunityView::is_mod_or_admin(pool, local_user_view.person.id, community_id).await?
+  {
+    return Ok(());
+  }
+  let limit = if is_trusted_account(local_user_view, local_site) {
+    daily_limit.saturating_mul(TRUSTED_ACCOUNT_QUOTA_MULTIPLIER)
+  } else {
+    daily_limit
+  };
+
+  let (count, oldest) = count_last_day.await?;
+  if count >= i64::from(limit) {
+    // Once the oldest item is more than a day old, the user is below the limit again
+    let reset_time = oldest
+      .unwrap_or_else(Utc::now)
+      .checked_add_days(Days::new(1))
+      .unwrap_or_else(Utc::now);
+    Err(LemmyErrorType::QuotaExceeded(reset_time.to_rfc3339()))?
+  }
+  Ok(())
+}
+
+fn is_trusted_account(local_user_view: &LocalUserView, local_site: &LocalSite) -> bool {
+  if local_site.trusted_account_days <= 0 {
+    return false;
+  }
+  let account_age = Utc::now() - local_user_view.person.published;
+  let counts = &local_user_view.counts;
+  account_age.num_days() >= i64::from(local_site.trusted_account_days)
+    && counts.post_score + counts.comment_score >= i64::from(local_site.trusted_account_min_score)
+}
+
+/// All ActivityPub activity types which are handled by lemmy_apub.
+const SUPPORTED_ACTIVITY_TYPES: [&str; 14] = [
+  "Accept", "Add", "Announce", "Block", "Create", "Delete", "Dislike", "Flag", "Follow", "Like",
+  "Lock", "Remove", "Undo", "Update",
+];
+
+/// The activity types which can be federated with this instance, depending on the site settings.
+pub fn supported_activity_types(local_site: &LocalSite) -> Vec<String> {
+  if !local_site.federation_enabled {
+    return vec![];
+  }
+  SUPPORTED_ACTIVITY_TYPES
+    .into_iter()
+    .filter(|a| local_site.enable_downvotes || *a != "Dislike")
+    .map(ToString::to_string)
+    .collect()
+}
+
+/// Returns the nsfw flag to store for a new or edited post. Posts in NSFW communities are always
+/// NSFW, regardless of what the client sent.
+///
+/// The flag is only checked against the site setting when it changes. This way posts in
+/// communities which were marked NSFW before it was disabled can still be edited, while new posts
+/// in those communities are rejected. Returns `None` if an edit doesn't change the flag.
+///
+/// * `orig_nsfw` - The current flag of the post, or `None` for a new post
+pub fn post_nsfw(
+  nsfw: Option<bool>,
+  orig_nsfw: Option<bool>,
+  community_nsfw: bool,
+  local_site: &LocalSite,
+) -> LemmyResult<Option<bool>> {
+  if nsfw.is_none() && orig_nsfw.is_some() {
+    return Ok(None);
+  }
+  let nsfw = nsfw.unwrap_or_default() || community_nsfw;
+  if orig_nsfw == Some(nsfw) {
+    Ok(None)
+  } else {
+    check_nsfw_allowed(Some(nsfw), local_site)?;
+    Ok(Some(nsfw))
+  }
+}
+
 #[tracing::instrument(skip_all)]
 pub async fn build_federated_instances(
   local_site: &LocalSite,
@@ -495,6 +658,7 @@ pub fn local_site_rate_limit_to_rate_limit_config(
     ActionType::Comment => (l.comment, l.comment_per_second),
     ActionType::Search => (l.search, l.search_per_second),
     ActionType::ImportUserSettings => (l.import_user_settings, l.import_user_settings_per_second),
+    ActionType::ExportUserData => (l.export_user_data, l.export_user_data_per_second),
   }
   .map(|_key, (capacity, secs_to_refill)| BucketConfig {
     capacity: u32::try_from(capacity).unwrap_or(0),
@@ -1040,6 +1204,15 @@ pub async fn proxy_image_link_opt_apub(
 mod tests {
 
   use super::*;
+  use chrono::TimeDelta;
+  use lemmy_db_schema::{
+    source::{
+      community::{CommunityInsertForm, CommunityModeratorForm},
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::PersonInsertForm,
+    },
+    traits::Joinable,
+  };
   use pretty_assertions::assert_eq;
   use serial_test::serial;
 
@@ -1060,6 +1233,57 @@ mod tests {
     assert!(honeypot_check(&Some("message".to_string())).is_err());
   }
 
+  #[test]
+  fn nsfw_allowed() {
+    let local_site = LocalSite {
+      enable_nsfw: false,
+      ..Default::default()
+    };
+    assert!(check_nsfw_allowed(None, &local_site).is_ok());
+    assert!(check_nsfw_allowed(Some(false), &local_site).is_ok());
+    assert!(check_nsfw_allowed(Some(true), &local_site).is_err());
+
+    let local_site = LocalSite {
+      enable_nsfw: true,
+      ..Default::default()
+    };
+    assert!(check_nsfw_allowed(Some(true), &local_site).is_ok());
+  }
+
+  #[test]
+  fn test_post_nsfw() -> LemmyResult<()> {
+    let local_site = LocalSite {
+      enable_nsfw: false,
+      ..Default::default()
+    };
+
+    // New posts in NSFW communities are always NSFW, so they are rejected while NSFW is disabled
+    assert_eq!(Some(false), post_nsfw(None, None, false, &local_site)?);
+    assert!(post_nsfw(Some(true), None, false, &local_site).is_err());
+    assert!(post_nsfw(Some(false), None, true, &local_site).is_err());
+
+    // Existing posts in NSFW communities can be edited as long as the flag isn't changed
+    assert_eq!(None, post_nsfw(None, Some(true), true, &local_site)?);
+    assert_eq!(None, post_nsfw(Some(false), Some(true), true, &local_site)?);
+    assert_eq!(None, post_nsfw(Some(true), Some(true), false, &local_site)?);
+    assert_eq!(
+      Some(false),
+      post_nsfw(Some(false), Some(true), false, &local_site)?
+    );
+    assert!(post_nsfw(Some(true), Some(false), false, &local_site).is_err());
+
+    let local_site = LocalSite {
+      enable_nsfw: true,
+      ..Default::default()
+    };
+    assert_eq!(Some(true), post_nsfw(Some(false), None, true, &local_site)?);
+    assert_eq!(
+      Some(true),
+      post_nsfw(Some(false), Some(false), true, &local_site)?
+    );
+    Ok(())
+  }
+
   #[test]
   fn test_limit_ban_term() {
     // Ban expires in past, should throw error
@@ -1081,6 +1305,183 @@ mod tests {
     );
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_check_daily_quota() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person_form = PersonInsertForm::test_form(instance.id, "quota_user");
+    let person = Person::create(pool, &person_form).await?;
+    let local_user_form = LocalUserInsertForm::test_form(person.id);
+    LocalUser::create(pool, &local_user_form, vec![]).await?;
+    let mut local_user_view = LocalUserView::read_person(pool, person.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPerson)?;
+    let community_form = CommunityInsertForm::builder()
+      .name("quota_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+
+    let oldest = Utc::now() - TimeDelta::try_hours(2).unwrap();
+    let count = |count: i64| async move { Ok::<_, LemmyError>((count, Some(oldest))) };
+    let local_site = LocalSite::default();
+
+    // A limit of 0 means no limit
+    check_daily_quota(
+      0,
+      count(100),
+      &local_user_view,
+      community.id,
+      &local_site,
+      pool,
+    )
+    .await?;
+    check_daily_quota(
+      2,
+      count(1),
+      &local_user_view,
+      community.id,
+      &local_site,
+      pool,
+    )
+    .await?;
+    let err = check_daily_quota(
+      2,
+      count(2),
+      &local_user_view,
+      community.id,
+      &local_site,
+      pool,
+    )
+    .await
+    .err()
+    .map(|e| e.error_type);
+    let reset_time = oldest.checked_add_days(Days::new(1)).unwrap();
+    assert_eq!(
+      Some(LemmyErrorType::QuotaExceeded(reset_time.to_rfc3339())),
+      err
+    );
+
+    // Trusted accounts get a higher limit, once they are old enough and have enough score
+    let local_site = LocalSite {
+      trusted_account_days: 7,
+      trusted_account_min_score: 10,
+      ..Default::default()
+    };
+    local_user_view.counts.post_score = 20;
+    assert!(check_daily_quota(
+      2,
+      count(2),
+      &local_user_view,
+      community.id,
+      &local_site,
+      pool
+    )
+    .await
+    .is_err());
+    local_user_view.person.published = Utc::now() - TimeDelta::try_days(8).unwrap();
+    assert!(check_daily_quota(
+      2,
+      count(2),
+      &local_user_view,
+      community.id,
+      &local_site,
+      pool
+    )
+    .await
+    .is_ok());
+    assert!(check_daily_quota(
+      2,
+      count(10),
+      &local_user_view,
+      community.id,
+      &local_site,
+      pool
+    )
+    .await
+    .is_err());
+    local_user_view.counts.post_score = 5;
+    assert!(check_daily_quota(
+      2,
+      count(2),
+      &local_user_view,
+      community.id,
+      &local_site,
+      pool
+    )
+    .await
+    .is_err());
+
+    // Mods of the community are exempt
+    let moderator_form = CommunityModeratorForm {
+      community_id: community.id,
+      person_id: person.id,
+    };
+    CommunityModerator::join(pool, &moderator_form).await?;
+    assert!(check_daily_quota(
+      2,
+      count(100),
+      &local_user_view,
+      community.id,
+      &local_site,
+      pool
+    )
+    .await
+    .is_ok());
+
+    Community::delete(pool, community.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_downvote_allowed() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person_form = PersonInsertForm::test_form(instance.id, "downvoter");
+    let person = Person::create(pool, &person_form).await?;
+    let local_user_form = LocalUserInsertForm::test_form(person.id);
+    LocalUser::create(pool, &local_user_form, vec![]).await?;
+    let mut local_user_view = LocalUserView::read_person(pool, person.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindPerson)?;
+    local_user_view.counts.post_score = -5;
+    local_user_view.counts.comment_score = 0;
+
+    // Thresholds of 0 never block downvotes, even with a negative score
+    let local_site = LocalSite::default();
+    assert!(check_downvote_allowed(-1, &local_user_view, &local_site).is_ok());
+
+    let local_site = LocalSite {
+      downvote_min_score: 10,
+      ..Default::default()
+    };
+    assert!(check_downvote_allowed(-1, &local_user_view, &local_site).is_err());
+    assert!(check_downvote_allowed(1, &local_user_view, &local_site).is_ok());
+    local_user_view.counts.comment_score = 15;
+    assert!(check_downvote_allowed(-1, &local_user_view, &local_site).is_ok());
+
+    // The account was just created, so it fails any minimum age
+    let local_site = LocalSite {
+      downvote_min_account_days: 7,
+      ..Default::default()
+    };
+    assert!(check_downvote_allowed(-1, &local_user_view, &local_site).is_err());
+    local_user_view.local_user.admin = true;
+    assert!(check_downvote_allowed(-1, &local_user_view, &local_site).is_ok());
+
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_proxy_image_link() {
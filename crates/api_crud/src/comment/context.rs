@@ -0,0 +1,76 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  comment::{GetCommentContext, GetCommentContextResponse},
+  context::LemmyContext,
+  utils::check_private_instance,
+};
+use lemmy_db_schema::source::local_site::LocalSite;
+use lemmy_db_views::{
+  comment_view::CommentQuery,
+  structs::{CommentView, LocalUserView},
+};
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+const DEFAULT_CONTEXT_DEPTH: i32 = 3;
+const MAX_CONTEXT_DEPTH: i32 = 20;
+
+#[tracing::instrument(skip(context))]
+pub async fn get_comment_context(
+  data: Query<GetCommentContext>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<GetCommentContextResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let person_id = local_user_view.as_ref().map(|l| l.person.id);
+  let comment_view = CommentView::read(&mut context.pool(), data.comment_id, person_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindComment)?;
+
+  // Only the closest parents are returned, the comment path lists them from the top down
+  let context_depth = data
+    .context_depth
+    .unwrap_or(DEFAULT_CONTEXT_DEPTH)
+    .clamp(0, MAX_CONTEXT_DEPTH) as usize;
+  let ancestor_ids = comment_view.comment.ancestor_comment_ids();
+  let skip = ancestor_ids.len().saturating_sub(context_depth);
+  let ancestor_ids = ancestor_ids.into_iter().skip(skip).collect::<Vec<_>>();
+  let mut ancestors = if ancestor_ids.is_empty() {
+    vec![]
+  } else {
+    // The ancestors are in the same post as the comment, which the user is allowed to see, so
+    // they are returned like the comment itself, regardless of blocks and language settings
+    CommentQuery {
+      comment_ids: Some(ancestor_ids.clone()),
+      local_user: local_user_view.as_ref(),
+      skip_viewer_filters: true,
+      limit: Some(MAX_CONTEXT_DEPTH.into()),
+      ..Default::default()
+    }
+    .list(&mut context.pool())
+    .await?
+  };
+  ancestors.sort_by_key(|a| ancestor_ids.iter().position(|id| *id == a.comment.id));
+
+  let replies = CommentQuery {
+    post_id: Some(comment_view.post.id),
+    parent_path: Some(comment_view.comment.path.clone()),
+    max_depth: Some(1),
+    local_user: local_user_view.as_ref(),
+    ..Default::default()
+  }
+  .list(&mut context.pool())
+  .await?
+  .into_iter()
+  // The query also returns the comment itself
+  .filter(|c| c.comment.id != comment_view.comment.id)
+  .collect();
+
+  Ok(Json(GetCommentContextResponse {
+    ancestors,
+    comment_view,
+    replies,
+  }))
+}
@@ -7,6 +7,7 @@ use lemmy_api_common::{
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
     check_community_user_action,
+    check_daily_quota,
     check_post_deleted_or_removed,
     generate_local_apub_endpoint,
     get_post,
@@ -31,7 +32,7 @@ use lemmy_db_schema::{
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::{
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
-  utils::{mention::scrape_text_for_mentions, validation::is_valid_body_field},
+  utils::{mention::scrape_text_for_mentions, validation::is_valid_content_length},
 };
 
 const MAX_COMMENT_DEPTH_LIMIT: usize = 100;
@@ -47,7 +48,10 @@ pub async fn create_comment(
   let slur_regex = local_site_to_slur_regex(&local_site);
   let url_blocklist = get_url_blocklist(&context).await?;
   let content = process_markdown(&data.content, &slur_regex, &url_blocklist, &context).await?;
-  is_valid_body_field(&Some(content.clone()), false)?;
+  is_valid_content_length(
+    &Some(content.clone()),
+    usize::try_from(local_site.comment_max_length)?,
+  )?;
 
   // Check for a community ban
   let post_id = data.post_id;
@@ -56,6 +60,15 @@ pub async fn create_comment(
 
   check_community_user_action(&local_user_view.person, community_id, &mut context.pool()).await?;
   check_post_deleted_or_removed(&post)?;
+  check_daily_quota(
+    local_site.daily_comment_limit,
+    Comment::count_last_day(&mut context.pool(), local_user_view.person.id),
+    &local_user_view,
+    community_id,
+    &local_site,
+    &mut context.pool(),
+  )
+  .await?;
 
   // Check if post is locked, no new comments
   let is_mod_or_admin = is_mod_or_admin(&mut context.pool(), &local_user_view.person, community_id)
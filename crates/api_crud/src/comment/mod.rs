@@ -1,3 +1,4 @@
+pub mod context;
 pub mod create;
 pub mod delete;
 pub mod read;
@@ -24,7 +24,7 @@ use lemmy_db_schema::{
 use lemmy_db_views::structs::{CommentView, LocalUserView};
 use lemmy_utils::{
   error::{LemmyErrorExt, LemmyErrorType, LemmyResult},
-  utils::{mention::scrape_text_for_mentions, validation::is_valid_body_field},
+  utils::{mention::scrape_text_for_mentions, validation::is_valid_content_length},
 };
 
 #[tracing::instrument(skip(context))]
@@ -63,7 +63,7 @@ pub async fn update_comment(
   let slur_regex = local_site_to_slur_regex(&local_site);
   let url_blocklist = get_url_blocklist(&context).await?;
   let content = process_markdown_opt(&data.content, &slur_regex, &url_blocklist, &context).await?;
-  is_valid_body_field(&content, false)?;
+  is_valid_content_length(&content, usize::try_from(local_site.comment_max_length)?)?;
 
   let comment_id = data.comment_id;
   let form = CommentUpdateForm {
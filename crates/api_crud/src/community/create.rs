@@ -5,6 +5,7 @@ use lemmy_api_common::{
   community::{CommunityResponse, CreateCommunity},
   context::LemmyContext,
   utils::{
+    check_nsfw_allowed,
     generate_followers_url,
     generate_inbox_url,
     generate_local_apub_endpoint,
@@ -55,22 +56,25 @@ pub async fn create_community(
     Err(LemmyErrorType::OnlyAdminsCanCreateCommunities)?
   }
 
+  // Surrounding whitespace is dropped, the same way as in ValidateCommunityName
+  let name = data.name.trim().to_string();
   let slur_regex = local_site_to_slur_regex(&local_site);
   let url_blocklist = get_url_blocklist(&context).await?;
-  check_slurs(&data.name, &slur_regex)?;
+  check_slurs(&name, &slur_regex)?;
   check_slurs(&data.title, &slur_regex)?;
   let description =
     process_markdown_opt(&data.description, &slur_regex, &url_blocklist, &context).await?;
   let icon = proxy_image_link_api(&data.icon, &context).await?;
   let banner = proxy_image_link_api(&data.banner, &context).await?;
 
-  is_valid_actor_name(&data.name, local_site.actor_name_max_length as usize)?;
+  is_valid_actor_name(&name, local_site.actor_name_max_length as usize)?;
   is_valid_body_field(&data.description, false)?;
+  check_nsfw_allowed(data.nsfw, &local_site)?;
 
   // Double check for duplicate community actor_ids
   let community_actor_id = generate_local_apub_endpoint(
     EndpointType::Community,
-    &data.name,
+    &name,
     &context.settings().get_protocol_and_hostname(),
   )?;
   let community_dupe =
@@ -83,7 +87,7 @@ pub async fn create_community(
   let keypair = generate_actor_keypair()?;
 
   let community_form = CommunityInsertForm::builder()
-    .name(data.name.clone())
+    .name(name)
     .title(data.title.clone())
     .description(description)
     .icon(icon)
@@ -98,6 +102,8 @@ pub async fn create_community(
     .posting_restricted_to_mods(data.posting_restricted_to_mods)
     .instance_id(site_view.site.instance_id)
     .visibility(data.visibility)
+    .default_sort_type(data.default_sort_type)
+    .hide_scores_until_hours(data.hide_scores_until_hours.filter(|h| *h > 0))
     .build();
 
   let inserted_community = Community::create(&mut context.pool(), &community_form)
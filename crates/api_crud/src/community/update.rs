@@ -8,6 +8,7 @@ use lemmy_api_common::{
   send_activity::{ActivityChannel, SendActivityData},
   utils::{
     check_community_mod_action,
+    check_nsfw_allowed,
     get_url_blocklist,
     local_site_to_slur_regex,
     process_markdown_opt,
@@ -21,7 +22,7 @@ use lemmy_db_schema::{
     local_site::LocalSite,
   },
   traits::Crud,
-  utils::{diesel_option_overwrite, naive_now},
+  utils::{diesel_option_overwrite, diesel_option_overwrite_or_clear, naive_now},
 };
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::{
@@ -46,6 +47,10 @@ pub async fn update_community(
   let old_community = Community::read(&mut context.pool(), data.community_id)
     .await?
     .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+  // Communities which were marked NSFW before it was disabled can still be edited
+  if data.nsfw != Some(old_community.nsfw) {
+    check_nsfw_allowed(data.nsfw, &local_site)?;
+  }
   replace_image(&data.icon, &old_community.icon, &context).await?;
   replace_image(&data.banner, &old_community.banner, &context).await?;
 
@@ -82,6 +87,13 @@ pub async fn update_community(
     nsfw: data.nsfw,
     posting_restricted_to_mods: data.posting_restricted_to_mods,
     visibility: data.visibility,
+    default_sort_type: diesel_option_overwrite_or_clear(
+      data.default_sort_type,
+      data.clear_default_sort_type,
+    ),
+    hide_scores_until_hours: data
+      .hide_scores_until_hours
+      .map(|h| Some(h).filter(|h| *h > 0)),
     updated: Some(Some(naive_now())),
     ..Default::default()
   };
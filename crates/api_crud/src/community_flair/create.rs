@@ -0,0 +1,52 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{CommunityFlairResponse, CreateCommunityFlair},
+  context::LemmyContext,
+  utils::{check_community_mod_action, local_site_to_slur_regex},
+};
+use lemmy_db_schema::{
+  source::{
+    community_flair::{CommunityFlair, CommunityFlairInsertForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::map_unique_violation,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  utils::{slurs::check_slurs, validation::is_valid_flair_name},
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn create_community_flair(
+  data: Json<CreateCommunityFlair>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommunityFlairResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let name = data.name.trim().to_string();
+  is_valid_flair_name(&name)?;
+  check_slurs(&name, &local_site_to_slur_regex(&local_site))?;
+
+  // Only mods can manage the flairs of a community
+  check_community_mod_action(
+    &local_user_view.person,
+    data.community_id,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
+
+  let form = CommunityFlairInsertForm {
+    community_id: data.community_id,
+    name,
+  };
+  let flair = map_unique_violation(
+    CommunityFlair::create(&mut context.pool(), &form).await,
+    LemmyErrorType::FlairAlreadyExists,
+  )?;
+
+  Ok(Json(CommunityFlairResponse { flair }))
+}
@@ -0,0 +1,36 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::DeleteCommunityFlair,
+  context::LemmyContext,
+  utils::check_community_mod_action,
+  SuccessResponse,
+};
+use lemmy_db_schema::{source::community_flair::CommunityFlair, traits::Crud};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+
+#[tracing::instrument(skip(context))]
+pub async fn delete_community_flair(
+  data: Json<DeleteCommunityFlair>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<SuccessResponse>> {
+  let flair = CommunityFlair::read(&mut context.pool(), data.flair_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindFlair)?;
+
+  // Only mods can manage the flairs of a community
+  check_community_mod_action(
+    &local_user_view.person,
+    flair.community_id,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
+
+  // Posts which use this flair are kept, their flair is removed by the database
+  CommunityFlair::delete(&mut context.pool(), data.flair_id).await?;
+
+  Ok(Json(SuccessResponse::default()))
+}
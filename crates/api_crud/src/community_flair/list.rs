@@ -0,0 +1,23 @@
+use actix_web::web::{Data, Json, Query};
+use lemmy_api_common::{
+  community::{ListCommunityFlairs, ListCommunityFlairsResponse},
+  context::LemmyContext,
+  utils::check_private_instance,
+};
+use lemmy_db_schema::source::{community_flair::CommunityFlair, local_site::LocalSite};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::LemmyResult;
+
+#[tracing::instrument(skip(context))]
+pub async fn list_community_flairs(
+  data: Query<ListCommunityFlairs>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<ListCommunityFlairsResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let flairs = CommunityFlair::list_for_community(&mut context.pool(), data.community_id).await?;
+
+  Ok(Json(ListCommunityFlairsResponse { flairs }))
+}
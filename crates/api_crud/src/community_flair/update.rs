@@ -0,0 +1,56 @@
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use lemmy_api_common::{
+  community::{CommunityFlairResponse, EditCommunityFlair},
+  context::LemmyContext,
+  utils::{check_community_mod_action, local_site_to_slur_regex},
+};
+use lemmy_db_schema::{
+  source::{
+    community_flair::{CommunityFlair, CommunityFlairUpdateForm},
+    local_site::LocalSite,
+  },
+  traits::Crud,
+  utils::{map_unique_violation, naive_now},
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::{
+  error::{LemmyErrorType, LemmyResult},
+  utils::{slurs::check_slurs, validation::is_valid_flair_name},
+};
+
+#[tracing::instrument(skip(context))]
+pub async fn update_community_flair(
+  data: Json<EditCommunityFlair>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<CommunityFlairResponse>> {
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  let name = data.name.trim().to_string();
+  is_valid_flair_name(&name)?;
+  check_slurs(&name, &local_site_to_slur_regex(&local_site))?;
+
+  let orig_flair = CommunityFlair::read(&mut context.pool(), data.flair_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindFlair)?;
+
+  // Only mods can manage the flairs of a community
+  check_community_mod_action(
+    &local_user_view.person,
+    orig_flair.community_id,
+    false,
+    &mut context.pool(),
+  )
+  .await?;
+
+  let form = CommunityFlairUpdateForm {
+    name: Some(name),
+    updated: Some(Some(naive_now())),
+  };
+  let flair = map_unique_violation(
+    CommunityFlair::update(&mut context.pool(), data.flair_id, &form).await,
+    LemmyErrorType::FlairAlreadyExists,
+  )?;
+
+  Ok(Json(CommunityFlairResponse { flair }))
+}
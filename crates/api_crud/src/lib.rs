@@ -1,5 +1,6 @@
 pub mod comment;
 pub mod community;
+pub mod community_flair;
 pub mod custom_emoji;
 pub mod post;
 pub mod private_message;
@@ -8,11 +8,14 @@ use lemmy_api_common::{
   send_activity::SendActivityData,
   utils::{
     check_community_user_action,
+    check_daily_quota,
+    check_flair_in_community,
     generate_local_apub_endpoint,
     get_url_blocklist,
     honeypot_check,
     local_site_to_slur_regex,
     mark_post_as_read,
+    post_nsfw,
     process_markdown_opt,
     proxy_image_link_opt_apub,
     EndpointType,
@@ -20,6 +23,7 @@ use lemmy_api_common::{
 };
 use lemmy_db_schema::{
   impls::actor_language::default_post_language,
+  newtypes::{CommunityId, DbUrl},
   source::{
     actor_language::CommunityLanguage,
     community::Community,
@@ -41,7 +45,7 @@ use lemmy_utils::{
       clean_url_params,
       is_url_blocked,
       is_valid_alt_text_field,
-      is_valid_body_field,
+      is_valid_content_length,
       is_valid_post_title,
     },
   },
@@ -69,8 +73,11 @@ pub async fn create_post(
   let url = data_url.map(clean_url_params); // TODO no good way to handle a "clear"
   let custom_thumbnail = data.custom_thumbnail.as_ref().map(clean_url_params);
 
-  is_valid_post_title(&data.name)?;
-  is_valid_body_field(&body, true)?;
+  is_valid_post_title(
+    &data.name,
+    usize::try_from(local_site.post_title_max_length)?,
+  )?;
+  is_valid_content_length(&body, usize::try_from(local_site.post_body_max_length)?)?;
   is_valid_alt_text_field(&data.alt_text)?;
   is_url_blocked(&url, &url_blocklist)?;
   check_url_scheme(&url)?;
@@ -83,6 +90,15 @@ pub async fn create_post(
     &mut context.pool(),
   )
   .await?;
+  check_daily_quota(
+    local_site.daily_post_limit,
+    Post::count_last_day(&mut context.pool(), local_user_view.person.id),
+    &local_user_view,
+    data.community_id,
+    &local_site,
+    &mut context.pool(),
+  )
+  .await?;
 
   let community_id = data.community_id;
   let community = Community::read(&mut context.pool(), community_id)
@@ -101,6 +117,13 @@ pub async fn create_post(
     }
   }
 
+  let nsfw = post_nsfw(data.nsfw, None, community.nsfw, &local_site)?;
+  check_flair_in_community(data.flair_id, community_id, &mut context.pool()).await?;
+
+  if let (Some(url), false) = (&url, data.allow_duplicate.unwrap_or_default()) {
+    check_duplicate_post(&local_site, community_id, url, &context).await?;
+  }
+
   // Only need to check if language is allowed in case user set it explicitly. When using default
   // language, it already only returns allowed languages.
   CommunityLanguage::is_allowed_community_language(
@@ -130,8 +153,9 @@ pub async fn create_post(
     .alt_text(data.alt_text.clone())
     .community_id(data.community_id)
     .creator_id(local_user_view.person.id)
-    .nsfw(data.nsfw)
+    .nsfw(nsfw)
     .language_id(language_id)
+    .flair_id(data.flair_id)
     .build();
 
   let inserted_post = Post::create(&mut context.pool(), &post_form)
@@ -201,3 +225,106 @@ pub async fn create_post(
 
   build_post_response(&context, community_id, &local_user_view.person, post_id).await
 }
+
+/// Rejects a post if the same url was posted to the community within the configured window.
+async fn check_duplicate_post(
+  local_site: &LocalSite,
+  community_id: CommunityId,
+  url: &DbUrl,
+  context: &LemmyContext,
+) -> LemmyResult<()> {
+  if local_site.duplicate_post_window_hours <= 0 {
+    return Ok(());
+  }
+  let duplicate = Post::read_duplicate(
+    &mut context.pool(),
+    community_id,
+    url,
+    local_site.duplicate_post_window_hours,
+  )
+  .await?;
+  if let Some(duplicate) = duplicate {
+    Err(LemmyErrorType::DuplicatePost(duplicate.id.0))?
+  }
+  Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+
+  use crate::post::create::check_duplicate_post;
+  use lemmy_api_common::context::LemmyContext;
+  use lemmy_db_schema::{
+    newtypes::DbUrl,
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      local_site::{LocalSite, LocalSiteInsertForm},
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      site::{Site, SiteInsertForm},
+    },
+    traits::Crud,
+  };
+  use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+  use url::Url;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_check_duplicate_post() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(instance.id)
+      .build();
+    let site = Site::create(pool, &site_form).await?;
+    let local_site_form = LocalSiteInsertForm::builder()
+      .site_id(site.id)
+      .duplicate_post_window_hours(Some(24))
+      .build();
+    let mut local_site = LocalSite::create(pool, &local_site_form).await?;
+
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "duplicate_poster"),
+    )
+    .await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("duplicate_posts".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+
+    let url: DbUrl = Url::parse("https://example.com/duplicate")?.into();
+    check_duplicate_post(&local_site, community.id, &url, &context).await?;
+
+    let post_form = PostInsertForm::builder()
+      .name("duplicate".into())
+      .url(Some(url.clone()))
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+    let res = check_duplicate_post(&local_site, community.id, &url, &context).await;
+    assert_eq!(
+      Some(LemmyErrorType::DuplicatePost(post.id.0)),
+      res.err().map(|e| e.error_type)
+    );
+
+    // A window of zero turns the check off
+    local_site.duplicate_post_window_hours = 0;
+    check_duplicate_post(&local_site, community.id, &url, &context).await?;
+
+    Site::delete(pool, site.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -8,8 +8,10 @@ use lemmy_api_common::{
   send_activity::SendActivityData,
   utils::{
     check_community_user_action,
+    check_flair_in_community,
     get_url_blocklist,
     local_site_to_slur_regex,
+    post_nsfw,
     process_markdown_opt,
     proxy_image_link_opt_apub,
   },
@@ -17,11 +19,12 @@ use lemmy_api_common::{
 use lemmy_db_schema::{
   source::{
     actor_language::CommunityLanguage,
+    community::Community,
     local_site::LocalSite,
     post::{Post, PostUpdateForm},
   },
   traits::Crud,
-  utils::{diesel_option_overwrite, naive_now},
+  utils::{diesel_option_overwrite, diesel_option_overwrite_or_clear, naive_now},
 };
 use lemmy_db_views::structs::LocalUserView;
 use lemmy_utils::{
@@ -33,7 +36,7 @@ use lemmy_utils::{
       clean_url_params,
       is_url_blocked,
       is_valid_alt_text_field,
-      is_valid_body_field,
+      is_valid_content_length,
       is_valid_post_title,
     },
   },
@@ -60,10 +63,10 @@ pub async fn update_post(
   let body = process_markdown_opt(&data.body, &slur_regex, &url_blocklist, &context).await?;
 
   if let Some(name) = &data.name {
-    is_valid_post_title(name)?;
+    is_valid_post_title(name, usize::try_from(local_site.post_title_max_length)?)?;
   }
 
-  is_valid_body_field(&body, true)?;
+  is_valid_content_length(&body, usize::try_from(local_site.post_body_max_length)?)?;
   is_valid_alt_text_field(&data.alt_text)?;
   is_url_blocked(&url, &url_blocklist)?;
   check_url_scheme(&url)?;
@@ -86,6 +89,11 @@ pub async fn update_post(
     Err(LemmyErrorType::NoPostEditAllowed)?
   }
 
+  let community = Community::read(&mut context.pool(), orig_post.community_id)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindCommunity)?;
+  let nsfw = post_nsfw(data.nsfw, Some(orig_post.nsfw), community.nsfw, &local_site)?;
+
   let url = match url {
     Some(url) => Some(proxy_image_link_opt_apub(Some(url), &context).await?),
     _ => Default::default(),
@@ -98,14 +106,16 @@ pub async fn update_post(
     orig_post.community_id,
   )
   .await?;
+  check_flair_in_community(data.flair_id, orig_post.community_id, &mut context.pool()).await?;
 
   let post_form = PostUpdateForm {
     name: data.name.clone(),
     url,
     body: diesel_option_overwrite(body),
     alt_text: diesel_option_overwrite(data.alt_text.clone()),
-    nsfw: data.nsfw,
+    nsfw,
     language_id: data.language_id,
+    flair_id: diesel_option_overwrite_or_clear(data.flair_id, data.clear_flair),
     updated: Some(Some(naive_now())),
     ..Default::default()
   };
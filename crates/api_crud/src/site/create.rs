@@ -1,4 +1,8 @@
-use crate::site::{application_question_check, site_default_post_listing_type_check};
+use crate::site::{
+  application_question_check,
+  read::build_site_response,
+  site_default_post_listing_type_check,
+};
 use activitypub_federation::http_signatures::generate_actor_keypair;
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
@@ -33,7 +37,9 @@ use lemmy_utils::{
     validation::{
       build_and_check_regex,
       check_site_visibility_valid,
+      content_length_limits_check,
       is_valid_body_field,
+      is_valid_site_image_url,
       site_description_length_check,
       site_name_length_check,
     },
@@ -106,6 +112,18 @@ pub async fn create_site(
     captcha_enabled: data.captcha_enabled,
     captcha_difficulty: data.captcha_difficulty.clone(),
     default_post_listing_mode: data.default_post_listing_mode,
+    new_user_badge_days: data.new_user_badge_days,
+    duplicate_post_window_hours: data.duplicate_post_window_hours,
+    auto_lock_posts_after_days: data.auto_lock_posts_after_days,
+    daily_post_limit: data.daily_post_limit,
+    daily_comment_limit: data.daily_comment_limit,
+    trusted_account_days: data.trusted_account_days,
+    trusted_account_min_score: data.trusted_account_min_score,
+    downvote_min_score: data.downvote_min_score,
+    downvote_min_account_days: data.downvote_min_account_days,
+    post_title_max_length: data.post_title_max_length,
+    post_body_max_length: data.post_body_max_length,
+    comment_max_length: data.comment_max_length,
     ..Default::default()
   };
 
@@ -140,9 +158,17 @@ pub async fn create_site(
     local_site_rate_limit_to_rate_limit_config(&site_view.local_site_rate_limit);
   context.rate_limit_cell().set_config(rate_limit_config);
 
+  let site_response = if data.include_site_response.unwrap_or_default() {
+    Some(Box::new(build_site_response(&context).await?.0))
+  } else {
+    None
+  };
+
   Ok(Json(SiteResponse {
     site_view,
     taglines,
+    changed_fields: None,
+    site_response,
   }))
 }
 
@@ -181,6 +207,15 @@ fn validate_create_payload(local_site: &LocalSite, create_site: &CreateSite) ->
   // Ensure that the sidebar has fewer than the max num characters...
   is_valid_body_field(&create_site.sidebar, false)?;
 
+  content_length_limits_check(
+    create_site.post_title_max_length,
+    create_site.post_body_max_length,
+    create_site.comment_max_length,
+  )?;
+
+  is_valid_site_image_url(&create_site.icon, LemmyErrorType::InvalidIconUrl)?;
+  is_valid_site_image_url(&create_site.banner, LemmyErrorType::InvalidBannerUrl)?;
+
   application_question_check(
     &local_site.application_question,
     &create_site.application_question,
@@ -229,7 +264,7 @@ mod tests {
       ),
       (
         "CreateSite name matches LocalSite slur filter",
-        LemmyErrorType::Slurs,
+        LemmyErrorType::Slurs(vec!["foo".to_string()]),
         &generate_local_site(
           false,
           Some(String::from("(foo|bar)")),
@@ -253,7 +288,7 @@ mod tests {
       ),
       (
         "CreateSite name matches new slur filter",
-        LemmyErrorType::Slurs,
+        LemmyErrorType::Slurs(vec!["zeta".to_string()]),
         &generate_local_site(
           false,
           Some(String::from("(foo|bar)")),
@@ -590,6 +625,19 @@ mod tests {
       registration_mode: site_registration_mode,
       content_warning: None,
       default_post_listing_mode: None,
+      new_user_badge_days: None,
+      duplicate_post_window_hours: None,
+      auto_lock_posts_after_days: None,
+      daily_post_limit: None,
+      daily_comment_limit: None,
+      trusted_account_days: None,
+      trusted_account_min_score: None,
+      downvote_min_score: None,
+      downvote_min_account_days: None,
+      post_title_max_length: None,
+      post_body_max_length: None,
+      comment_max_length: None,
+      include_site_response: None,
     }
   }
 }
@@ -1,12 +1,14 @@
 use actix_web::web::{Data, Json};
 use lemmy_api_common::{
   context::LemmyContext,
-  site::{GetSiteResponse, MyUserInfo},
+  site::{BuildInfo, GetSiteResponse, MyUserInfo},
+  utils::{supported_activity_types, SITE_CACHE},
 };
 use lemmy_db_schema::source::{
   actor_language::{LocalUserLanguage, SiteLanguage},
   language::Language,
   local_site_url_blocklist::LocalSiteUrlBlocklist,
+  local_user::LocalUser,
   tagline::Tagline,
 };
 use lemmy_db_views::structs::{CustomEmojiView, LocalUserView, SiteView};
@@ -20,58 +22,48 @@ use lemmy_db_views_actor::structs::{
 };
 use lemmy_utils::{
   error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
-  CACHE_DURATION_API,
   VERSION,
 };
-use moka::future::Cache;
-use once_cell::sync::Lazy;
+use std::fmt::Display;
+use tracing::warn;
 
 #[tracing::instrument(skip(context))]
 pub async fn get_site(
   local_user_view: Option<LocalUserView>,
   context: Data<LemmyContext>,
 ) -> LemmyResult<Json<GetSiteResponse>> {
-  static CACHE: Lazy<Cache<(), GetSiteResponse>> = Lazy::new(|| {
-    Cache::builder()
-      .max_capacity(1)
-      .time_to_live(CACHE_DURATION_API)
-      .build()
-  });
-
   // This data is independent from the user account so we can cache it across requests
-  let mut site_response = CACHE
-    .try_get_with::<_, LemmyError>((), async {
-      let site_view = SiteView::read_local(&mut context.pool())
-        .await?
-        .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
-      let admins = PersonView::admins(&mut context.pool()).await?;
-      let all_languages = Language::read_all(&mut context.pool()).await?;
-      let discussion_languages = SiteLanguage::read_local_raw(&mut context.pool()).await?;
-      let taglines = Tagline::get_all(&mut context.pool(), site_view.local_site.id).await?;
-      let custom_emojis =
-        CustomEmojiView::get_all(&mut context.pool(), site_view.local_site.id).await?;
-      let blocked_urls = LocalSiteUrlBlocklist::get_all(&mut context.pool()).await?;
-      Ok(GetSiteResponse {
-        site_view,
-        admins,
-        version: VERSION.to_string(),
-        my_user: None,
-        all_languages,
-        discussion_languages,
-        taglines,
-        custom_emojis,
-        blocked_urls,
-      })
+  let mut site_response = match SITE_CACHE
+    .try_get_with((), async {
+      match build_site_response(&context).await {
+        Ok((site_response, false)) => Ok(site_response),
+        // Returned as error so that it isn't cached, and the missing data is read again on the
+        // next request
+        Ok((site_response, true)) => Err(SiteResponseError::Partial(Box::new(site_response))),
+        Err(e) => Err(SiteResponseError::Failed(e)),
+      }
     })
     .await
-    .map_err(|e| anyhow::anyhow!("Failed to construct site response: {e}"))?;
+  {
+    Ok(site_response) => site_response,
+    Err(e) => match e.as_ref() {
+      SiteResponseError::Partial(site_response) => (**site_response).clone(),
+      SiteResponseError::Failed(e) => return Err(e.error_type.clone().into()),
+    },
+  };
 
   // Build the local user with parallel queries and add it to site response
-  site_response.my_user = if let Some(local_user_view) = local_user_view {
+  site_response.my_user = if let Some(mut local_user_view) = local_user_view {
     let person_id = local_user_view.person.id;
     let local_user_id = local_user_view.local_user.id;
     let pool = &mut context.pool();
 
+    // Record the visit, so that content which is new since the previous visit can be listed
+    match LocalUser::update_last_visit(pool, &local_user_view.local_user).await {
+      Ok(local_user) => local_user_view.local_user = local_user,
+      Err(e) => warn!("Failed to update last visit: {e}"),
+    }
+
     let (
       follows,
       community_blocks,
@@ -104,3 +96,76 @@ pub async fn get_site(
 
   Ok(Json(site_response))
 }
+
+/// Reasons why the site response isn't cached
+enum SiteResponseError {
+  /// Some of the non-essential data is missing, but the response can still be used
+  Partial(Box<GetSiteResponse>),
+  Failed(LemmyError),
+}
+
+/// Reads the site data which is independent of the user account. The returned flag is true if
+/// some of the non-essential data couldn't be read and was left empty.
+pub(crate) async fn build_site_response(
+  context: &LemmyContext,
+) -> LemmyResult<(GetSiteResponse, bool)> {
+  let site_view = SiteView::read_local(&mut context.pool())
+    .await?
+    .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
+  let admins = PersonView::admins(&mut context.pool()).await?;
+  // The remaining data is not essential, so errors only result in an empty list. This way the
+  // site can still be rendered if one of these queries fails.
+  let mut partial = false;
+  let all_languages = or_empty(
+    Language::read_all(&mut context.pool()).await,
+    "languages",
+    &mut partial,
+  );
+  let discussion_languages = or_empty(
+    SiteLanguage::read_local_raw(&mut context.pool()).await,
+    "discussion languages",
+    &mut partial,
+  );
+  let taglines = or_empty(
+    Tagline::get_all(&mut context.pool(), site_view.local_site.id).await,
+    "taglines",
+    &mut partial,
+  );
+  let custom_emojis = or_empty(
+    CustomEmojiView::get_all(&mut context.pool(), site_view.local_site.id).await,
+    "custom emojis",
+    &mut partial,
+  );
+  let blocked_urls = or_empty(
+    LocalSiteUrlBlocklist::get_all(&mut context.pool()).await,
+    "blocked urls",
+    &mut partial,
+  );
+  let supported_activities = supported_activity_types(&site_view.local_site);
+  let federation_enabled = site_view.local_site.federation_enabled;
+  let site_response = GetSiteResponse {
+    site_view,
+    admins,
+    version: VERSION.to_string(),
+    build_info: BuildInfo::default(),
+    my_user: None,
+    all_languages,
+    discussion_languages,
+    taglines,
+    custom_emojis,
+    blocked_urls,
+    federation_enabled,
+    supported_activities,
+  };
+  Ok((site_response, partial))
+}
+
+/// Returns the query result, or an empty value if the query failed. In that case the response is
+/// marked as partial.
+fn or_empty<T: Default, E: Display>(res: Result<T, E>, name: &str, partial: &mut bool) -> T {
+  res.unwrap_or_else(|e| {
+    warn!("Failed to read {name} for site response: {e}");
+    *partial = true;
+    T::default()
+  })
+}
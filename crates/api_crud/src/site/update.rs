@@ -7,6 +7,7 @@ use lemmy_api_common::{
   site::{EditSite, SiteResponse},
   utils::{
     get_url_blocklist,
+    invalidate_site_cache,
     is_admin,
     local_site_rate_limit_to_rate_limit_config,
     local_site_to_slur_regex,
@@ -15,10 +16,12 @@ use lemmy_api_common::{
   },
 };
 use lemmy_db_schema::{
+  newtypes::LocalSiteId,
   source::{
     actor_language::SiteLanguage,
     federation_allowlist::FederationAllowList,
     federation_blocklist::FederationBlockList,
+    instance::Instance,
     local_site::{LocalSite, LocalSiteUpdateForm},
     local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitUpdateForm},
     local_site_url_blocklist::LocalSiteUrlBlocklist,
@@ -39,12 +42,16 @@ use lemmy_utils::{
       build_and_check_regex,
       check_site_visibility_valid,
       check_urls_are_valid,
+      content_length_limits_check,
       is_valid_body_field,
+      is_valid_site_image_url,
       site_description_length_check,
       site_name_length_check,
     },
   },
 };
+use serde::Serialize;
+use serde_json::{Map, Value};
 
 #[tracing::instrument(skip(context))]
 pub async fn update_site(
@@ -52,25 +59,26 @@ pub async fn update_site(
   context: Data<LemmyContext>,
   local_user_view: LocalUserView,
 ) -> LemmyResult<Json<SiteResponse>> {
-  let site_view = SiteView::read_local(&mut context.pool())
+  let old_site_view = SiteView::read_local(&mut context.pool())
     .await?
     .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
-  let local_site = site_view.local_site;
-  let site = site_view.site;
+  let old_lists = read_replaced_lists(&data, old_site_view.local_site.id, &context).await?;
+  let local_site = &old_site_view.local_site;
+  let site = &old_site_view.site;
 
   // Make sure user is an admin; other types of users should not update site data...
   is_admin(&local_user_view)?;
 
-  validate_update_payload(&local_site, &data)?;
+  validate_update_payload(local_site, &data)?;
 
   if let Some(discussion_languages) = data.discussion_languages.clone() {
-    SiteLanguage::update(&mut context.pool(), discussion_languages.clone(), &site).await?;
+    SiteLanguage::update(&mut context.pool(), discussion_languages.clone(), site).await?;
   }
 
   replace_image(&data.icon, &site.icon, &context).await?;
   replace_image(&data.banner, &site.banner, &context).await?;
 
-  let slur_regex = local_site_to_slur_regex(&local_site);
+  let slur_regex = local_site_to_slur_regex(local_site);
   let url_blocklist = get_url_blocklist(&context).await?;
   let sidebar = process_markdown_opt(&data.sidebar, &slur_regex, &url_blocklist, &context).await?;
   let icon = proxy_image_link_opt_api(&data.icon, &context).await?;
@@ -115,6 +123,18 @@ pub async fn update_site(
     captcha_difficulty: data.captcha_difficulty.clone(),
     reports_email_admins: data.reports_email_admins,
     default_post_listing_mode: data.default_post_listing_mode,
+    new_user_badge_days: data.new_user_badge_days,
+    duplicate_post_window_hours: data.duplicate_post_window_hours,
+    auto_lock_posts_after_days: data.auto_lock_posts_after_days,
+    daily_post_limit: data.daily_post_limit,
+    daily_comment_limit: data.daily_comment_limit,
+    trusted_account_days: data.trusted_account_days,
+    trusted_account_min_score: data.trusted_account_min_score,
+    downvote_min_score: data.downvote_min_score,
+    downvote_min_account_days: data.downvote_min_account_days,
+    post_title_max_length: data.post_title_max_length,
+    post_body_max_length: data.post_body_max_length,
+    comment_max_length: data.comment_max_length,
     ..Default::default()
   };
 
@@ -191,12 +211,102 @@ pub async fn update_site(
     local_site_rate_limit_to_rate_limit_config(&site_view.local_site_rate_limit);
   context.rate_limit_cell().set_config(rate_limit_config);
 
+  let mut changed_fields = changed_site_fields("site", &old_site_view.site, &site_view.site)?;
+  changed_fields.extend(changed_site_fields(
+    "local_site",
+    &old_site_view.local_site,
+    &site_view.local_site,
+  )?);
+  changed_fields.extend(changed_site_fields(
+    "local_site_rate_limit",
+    &old_site_view.local_site_rate_limit,
+    &site_view.local_site_rate_limit,
+  )?);
+  let new_lists = read_replaced_lists(&data, old_site_view.local_site.id, &context).await?;
+  changed_fields.extend(changed_site_fields("", &old_lists, &new_lists)?);
+  invalidate_site_cache().await;
+
   Ok(Json(SiteResponse {
     site_view,
     taglines,
+    changed_fields: Some(changed_fields),
+    site_response: None,
   }))
 }
 
+/// Reads the list settings which are replaced by this [EditSite], keyed by the name of the
+/// matching field. Lists which aren't part of the edit can't change, so they aren't read.
+async fn read_replaced_lists(
+  data: &EditSite,
+  local_site_id: LocalSiteId,
+  context: &LemmyContext,
+) -> LemmyResult<Map<String, Value>> {
+  let pool = &mut context.pool();
+  let mut lists = Map::new();
+  if data.taglines.is_some() {
+    let taglines = Tagline::get_all(pool, local_site_id)
+      .await?
+      .into_iter()
+      .map(|t| t.content)
+      .collect::<Vec<_>>();
+    lists.insert("taglines".into(), serde_json::to_value(taglines)?);
+  }
+  if data.discussion_languages.is_some() {
+    let languages = SiteLanguage::read_local_raw(pool).await?;
+    lists.insert(
+      "discussion_languages".into(),
+      serde_json::to_value(languages)?,
+    );
+  }
+  if data.blocked_urls.is_some() {
+    let blocked_urls = LocalSiteUrlBlocklist::get_all(pool)
+      .await?
+      .into_iter()
+      .map(|b| b.url)
+      .collect::<Vec<_>>();
+    lists.insert("blocked_urls".into(), serde_json::to_value(blocked_urls)?);
+  }
+  let instance_domains = |instances: Vec<Instance>| {
+    let mut domains = instances.into_iter().map(|i| i.domain).collect::<Vec<_>>();
+    domains.sort();
+    domains
+  };
+  if data.allowed_instances.is_some() {
+    let allowed = instance_domains(Instance::allowlist(pool).await?);
+    lists.insert("allowed_instances".into(), serde_json::to_value(allowed)?);
+  }
+  if data.blocked_instances.is_some() {
+    let blocked = instance_domains(Instance::blocklist(pool).await?);
+    lists.insert("blocked_instances".into(), serde_json::to_value(blocked)?);
+  }
+  Ok(lists)
+}
+
+/// Returns the sorted names of all fields which differ between the old and new version of a
+/// setting struct, so that clients can highlight what was modified. Each name is prefixed with the
+/// struct it belongs to, eg `local_site.enable_downvotes`. Timestamps are left out, as they change
+/// with every edit.
+fn changed_site_fields<T: Serialize>(prefix: &str, old: &T, new: &T) -> LemmyResult<Vec<String>> {
+  let (Value::Object(old), Value::Object(new)) =
+    (serde_json::to_value(old)?, serde_json::to_value(new)?)
+  else {
+    return Ok(vec![]);
+  };
+  let mut changed = new
+    .iter()
+    .filter(|(name, value)| !name.ends_with("updated") && old.get(*name) != Some(value))
+    .map(|(name, _)| {
+      if prefix.is_empty() {
+        name.clone()
+      } else {
+        format!("{prefix}.{name}")
+      }
+    })
+    .collect::<Vec<_>>();
+  changed.sort();
+  Ok(changed)
+}
+
 fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> LemmyResult<()> {
   // Check that the slur regex compiles, and return the regex if valid...
   // Prioritize using new slur regex from the request; if not provided, use the existing regex.
@@ -230,6 +340,15 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
   // Ensure that the sidebar has fewer than the max num characters...
   is_valid_body_field(&edit_site.sidebar, false)?;
 
+  content_length_limits_check(
+    edit_site.post_title_max_length,
+    edit_site.post_body_max_length,
+    edit_site.comment_max_length,
+  )?;
+
+  is_valid_site_image_url(&edit_site.icon, LemmyErrorType::InvalidIconUrl)?;
+  is_valid_site_image_url(&edit_site.banner, LemmyErrorType::InvalidBannerUrl)?;
+
   application_question_check(
     &local_site.application_question,
     &edit_site.application_question,
@@ -244,17 +363,48 @@ fn validate_update_payload(local_site: &LocalSite, edit_site: &EditSite) -> Lemm
 #[allow(clippy::indexing_slicing)]
 mod tests {
 
-  use crate::site::update::validate_update_payload;
+  use crate::site::update::{changed_site_fields, validate_update_payload};
   use lemmy_api_common::site::EditSite;
   use lemmy_db_schema::{source::local_site::LocalSite, ListingType, RegistrationMode, SortType};
   use lemmy_utils::error::LemmyErrorType;
+  use serde_json::json;
+
+  #[test]
+  fn test_changed_site_fields() {
+    let old = json!({
+      "name": "site",
+      "updated": "2024-01-01T00:00:00Z",
+      "post": 6,
+      "comment": 6,
+    });
+    let new = json!({
+      "post": 10,
+      "comment": 8,
+      "name": "site",
+      "updated": "2024-01-02T00:00:00Z",
+    });
+
+    let changed = changed_site_fields("local_site_rate_limit", &old, &new).unwrap();
+    assert_eq!(
+      vec![
+        "local_site_rate_limit.comment",
+        "local_site_rate_limit.post"
+      ],
+      changed
+    );
+
+    let old = json!({ "taglines": ["a", "b"], "blocked_instances": [] });
+    let new = json!({ "blocked_instances": ["example.com"], "taglines": ["a", "b"] });
+    let changed = changed_site_fields("", &old, &new).unwrap();
+    assert_eq!(vec!["blocked_instances"], changed);
+  }
 
   #[test]
   fn test_validate_invalid_update_payload() {
     let invalid_payloads = [
       (
         "EditSite name matches LocalSite slur filter",
-        LemmyErrorType::Slurs,
+        LemmyErrorType::Slurs(vec!["foo".to_string()]),
         &generate_local_site(
           Some(String::from("(foo|bar)")),
           true,
@@ -277,7 +427,7 @@ mod tests {
       ),
       (
         "EditSite name matches new slur filter",
-        LemmyErrorType::Slurs,
+        LemmyErrorType::Slurs(vec!["zeta".to_string()]),
         &generate_local_site(
           Some(String::from("(foo|bar)")),
           true,
@@ -602,6 +752,18 @@ mod tests {
       reports_email_admins: None,
       content_warning: None,
       default_post_listing_mode: None,
+      new_user_badge_days: None,
+      duplicate_post_window_hours: None,
+      auto_lock_posts_after_days: None,
+      daily_post_limit: None,
+      daily_comment_limit: None,
+      trusted_account_days: None,
+      trusted_account_min_score: None,
+      downvote_min_score: None,
+      downvote_min_account_days: None,
+      post_title_max_length: None,
+      post_body_max_length: None,
+      comment_max_length: None,
     }
   }
 }
@@ -18,6 +18,7 @@ use lemmy_api_common::{
 };
 use lemmy_db_schema::{
   aggregates::structs::PersonAggregates,
+  newtypes::LocalUserId,
   source::{
     captcha_answer::{CaptchaAnswer, CheckCaptchaAnswer},
     language::Language,
@@ -38,6 +39,7 @@ use lemmy_utils::{
   },
 };
 use std::collections::HashSet;
+use tracing::debug;
 
 #[tracing::instrument(skip(context))]
 pub async fn register(
@@ -57,7 +59,31 @@ pub async fn register(
   }
 
   password_length_check(&data.password)?;
-  honeypot_check(&data.honeypot)?;
+
+  // Pretend that the registration succeeded, so that bots don't notice they were caught
+  if honeypot_check(&data.honeypot).is_err() {
+    debug!(
+      "Discarded registration of {} with filled honeypot",
+      data.username
+    );
+    // Same conditions as for real registrations below
+    let logs_in = !local_site.site_setup
+      || (!require_registration_application && !local_site.require_email_verification);
+    let jwt = if logs_in {
+      let next_user_id = i32::try_from(site_view.counts.users + 1).unwrap_or(i32::MAX);
+      Some(Claims::generate_unusable(
+        LocalUserId(next_user_id),
+        &context,
+      )?)
+    } else {
+      None
+    };
+    return Ok(Json(LoginResponse {
+      jwt,
+      registration_created: !logs_in && require_registration_application,
+      verify_email_sent: !logs_in && local_site.require_email_verification,
+    }));
+  }
 
   if local_site.require_email_verification && data.email.is_none() {
     Err(LemmyErrorType::EmailRequired)?
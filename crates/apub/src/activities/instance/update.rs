@@ -0,0 +1,72 @@
+use crate::{
+  activities::{generate_activity_id, send_lemmy_activity, verify_is_public},
+  insert_received_activity,
+  objects::instance::ApubSite,
+  protocol::activities::instance::update::UpdateInstance,
+};
+use activitypub_federation::{
+  config::Data,
+  kinds::{activity::UpdateType, public},
+  protocol::verification::verify_domains_match,
+  traits::{ActivityHandler, Actor, Object},
+};
+use lemmy_api_common::context::LemmyContext;
+use lemmy_db_schema::source::{activity::ActivitySendTargets, site::Site};
+use lemmy_utils::error::{LemmyError, LemmyResult};
+use url::Url;
+
+pub(crate) async fn send_update_instance(
+  site: Site,
+  context: Data<LemmyContext>,
+) -> LemmyResult<()> {
+  let site: ApubSite = site.into();
+  let id = generate_activity_id(
+    UpdateType::Update,
+    &context.settings().get_protocol_and_hostname(),
+  )?;
+  let update = UpdateInstance {
+    actor: site.id().into(),
+    to: vec![public()],
+    object: Box::new(site.clone().into_json(&context).await?),
+    kind: UpdateType::Update,
+    id,
+  };
+
+  send_lemmy_activity(
+    &context,
+    update,
+    &site,
+    ActivitySendTargets::to_all_instances(),
+    false,
+  )
+  .await
+}
+
+#[async_trait::async_trait]
+impl ActivityHandler for UpdateInstance {
+  type DataType = LemmyContext;
+  type Error = LemmyError;
+
+  fn id(&self) -> &Url {
+    &self.id
+  }
+
+  fn actor(&self) -> &Url {
+    self.actor.inner()
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn verify(&self, context: &Data<Self::DataType>) -> LemmyResult<()> {
+    verify_is_public(&self.to, &[])?;
+    verify_domains_match(self.actor.inner(), &self.id)?;
+    ApubSite::verify(&self.object, self.actor.inner(), context).await?;
+    Ok(())
+  }
+
+  #[tracing::instrument(skip_all)]
+  async fn receive(self, context: &Data<Self::DataType>) -> LemmyResult<()> {
+    insert_received_activity(&self.id, context).await?;
+    ApubSite::from_json(*self.object, context).await?;
+    Ok(())
+  }
+}
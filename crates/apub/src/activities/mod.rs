@@ -14,6 +14,7 @@ use crate::{
       send_apub_delete_user,
       DeletableObjects,
     },
+    instance::update::send_update_instance,
     voting::send_like_activity,
   },
   objects::{community::ApubCommunity, person::ApubPerson},
@@ -53,6 +54,7 @@ pub mod community;
 pub mod create_or_update;
 pub mod deletion;
 pub mod following;
+pub mod instance;
 pub mod voting;
 
 /// Checks that the specified Url actually identifies a Person (by fetching it), and that the person
@@ -383,6 +385,7 @@ pub async fn match_outgoing_activities(
         community,
         reason,
       } => Report::send(ObjectId::from(object_id), actor, community, reason, context).await,
+      UpdateInstance(site) => send_update_instance(site, context).await,
     }
   };
   fed_task.await?;
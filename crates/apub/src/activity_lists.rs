@@ -18,6 +18,7 @@ use crate::{
       },
       deletion::{delete::Delete, undo_delete::UndoDelete},
       following::{accept::AcceptFollow, follow::Follow, undo_follow::UndoFollow},
+      instance::update::UpdateInstance,
       voting::{undo_vote::UndoVote, vote::Vote},
     },
     objects::page::Page,
@@ -44,6 +45,7 @@ pub enum SharedInboxActivities {
   UndoFollow(UndoFollow),
   CreateOrUpdatePrivateMessage(CreateOrUpdateChatMessage),
   Report(Report),
+  UpdateInstance(UpdateInstance),
   AnnounceActivity(AnnounceActivity),
   /// This is a catch-all and needs to be last
   RawAnnouncableActivities(RawAnnouncableActivities),
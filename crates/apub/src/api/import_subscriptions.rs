@@ -0,0 +1,100 @@
+use crate::{fetcher::resolve_actor_identifier, objects::community::ApubCommunity};
+use activitypub_federation::config::Data;
+use actix_web::web::Json;
+use futures::StreamExt;
+use lemmy_api_common::{
+  community::{ImportSubscriptionResult, ImportSubscriptions, ImportSubscriptionsResponse},
+  context::LemmyContext,
+  send_activity::{ActivityChannel, SendActivityData},
+  utils::check_community_user_action,
+};
+use lemmy_db_schema::{
+  newtypes::CommunityId,
+  source::community::{Community, CommunityFollower, CommunityFollowerForm},
+  traits::Followable,
+};
+use lemmy_db_views::structs::LocalUserView;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorExt2, LemmyErrorType, LemmyResult};
+
+/// Maximum number of handles which can be imported with a single request. Each of them may need
+/// to be fetched from a remote instance, so this is much lower than for other list parameters.
+const MAX_IMPORT_HANDLES: usize = 50;
+/// How many handles are resolved at the same time.
+const PARALLELISM: usize = 5;
+
+#[tracing::instrument(skip(context))]
+pub async fn import_subscriptions(
+  data: Json<ImportSubscriptions>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<Json<ImportSubscriptionsResponse>> {
+  if data.handles.len() > MAX_IMPORT_HANDLES {
+    Err(LemmyErrorType::TooManyItems)?;
+  }
+
+  let local_user_view = &local_user_view;
+  // `buffered` keeps the results in the same order as the submitted handles
+  let results: Vec<_> = futures::stream::iter(data.handles.clone().into_iter().map(|handle| {
+    // reset_request_count works like clone, and is necessary to avoid running into request limit
+    let context = context.reset_request_count();
+    async move {
+      match subscribe(&handle, local_user_view, &context).await {
+        Ok(community_id) => ImportSubscriptionResult {
+          handle,
+          community_id: Some(community_id),
+          error: None,
+        },
+        Err(e) => ImportSubscriptionResult {
+          handle,
+          community_id: None,
+          error: Some(e.error_type),
+        },
+      }
+    }
+  }))
+  .buffered(PARALLELISM)
+  .collect()
+  .await;
+
+  Ok(Json(ImportSubscriptionsResponse { results }))
+}
+
+/// Resolves a single `!community@instance` handle and follows the community.
+async fn subscribe(
+  handle: &str,
+  local_user_view: &LocalUserView,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<CommunityId> {
+  let identifier = handle.trim().trim_start_matches('!');
+  let community = resolve_actor_identifier::<ApubCommunity, Community>(
+    identifier,
+    context,
+    &Some(local_user_view.clone()),
+    false,
+  )
+  .await
+  .with_lemmy_type(LemmyErrorType::CouldntFindCommunity)?;
+
+  let person = &local_user_view.person;
+  if community.local {
+    check_community_user_action(person, community.id, &mut context.pool()).await?;
+  }
+  // Remote follows stay pending until the community accepts them
+  let form = CommunityFollowerForm {
+    community_id: community.id,
+    person_id: person.id,
+    pending: !community.local,
+  };
+  CommunityFollower::follow(&mut context.pool(), &form)
+    .await
+    .with_lemmy_type(LemmyErrorType::CommunityFollowerAlreadyExists)?;
+
+  if !community.local {
+    ActivityChannel::submit_activity(
+      SendActivityData::FollowCommunity((*community).clone(), person.clone(), true),
+      context,
+    )
+    .await?;
+  }
+  Ok(community.id)
+}
@@ -1,5 +1,5 @@
 use crate::{
-  api::{listing_type_with_default, sort_type_with_default},
+  api::{listing_type_with_default, sort_type_or_community_default, sort_type_with_default},
   fetcher::resolve_actor_identifier,
   objects::community::ApubCommunity,
 };
@@ -57,12 +57,19 @@ pub async fn list_posts(
     community_id,
   ));
 
+  let sort = sort_type_or_community_default(data.sort, community_id, &context).await?;
   let sort = Some(sort_type_with_default(
-    data.sort,
+    sort,
     local_user_ref,
     &local_site.local_site,
   ));
 
+  let published_after = if data.since_last_visit.unwrap_or_default() {
+    local_user_ref.and_then(|l| l.previous_visit)
+  } else {
+    None
+  };
+
   // parse pagination token
   let page_after = if let Some(pa) = &data.page_cursor {
     Some(pa.read(&mut context.pool()).await?)
@@ -82,6 +89,7 @@ pub async fn list_posts(
     page_after,
     limit,
     show_hidden,
+    published_after,
     ..Default::default()
   }
   .list(&local_site.site, &mut context.pool())
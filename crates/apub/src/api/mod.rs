@@ -1,16 +1,21 @@
+use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
   newtypes::CommunityId,
-  source::{local_site::LocalSite, local_user::LocalUser},
+  source::{community::Community, local_site::LocalSite, local_user::LocalUser},
+  traits::Crud,
   ListingType,
   SortType,
 };
+use lemmy_utils::error::LemmyResult;
 
+pub mod import_subscriptions;
 pub mod list_comments;
 pub mod list_posts;
 pub mod read_community;
 pub mod read_person;
 pub mod resolve_object;
 pub mod search;
+pub mod user_data_export;
 pub mod user_settings_backup;
 
 /// Returns default listing type, depending if the query is for frontpage or community.
@@ -46,3 +51,97 @@ fn sort_type_with_default(
       .unwrap_or(local_site.default_sort_type),
   )
 }
+
+/// Returns the sort type given by the user, or otherwise the default sort type of the community.
+/// The community is only read if it is needed.
+async fn sort_type_or_community_default(
+  type_: Option<SortType>,
+  community_id: Option<CommunityId>,
+  context: &LemmyContext,
+) -> LemmyResult<Option<SortType>> {
+  match (type_, community_id) {
+    (None, Some(community_id)) => Ok(
+      Community::read(&mut context.pool(), community_id)
+        .await?
+        .and_then(|c| c.default_sort_type),
+    ),
+    _ => Ok(type_),
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+  use super::{sort_type_or_community_default, sort_type_with_default};
+  use lemmy_api_common::context::LemmyContext;
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      local_site::LocalSite,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    SortType,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_sort_type_with_default() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance.id, "sorter")).await?;
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(person.id)
+      .password_encrypted(String::new())
+      .default_sort_type(Some(SortType::New))
+      .build();
+    let local_user = LocalUser::create(pool, &local_user_form, vec![]).await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("sort_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .default_sort_type(Some(SortType::TopWeek))
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let local_site = LocalSite {
+      default_sort_type: SortType::Hot,
+      ..Default::default()
+    };
+
+    // The sort type of the user comes first, then the community default
+    let sort = |type_| sort_type_or_community_default(type_, Some(community.id), &context);
+    assert_eq!(Some(SortType::TopDay), sort(Some(SortType::TopDay)).await?);
+    assert_eq!(Some(SortType::TopWeek), sort(None).await?);
+    assert_eq!(
+      None,
+      sort_type_or_community_default(None, None, &context).await?
+    );
+
+    // Then the default of the local user, and of the site
+    assert_eq!(
+      SortType::TopDay,
+      sort_type_with_default(Some(SortType::TopDay), Some(&local_user), &local_site)
+    );
+    assert_eq!(
+      SortType::New,
+      sort_type_with_default(None, Some(&local_user), &local_site)
+    );
+    assert_eq!(
+      SortType::Hot,
+      sort_type_with_default(None, None, &local_site)
+    );
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
@@ -5,9 +5,16 @@ use crate::fetcher::{
 use activitypub_federation::config::Data;
 use actix_web::web::{Json, Query};
 use diesel::NotFound;
+use futures::StreamExt;
 use lemmy_api_common::{
   context::LemmyContext,
-  site::{ResolveObject, ResolveObjectResponse},
+  site::{
+    ResolveObject,
+    ResolveObjectResponse,
+    ResolveObjectResult,
+    ResolveObjects,
+    ResolveObjectsResponse,
+  },
   utils::check_private_instance,
 };
 use lemmy_db_schema::{newtypes::PersonId, source::local_site::LocalSite, utils::DbPool};
@@ -15,6 +22,13 @@ use lemmy_db_views::structs::{CommentView, LocalUserView, PostView};
 use lemmy_db_views_actor::structs::{CommunityView, PersonView};
 use lemmy_utils::error::{LemmyErrorExt2, LemmyErrorType, LemmyResult};
 
+/// Maximum number of ids which can be resolved with a single request. Each of them may need to be
+/// fetched from a remote instance.
+const MAX_RESOLVE_IDS: usize = 20;
+
+/// How many ids are resolved at the same time.
+const PARALLELISM: usize = 5;
+
 #[tracing::instrument(skip(context))]
 pub async fn resolve_object(
   data: Query<ResolveObject>,
@@ -24,16 +38,71 @@ pub async fn resolve_object(
   let local_site = LocalSite::read(&mut context.pool()).await?;
   check_private_instance(&local_user_view, &local_site)?;
   let person_id = local_user_view.map(|v| v.person.id);
+
+  Ok(Json(resolve(&data.q, person_id, &context).await?))
+}
+
+#[tracing::instrument(skip(context))]
+pub async fn resolve_objects(
+  data: Json<ResolveObjects>,
+  context: Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<ResolveObjectsResponse>> {
+  if data.ids.len() > MAX_RESOLVE_IDS {
+    Err(LemmyErrorType::TooManyObjects)?;
+  }
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+  let person_id = local_user_view.map(|v| v.person.id);
+
+  let results = resolve_all(data.ids.clone(), person_id, &context).await;
+  Ok(Json(ResolveObjectsResponse { results }))
+}
+
+/// Resolves each of the ids, keeping the results in the same order. All of them share the limit
+/// of outgoing requests of the context, so that a batch can't cause more fetches than a single
+/// object.
+async fn resolve_all(
+  ids: Vec<String>,
+  person_id: Option<PersonId>,
+  context: &Data<LemmyContext>,
+) -> Vec<ResolveObjectResult> {
+  // `buffered` keeps the results in the same order as the submitted ids
+  futures::stream::iter(ids.into_iter().map(|id| async move {
+    match resolve(&id, person_id, context).await {
+      Ok(object) => ResolveObjectResult {
+        id,
+        object: Some(object),
+        error: None,
+      },
+      Err(e) => ResolveObjectResult {
+        id,
+        object: None,
+        error: Some(e.error_type),
+      },
+    }
+  }))
+  .buffered(PARALLELISM)
+  .collect()
+  .await
+}
+
+/// Fetches a single object. Remote lookups are only done for logged in users.
+async fn resolve(
+  q: &str,
+  person_id: Option<PersonId>,
+  context: &Data<LemmyContext>,
+) -> LemmyResult<ResolveObjectResponse> {
   // If we get a valid personId back we can safely assume that the user is authenticated,
   // if there's no personId then the JWT was missing or invalid.
   let is_authenticated = person_id.is_some();
 
   let res = if is_authenticated {
     // user is fully authenticated; allow remote lookups as well.
-    search_query_to_object_id(data.q.clone(), &context).await
+    search_query_to_object_id(q.to_string(), context).await
   } else {
     // user isn't authenticated only allow a local search.
-    search_query_to_object_id_local(&data.q, &context).await
+    search_query_to_object_id_local(q, context).await
   }
   .with_lemmy_type(LemmyErrorType::CouldntFindObject)?;
 
@@ -46,7 +115,7 @@ async fn convert_response(
   object: SearchableObjects,
   user_id: Option<PersonId>,
   pool: &mut DbPool<'_>,
-) -> LemmyResult<Json<ResolveObjectResponse>> {
+) -> LemmyResult<ResolveObjectResponse> {
   use SearchableObjects::*;
   let removed_or_deleted;
   let mut res = ResolveObjectResponse::default();
@@ -90,6 +159,56 @@ async fn convert_response(
   if removed_or_deleted {
     Err(NotFound {}.into())
   } else {
-    Ok(Json(res))
+    Ok(res)
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+  use super::{resolve_all, resolve_objects, MAX_RESOLVE_IDS};
+  use actix_web::web::Json;
+  use lemmy_api_common::{context::LemmyContext, site::ResolveObjects};
+  use lemmy_db_schema::newtypes::PersonId;
+  use lemmy_utils::LemmyErrorType;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_resolve_objects_limits() {
+    let context = LemmyContext::init_test_context().await;
+
+    let ids = (0..=MAX_RESOLVE_IDS)
+      .map(|i| format!("https://remote.example/post/{i}"))
+      .collect();
+    let res = resolve_objects(
+      Json(ResolveObjects { ids }),
+      context.reset_request_count(),
+      None,
+    )
+    .await;
+    assert_eq!(
+      LemmyErrorType::TooManyObjects,
+      res.err().unwrap().error_type
+    );
+
+    // The ids share a single limit of outgoing requests. The test context doesn't allow any
+    // requests, so each remote id uses up one attempt and fails.
+    let ids = vec![
+      "https://remote.example/post/1".to_string(),
+      "https://remote.example/post/2".to_string(),
+    ];
+    let context = context.reset_request_count();
+    let results = resolve_all(ids.clone(), Some(PersonId(-1)), &context).await;
+    assert_eq!(2, context.request_count());
+    assert_eq!(
+      ids,
+      results.iter().map(|r| r.id.clone()).collect::<Vec<_>>()
+    );
+    assert!(results
+      .iter()
+      .all(|r| r.object.is_none() && r.error.is_some()));
   }
 }
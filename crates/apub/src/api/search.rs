@@ -1,19 +1,95 @@
-use crate::{fetcher::resolve_actor_identifier, objects::community::ApubCommunity};
+use crate::{
+  api::sort_type_or_community_default,
+  fetcher::resolve_actor_identifier,
+  objects::community::ApubCommunity,
+};
 use activitypub_federation::config::Data;
-use actix_web::web::{Json, Query};
+use actix_web::{
+  error::QueryPayloadError,
+  web::{Json, Query},
+  HttpRequest,
+};
+use chrono::{DateTime, Utc};
+use diesel::result::Error as DieselError;
+use diesel_async::{scoped_futures::ScopedBoxFuture, AsyncPgConnection};
 use lemmy_api_common::{
   context::LemmyContext,
-  site::{Search, SearchResponse},
+  site::{
+    CommunityPostGroup,
+    CrosspostCount,
+    Search,
+    SearchCounts,
+    SearchResponse,
+    SearchResultItem,
+  },
   utils::{check_private_instance, is_admin},
 };
-use lemmy_db_schema::{source::community::Community, utils::post_to_comment_sort_type, SearchType};
+use lemmy_db_schema::{
+  source::{community::Community, site::Site},
+  traits::Crud,
+  utils::{
+    get_conn,
+    is_statement_timeout,
+    post_to_comment_sort_type,
+    run_with_statement_timeout,
+    DbPool,
+  },
+  SearchType,
+  SortType,
+};
 use lemmy_db_views::{
   comment_view::CommentQuery,
   post_view::PostQuery,
-  structs::{LocalUserView, SiteView},
+  structs::{CommentView, LocalUserView, PostView, SiteView},
 };
-use lemmy_db_views_actor::{community_view::CommunityQuery, person_view::PersonQuery};
-use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+use lemmy_db_views_actor::{
+  community_view::CommunityQuery,
+  person_view::PersonQuery,
+  structs::{CommunityView, PersonView},
+};
+use lemmy_utils::{
+  error::{LemmyError, LemmyResult},
+  LemmyErrorType,
+};
+use std::{cell::Cell, cmp::Reverse, collections::HashMap, str::FromStr, time::Duration};
+use tokio::time::Instant;
+use url::Url;
+
+/// How long a search may run before falling back to partial results, if the client requested it.
+const SEARCH_SOFT_DEADLINE: Duration = Duration::from_secs(5);
+/// Suggestions are only computed if the search found fewer results than this.
+const SUGGESTIONS_THRESHOLD: usize = 3;
+const SUGGESTIONS_LIMIT: usize = 5;
+
+/// Shorter search terms would match nearly everything, unless the search is limited to a
+/// community or creator.
+const SEARCH_TERM_MIN_LENGTH: usize = 2;
+const SEARCH_TERM_MAX_LENGTH: usize = 255;
+/// Url searches contain a whole url, which can be as long as the post url column allows.
+const SEARCH_URL_MAX_LENGTH: usize = 512;
+
+const ALL_SORTS: &[SortType] = &[
+  SortType::Active,
+  SortType::Hot,
+  SortType::New,
+  SortType::Old,
+  SortType::TopDay,
+  SortType::TopWeek,
+  SortType::TopMonth,
+  SortType::TopYear,
+  SortType::TopAll,
+  SortType::MostComments,
+  SortType::NewComments,
+  SortType::TopHour,
+  SortType::TopSixHour,
+  SortType::TopTwelveHour,
+  SortType::TopThreeMonths,
+  SortType::TopSixMonths,
+  SortType::TopNineMonths,
+  SortType::Controversial,
+  SortType::Scaled,
+  SortType::Relevance,
+];
 
 #[tracing::instrument(skip(context))]
 pub async fn search(
@@ -27,24 +103,51 @@ pub async fn search(
 
   check_private_instance(&local_user_view, &local_site.local_site)?;
 
+  if let (Some(min_score), Some(max_score)) = (data.min_score, data.max_score) {
+    if min_score > max_score {
+      Err(LemmyErrorType::ContradictingFilters)?
+    }
+  }
+  if let Some(sort) = data.sort {
+    if !valid_sorts_for(data.type_.unwrap_or(SearchType::All)).contains(&sort) {
+      Err(LemmyErrorType::InvalidSortForType)?
+    }
+  }
+  if let (Some(after), Some(before)) = (data.published_after, data.published_before) {
+    if after > before {
+      Err(LemmyErrorType::InvalidDateRange)?
+    }
+  }
+  let search_term = data.q.trim();
+  let scoped =
+    data.community_id.is_some() || data.community_name.is_some() || data.creator_id.is_some();
+  check_search_term(search_term, scoped, data.type_)?;
+
   let is_admin = local_user_view
     .as_ref()
     .map(|luv| is_admin(luv).is_ok())
     .unwrap_or_default();
+  let (include_removed, include_deleted) = include_removed_and_deleted(&data, is_admin);
 
   let mut posts = Vec::new();
   let mut comments = Vec::new();
   let mut communities = Vec::new();
   let mut users = Vec::new();
 
-  // TODO no clean / non-nsfw searching rn
-
-  let q = data.q.clone();
+  let q = search_term.to_string();
   let page = data.page;
   let limit = data.limit;
-  let sort = data.sort;
   let listing_type = data.listing_type;
   let search_type = data.type_.unwrap_or(SearchType::All);
+  // Otherwise a missing community would look the same as a search without matches
+  if let Some(community_id) = data.community_id {
+    let exists = Community::read(&mut context.pool(), community_id)
+      .await?
+      .is_some_and(|c| !c.deleted && !c.removed);
+    if !exists {
+      Err(LemmyErrorType::CouldntFindCommunity)?
+    }
+  }
   let community_id = if let Some(name) = &data.community_name {
     Some(
       resolve_actor_identifier::<ApubCommunity, Community>(name, &context, &local_user_view, false)
@@ -54,153 +157,263 @@ pub async fn search(
   } else {
     data.community_id
   };
+  // Searches within a community use its default sort type, if the user doesn't give one
+  let sort = sort_type_or_community_default(data.sort, community_id, &context).await?;
+  let use_fts = data.use_fts.unwrap_or_default();
+  let sort_by_relevance = sort == Some(SortType::Relevance);
   let creator_id = data.creator_id;
   let local_user = local_user_view.as_ref().map(|luv| &luv.local_user);
+  let last_visit = if data.since_last_visit.unwrap_or_default() {
+    local_user.and_then(|l| l.previous_visit)
+  } else {
+    None
+  };
+  // Use the later time if both are given
+  let published_after = data.published_after.max(last_visit);
+  let published_before = data.published_before;
+  // Respect the nsfw setting of the user. Without login, the defaults of the queries are kept, eg
+  // posts are only shown with nsfw content if the site has a content warning.
+  let show_nsfw = local_user.map(|l| l.show_nsfw);
+  let deadline = SoftDeadline::new(data.partial_on_timeout.unwrap_or_default());
+
+  // The types which aren't searched are counted as 0. A count is None if it timed out.
+  let (mut post_count, mut comment_count, mut community_count, mut user_count) =
+    (Some(0), Some(0), Some(0), Some(0));
 
   match search_type {
     SearchType::Posts => {
-      posts = PostQuery {
+      let post_query = PostQuery {
         sort: (sort),
         listing_type: (listing_type),
         community_id: (community_id),
         creator_id: (creator_id),
         local_user: (local_user_view.as_ref()),
         search_term: (Some(q)),
+        search_history: data.search_history.unwrap_or_default(),
+        use_fts,
+        min_score: data.min_score,
+        max_score: data.max_score,
+        exclude_community_ids: data.exclude_community_ids.clone(),
+        has_media: data.has_media,
+        published_after,
+        published_before,
+        show_nsfw,
+        include_removed,
+        include_deleted,
         page: (page),
         limit: (limit),
         ..Default::default()
-      }
-      .list(&local_site.site, &mut context.pool())
-      .await?;
+      };
+      (post_count, posts) =
+        search_posts(post_query, &local_site.site, &deadline, &mut context.pool()).await?;
     }
     SearchType::Comments => {
-      comments = CommentQuery {
+      let comment_query = CommentQuery {
         sort: (sort.map(post_to_comment_sort_type)),
         listing_type: (listing_type),
         search_term: (Some(q)),
+        use_fts,
+        sort_by_relevance,
+        min_score: data.min_score,
+        max_score: data.max_score,
+        exclude_community_ids: data.exclude_community_ids.clone(),
+        published_after,
+        published_before,
+        show_nsfw,
         community_id: (community_id),
         creator_id: (creator_id),
         local_user: (local_user_view.as_ref()),
+        include_removed,
+        exclude_removed: !include_removed,
+        exclude_deleted: !include_deleted,
         page: (page),
         limit: (limit),
         ..Default::default()
-      }
-      .list(&mut context.pool())
-      .await?;
+      };
+      (comment_count, comments) =
+        search_comments(comment_query, &deadline, &mut context.pool()).await?;
     }
     SearchType::Communities => {
-      communities = CommunityQuery {
+      let community_query = CommunityQuery {
         sort: (sort),
         listing_type: (listing_type),
         search_term: (Some(q)),
         local_user,
         is_mod_or_admin: (is_admin),
+        show_nsfw: show_nsfw.unwrap_or_default(),
         page: (page),
         limit: (limit),
-        ..Default::default()
-      }
-      .list(&local_site.site, &mut context.pool())
+      };
+      (community_count, communities) = search_communities(
+        Some(community_query),
+        &local_site.site,
+        &deadline,
+        &mut context.pool(),
+      )
       .await?;
     }
     SearchType::Users => {
-      users = PersonQuery {
+      let person_query = PersonQuery {
         sort,
         search_term: (Some(q)),
         listing_type: (listing_type),
         page: (page),
         limit: (limit),
-      }
-      .list(&mut context.pool())
-      .await?;
+      };
+      (user_count, users) =
+        search_users(Some(person_query), &deadline, &mut context.pool()).await?;
     }
     SearchType::All => {
-      // If the community or creator is included, dont search communities or users
-      let community_or_creator_included =
-        data.community_id.is_some() || data.community_name.is_some() || data.creator_id.is_some();
-
-      let q = data.q.clone();
+      let q = search_term.to_string();
 
-      posts = PostQuery {
+      let post_query = PostQuery {
         sort: (sort),
         listing_type: (listing_type),
         community_id: (community_id),
         creator_id: (creator_id),
         local_user: (local_user_view.as_ref()),
         search_term: (Some(q)),
+        search_history: data.search_history.unwrap_or_default(),
+        use_fts,
+        min_score: data.min_score,
+        max_score: data.max_score,
+        exclude_community_ids: data.exclude_community_ids.clone(),
+        has_media: data.has_media,
+        published_after,
+        published_before,
+        show_nsfw,
+        include_removed,
+        include_deleted,
         page: (page),
-        limit: (limit),
+        limit: data.limit_posts.or(limit),
         ..Default::default()
-      }
-      .list(&local_site.site, &mut context.pool())
-      .await?;
+      };
 
-      let q = data.q.clone();
+      let q = search_term.to_string();
 
-      comments = CommentQuery {
+      let comment_query = CommentQuery {
         sort: (sort.map(post_to_comment_sort_type)),
         listing_type: (listing_type),
         search_term: (Some(q)),
+        use_fts,
+        sort_by_relevance,
+        min_score: data.min_score,
+        max_score: data.max_score,
+        exclude_community_ids: data.exclude_community_ids.clone(),
+        published_after,
+        published_before,
+        show_nsfw,
         community_id: (community_id),
         creator_id: (creator_id),
         local_user: (local_user_view.as_ref()),
+        include_removed,
+        exclude_removed: !include_removed,
+        exclude_deleted: !include_deleted,
         page: (page),
-        limit: (limit),
+        limit: data.limit_comments.or(limit),
         ..Default::default()
-      }
-      .list(&mut context.pool())
-      .await?;
-
-      let q = data.q.clone();
-
-      communities = if community_or_creator_included {
-        vec![]
-      } else {
-        CommunityQuery {
-          sort: (sort),
-          listing_type: (listing_type),
-          search_term: (Some(q)),
-          local_user,
-          is_mod_or_admin: (is_admin),
-          page: (page),
-          limit: (limit),
-          ..Default::default()
-        }
-        .list(&local_site.site, &mut context.pool())
-        .await?
       };
 
-      let q = data.q.clone();
-
-      users = if community_or_creator_included {
-        vec![]
-      } else {
-        PersonQuery {
-          sort,
-          search_term: (Some(q)),
-          listing_type: (listing_type),
-          page: (page),
-          limit: (limit),
-        }
-        .list(&mut context.pool())
-        .await?
-      };
+      // If the community or creator is included, dont search communities or users
+      let community_query = (!scoped).then(|| CommunityQuery {
+        sort: (sort),
+        listing_type: (listing_type),
+        search_term: (Some(search_term.to_string())),
+        local_user,
+        is_mod_or_admin: (is_admin),
+        show_nsfw: show_nsfw.unwrap_or_default(),
+        page: (page),
+        limit: data.limit_communities.or(limit),
+      });
+
+      let person_query = (!scoped).then(|| PersonQuery {
+        sort,
+        search_term: (Some(search_term.to_string())),
+        listing_type: (listing_type),
+        page: (page),
+        limit: data.limit_users.or(limit),
+      });
+
+      // The four searches are independent, so run them concurrently
+      let site = &local_site.site;
+      let deadline = &deadline;
+      let pool = &mut context.pool();
+      (
+        (post_count, posts),
+        (comment_count, comments),
+        (community_count, communities),
+        (user_count, users),
+      ) = lemmy_db_schema::try_join_with_pool!(pool => (
+        |pool| search_posts(post_query, site, deadline, pool),
+        |pool| search_comments(comment_query, deadline, pool),
+        |pool| search_communities(community_query, site, deadline, pool),
+        |pool| search_users(person_query, deadline, pool)
+      ))?;
     }
     SearchType::Url => {
-      posts = PostQuery {
+      let post_query = PostQuery {
         sort: (sort),
         listing_type: (listing_type),
         community_id: (community_id),
         creator_id: (creator_id),
         url_search: (Some(q)),
+        has_media: data.has_media,
+        published_after,
+        published_before,
+        show_nsfw,
+        include_removed,
+        include_deleted,
         page: (page),
         limit: (limit),
         ..Default::default()
-      }
-      .list(&local_site.site, &mut context.pool())
-      .await?;
+      };
+      (post_count, posts) =
+        search_posts(post_query, &local_site.site, &deadline, &mut context.pool()).await?;
     }
   };
 
+  let result_count = posts.len() + comments.len() + communities.len() + users.len();
+  let suggestions = if data.suggestions.unwrap_or_default() && result_count < SUGGESTIONS_THRESHOLD
+  {
+    // Like nsfw posts, nsfw communities are only suggested without login if the site has a
+    // content warning
+    let show_nsfw = show_nsfw.unwrap_or(local_site.site.content_warning.is_some());
+    Some(
+      Community::similar_names(
+        &mut context.pool(),
+        search_term,
+        SUGGESTIONS_LIMIT,
+        show_nsfw,
+      )
+      .await?,
+    )
+  } else {
+    None
+  };
+
+  // Collapsing in the query would need a DISTINCT ON the normalized url, which can't be combined
+  // with the ordering and pagination of PostQuery. So pages shrink instead, as documented in the
+  // API.
+  let crosspost_counts = if data.collapse_crossposts.unwrap_or_default() {
+    let (collapsed, counts) = collapse_crossposts(posts);
+    posts = collapsed;
+    Some(counts)
+  } else {
+    None
+  };
+
+  let merged = if data.merge.unwrap_or_default() {
+    Some(merge_results(&posts, &comments, &communities, &users, sort))
+  } else {
+    None
+  };
+
+  let community_post_groups = if data.group_by_community.unwrap_or_default() {
+    Some(group_by_community(std::mem::take(&mut posts)))
+  } else {
+    None
+  };
+
   // Return the jwt
   Ok(Json(SearchResponse {
     type_: search_type,
@@ -208,5 +421,782 @@ pub async fn search(
     posts,
     communities,
     users,
+    crosspost_counts,
+    partial: deadline.timed_out.get().then_some(true),
+    community_post_groups,
+    suggestions,
+    counts: SearchCounts {
+      posts: post_count.unwrap_or_default(),
+      comments: comment_count.unwrap_or_default(),
+      communities: community_count.unwrap_or_default(),
+      users: user_count.unwrap_or_default(),
+      incomplete: [post_count, comment_count, community_count, user_count].contains(&None),
+    },
+    merged,
   }))
 }
+
+/// Checks the length of the trimmed search term. Scoped searches (within a community or by a
+/// creator) may have an empty term.
+fn check_search_term(
+  search_term: &str,
+  scoped: bool,
+  search_type: Option<SearchType>,
+) -> LemmyResult<()> {
+  let len = search_term.chars().count();
+  if len < SEARCH_TERM_MIN_LENGTH && !scoped {
+    Err(LemmyErrorType::SearchQueryTooShort)?
+  }
+  let max_length = if search_type == Some(SearchType::Url) {
+    SEARCH_URL_MAX_LENGTH
+  } else {
+    SEARCH_TERM_MAX_LENGTH
+  };
+  if len > max_length {
+    Err(LemmyErrorType::SearchQueryTooLong)?
+  }
+  Ok(())
+}
+
+/// Removed and deleted posts and comments are left out of search results, unless an admin asks
+/// for them.
+fn include_removed_and_deleted(data: &Search, is_admin: bool) -> (bool, bool) {
+  (
+    is_admin && data.include_removed.unwrap_or_default(),
+    is_admin && data.include_deleted.unwrap_or_default(),
+  )
+}
+
+/// The sort types which are meaningful for a search type. Other sorts can't be applied to the
+/// type, and would silently fall back to a different order.
+fn valid_sorts_for(search_type: SearchType) -> &'static [SortType] {
+  match search_type {
+    SearchType::All | SearchType::Posts | SearchType::Url => ALL_SORTS,
+    // Comments have no comment count
+    SearchType::Comments => &[
+      SortType::Active,
+      SortType::Hot,
+      SortType::New,
+      SortType::Old,
+      SortType::TopDay,
+      SortType::TopWeek,
+      SortType::TopMonth,
+      SortType::TopYear,
+      SortType::TopAll,
+      SortType::NewComments,
+      SortType::TopHour,
+      SortType::TopSixHour,
+      SortType::TopTwelveHour,
+      SortType::TopThreeMonths,
+      SortType::TopSixMonths,
+      SortType::TopNineMonths,
+      SortType::Controversial,
+      SortType::Scaled,
+      SortType::Relevance,
+    ],
+    // Communities have no votes, or a time of their latest comment
+    SearchType::Communities => &[
+      SortType::Active,
+      SortType::Hot,
+      SortType::New,
+      SortType::Old,
+      SortType::TopDay,
+      SortType::TopWeek,
+      SortType::TopMonth,
+      SortType::TopYear,
+      SortType::TopAll,
+      SortType::MostComments,
+      SortType::TopHour,
+      SortType::TopSixHour,
+      SortType::TopTwelveHour,
+      SortType::TopThreeMonths,
+      SortType::TopSixMonths,
+      SortType::TopNineMonths,
+      SortType::Scaled,
+    ],
+    // Users can only be sorted by age, comment count or total comment score
+    SearchType::Users => &[
+      SortType::New,
+      SortType::Old,
+      SortType::MostComments,
+      SortType::TopAll,
+    ],
+  }
+}
+
+/// Replaces the generic error for an unparseable search query with a specific one, so that clients
+/// can tell an invalid search type apart from an invalid sort type.
+pub fn search_query_error(err: QueryPayloadError, req: &HttpRequest) -> actix_web::Error {
+  let error_type = Query::<HashMap<String, String>>::from_query(req.query_string())
+    .ok()
+    .and_then(|params| invalid_search_param(&params));
+  match error_type {
+    Some(error_type) => LemmyError::from(error_type).into(),
+    None => err.into(),
+  }
+}
+
+fn invalid_search_param(params: &HashMap<String, String>) -> Option<LemmyErrorType> {
+  if params
+    .get("type_")
+    .is_some_and(|t| SearchType::from_str(t).is_err())
+  {
+    Some(LemmyErrorType::InvalidSearchType)
+  } else if params
+    .get("sort")
+    .is_some_and(|s| SortType::from_str(s).is_err())
+  {
+    Some(LemmyErrorType::InvalidSortType)
+  } else {
+    None
+  }
+}
+
+/// Shared deadline for all queries of a single search. Postgres cancels queries which exceed it,
+/// and they return no results instead of failing the whole request.
+struct SoftDeadline {
+  deadline: Option<Instant>,
+  /// A cell, so that several queries can run concurrently with the same deadline.
+  timed_out: Cell<bool>,
+}
+
+impl SoftDeadline {
+  fn new(enabled: bool) -> Self {
+    SoftDeadline {
+      deadline: enabled.then(|| Instant::now() + SEARCH_SOFT_DEADLINE),
+      timed_out: Cell::new(false),
+    }
+  }
+
+  async fn run<'a, T, F>(&self, pool: &mut DbPool<'_>, query: F) -> LemmyResult<T>
+  where
+    T: Default + 'a,
+    F: for<'r> FnOnce(&'r mut AsyncPgConnection) -> ScopedBoxFuture<'a, 'r, Result<T, DieselError>>
+      + Send
+      + 'a,
+  {
+    let Some(deadline) = self.deadline else {
+      let conn = &mut get_conn(pool).await?;
+      return Ok(query(conn).await?);
+    };
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    let res = if remaining.is_zero() {
+      None
+    } else {
+      match run_with_statement_timeout(pool, remaining, query).await {
+        Err(e) if is_statement_timeout(&e) => None,
+        res => Some(res?),
+      }
+    };
+    if res.is_none() {
+      self.timed_out.set(true);
+    }
+    Ok(res.unwrap_or_default())
+  }
+}
+
+/// Lists the posts matching the query, and counts all of them. The count is None if it
+/// timed out.
+async fn search_posts(
+  query: PostQuery<'_>,
+  site: &Site,
+  deadline: &SoftDeadline,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<(Option<i64>, Vec<PostView>)> {
+  let query_ref = &query;
+  let count = deadline
+    .run(pool, |conn| {
+      Box::pin(async move { query_ref.count(site, &mut conn.into()).await.map(Some) }) as _
+    })
+    .await?;
+  let posts = deadline
+    .run(pool, |conn| {
+      Box::pin(async move { query.list(site, &mut conn.into()).await }) as _
+    })
+    .await?;
+  Ok((count, posts))
+}
+
+/// Lists the comments matching the query, and counts all of them. The count is None if it
+/// timed out.
+async fn search_comments(
+  query: CommentQuery<'_>,
+  deadline: &SoftDeadline,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<(Option<i64>, Vec<CommentView>)> {
+  let query_ref = &query;
+  let count = deadline
+    .run(pool, |conn| {
+      Box::pin(async move { query_ref.count(&mut conn.into()).await.map(Some) }) as _
+    })
+    .await?;
+  let comments = deadline
+    .run(pool, |conn| {
+      Box::pin(async move { query.list(&mut conn.into()).await }) as _
+    })
+    .await?;
+  Ok((count, comments))
+}
+
+/// Lists the communities matching the query, if there is one, and counts all of them. The count
+/// is None if it timed out.
+async fn search_communities(
+  query: Option<CommunityQuery<'_>>,
+  site: &Site,
+  deadline: &SoftDeadline,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<(Option<i64>, Vec<CommunityView>)> {
+  let Some(query) = query else {
+    return Ok((Some(0), vec![]));
+  };
+  let query_ref = &query;
+  let count = deadline
+    .run(pool, |conn| {
+      Box::pin(async move { query_ref.count(site, &mut conn.into()).await.map(Some) }) as _
+    })
+    .await?;
+  let communities = deadline
+    .run(pool, |conn| {
+      Box::pin(async move { query.list(site, &mut conn.into()).await }) as _
+    })
+    .await?;
+  Ok((count, communities))
+}
+
+/// Lists the users matching the query, if there is one, and counts all of them. The count is
+/// None if it timed out.
+async fn search_users(
+  query: Option<PersonQuery>,
+  deadline: &SoftDeadline,
+  pool: &mut DbPool<'_>,
+) -> LemmyResult<(Option<i64>, Vec<PersonView>)> {
+  let Some(query) = query else {
+    return Ok((Some(0), vec![]));
+  };
+  let query_ref = &query;
+  let count = deadline
+    .run(pool, |conn| {
+      Box::pin(async move { query_ref.count(&mut conn.into()).await.map(Some) }) as _
+    })
+    .await?;
+  let users = deadline
+    .run(pool, |conn| {
+      Box::pin(async move { query.list(&mut conn.into()).await }) as _
+    })
+    .await?;
+  Ok((count, users))
+}
+
+/// Puts the results of all types into a single list. New and old sorts order by publish date, all
+/// other sorts by score (subscribers for communities, total score for users).
+fn merge_results(
+  posts: &[PostView],
+  comments: &[CommentView],
+  communities: &[CommunityView],
+  users: &[PersonView],
+  sort: Option<SortType>,
+) -> Vec<SearchResultItem> {
+  let mut merged: Vec<SearchResultItem> = posts
+    .iter()
+    .cloned()
+    .map(SearchResultItem::Post)
+    .chain(comments.iter().cloned().map(SearchResultItem::Comment))
+    .chain(communities.iter().cloned().map(SearchResultItem::Community))
+    .chain(users.iter().cloned().map(SearchResultItem::Person))
+    .collect();
+  match sort.unwrap_or_default() {
+    SortType::New | SortType::NewComments => merged.sort_by_key(|i| Reverse(result_published(i))),
+    SortType::Old => merged.sort_by_key(result_published),
+    _ => merged.sort_by_key(|i| Reverse((result_score(i), result_published(i)))),
+  }
+  merged
+}
+
+fn result_published(item: &SearchResultItem) -> DateTime<Utc> {
+  match item {
+    SearchResultItem::Post(p) => p.post.published,
+    SearchResultItem::Comment(c) => c.comment.published,
+    SearchResultItem::Community(c) => c.community.published,
+    SearchResultItem::Person(p) => p.person.published,
+  }
+}
+
+fn result_score(item: &SearchResultItem) -> i64 {
+  match item {
+    SearchResultItem::Post(p) => p.counts.score,
+    SearchResultItem::Comment(c) => c.counts.score,
+    SearchResultItem::Community(c) => c.counts.subscribers,
+    SearchResultItem::Person(p) => p.counts.post_score + p.counts.comment_score,
+  }
+}
+
+/// Keeps only the first (best ranked) post for each url, and counts how many other posts linking
+/// to the same url were dropped. Posts without url are left untouched.
+fn collapse_crossposts(posts: Vec<PostView>) -> (Vec<PostView>, Vec<CrosspostCount>) {
+  let mut collapsed = Vec::with_capacity(posts.len());
+  let mut counts: Vec<CrosspostCount> = vec![];
+  let mut seen_urls = HashMap::new();
+
+  for post_view in posts {
+    let Some(key) = post_view.post.url.as_ref().map(|u| crosspost_key(u)) else {
+      collapsed.push(post_view);
+      continue;
+    };
+    if let Some(count) = seen_urls.get(&key).and_then(|i: &usize| counts.get_mut(*i)) {
+      count.crosspost_count += 1;
+    } else {
+      seen_urls.insert(key, counts.len());
+      counts.push(CrosspostCount {
+        post_id: post_view.post.id,
+        crosspost_count: 0,
+      });
+      collapsed.push(post_view);
+    }
+  }
+  (collapsed, counts)
+}
+
+/// Groups posts by their community. Groups are ordered by their best ranked post, and posts keep
+/// their order within each group.
+fn group_by_community(posts: Vec<PostView>) -> Vec<CommunityPostGroup> {
+  let mut groups: Vec<CommunityPostGroup> = vec![];
+  let mut group_indexes = HashMap::new();
+
+  for post_view in posts {
+    let community_id = post_view.community.id;
+    if let Some(group) = group_indexes
+      .get(&community_id)
+      .and_then(|i: &usize| groups.get_mut(*i))
+    {
+      group.posts.push(post_view);
+    } else {
+      group_indexes.insert(community_id, groups.len());
+      groups.push(CommunityPostGroup {
+        community: post_view.community.clone(),
+        posts: vec![post_view],
+      });
+    }
+  }
+  groups
+}
+
+/// Normalizes a post url so that trivially different links to the same page are grouped together.
+fn crosspost_key(url: &Url) -> String {
+  let mut url = url.clone();
+  url.set_fragment(None);
+  url.as_str().trim_end_matches('/').to_string()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+  use super::{
+    check_search_term,
+    include_removed_and_deleted,
+    merge_results,
+    search,
+    search_query_error,
+    valid_sorts_for,
+  };
+  use actix_web::{body::to_bytes, test::TestRequest, web::Query};
+  use chrono::DateTime;
+  use lemmy_api_common::{
+    context::LemmyContext,
+    site::{Search, SearchCounts, SearchResultItem},
+  };
+  use lemmy_db_schema::{
+    newtypes::InstanceId,
+    source::{
+      comment::{Comment, CommentInsertForm},
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      local_site::{LocalSite, LocalSiteInsertForm},
+      local_site_rate_limit::{LocalSiteRateLimit, LocalSiteRateLimitInsertForm},
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      site::{Site, SiteInsertForm},
+    },
+    traits::Crud,
+    utils::DbPool,
+    SearchType,
+    SortType,
+  };
+  use lemmy_db_views::structs::{CommentView, LocalUserView, PostView};
+  use lemmy_db_views_actor::structs::{CommunityView, PersonView};
+  use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  async fn search_error(query: &str) -> String {
+    let req = TestRequest::get()
+      .uri(&format!("/api/v3/search?{query}"))
+      .to_http_request();
+    let err = Query::<Search>::from_query(req.query_string())
+      .err()
+      .unwrap();
+    let body = to_bytes(search_query_error(err, &req).error_response().into_body())
+      .await
+      .unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+  }
+
+  #[test]
+  fn test_check_search_term() {
+    assert!(check_search_term("ab", false, None).is_ok());
+    assert!(check_search_term("a", false, None).is_err());
+    assert!(check_search_term("", true, None).is_ok());
+    assert!(check_search_term(&"a".repeat(256), true, None).is_err());
+    let long_url = format!("https://example.com/{}", "a".repeat(300));
+    assert!(check_search_term(&long_url, false, Some(SearchType::Posts)).is_err());
+    assert!(check_search_term(&long_url, false, Some(SearchType::Url)).is_ok());
+    assert!(check_search_term(&"a".repeat(513), false, Some(SearchType::Url)).is_err());
+  }
+
+  #[test]
+  fn test_valid_sorts_for() {
+    assert!(valid_sorts_for(SearchType::All).contains(&SortType::Controversial));
+    assert!(valid_sorts_for(SearchType::Posts).contains(&SortType::MostComments));
+    assert!(!valid_sorts_for(SearchType::Comments).contains(&SortType::MostComments));
+    assert!(!valid_sorts_for(SearchType::Communities).contains(&SortType::Controversial));
+    assert!(valid_sorts_for(SearchType::Users).contains(&SortType::New));
+    assert!(!valid_sorts_for(SearchType::Users).contains(&SortType::Hot));
+  }
+
+  #[test]
+  fn test_include_removed_and_deleted() {
+    let data = Search {
+      include_removed: Some(true),
+      include_deleted: Some(true),
+      ..Default::default()
+    };
+    assert_eq!((true, true), include_removed_and_deleted(&data, true));
+    assert_eq!((false, false), include_removed_and_deleted(&data, false));
+    assert_eq!(
+      (false, false),
+      include_removed_and_deleted(&Search::default(), true)
+    );
+  }
+
+  #[tokio::test]
+  async fn test_search_query_errors() {
+    assert_eq!(
+      r#"{"error":"invalid_search_type"}"#,
+      search_error("q=test&type_=Invalid").await
+    );
+    assert_eq!(
+      r#"{"error":"invalid_sort_type"}"#,
+      search_error("q=test&sort=Invalid").await
+    );
+  }
+
+  async fn create_local_user(
+    pool: &mut DbPool<'_>,
+    instance_id: InstanceId,
+    name: &str,
+    admin: bool,
+  ) -> LemmyResult<LocalUserView> {
+    let person = Person::create(pool, &PersonInsertForm::test_form(instance_id, name)).await?;
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(person.id)
+      .admin(Some(admin))
+      .password_encrypted(String::new())
+      .build();
+    let local_user = LocalUser::create(pool, &local_user_form, vec![]).await?;
+    Ok(
+      LocalUserView::read(pool, local_user.id)
+        .await?
+        .ok_or(LemmyErrorType::CouldntFindLocalUser)?,
+    )
+  }
+
+  async fn create_site(pool: &mut DbPool<'_>, instance_id: InstanceId) -> LemmyResult<Site> {
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(instance_id)
+      .build();
+    let site = Site::create(pool, &site_form).await?;
+    let local_site_form = LocalSiteInsertForm::builder().site_id(site.id).build();
+    let local_site = LocalSite::create(pool, &local_site_form).await?;
+    let rate_limit_form = LocalSiteRateLimitInsertForm::builder()
+      .local_site_id(local_site.id)
+      .build();
+    LocalSiteRateLimit::create(pool, &rate_limit_form).await?;
+    Ok(site)
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_removed_posts_are_only_found_by_admins() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let site = create_site(pool, instance.id).await?;
+
+    let admin_view = create_local_user(pool, instance.id, "search_admin", true).await?;
+    let user_view = create_local_user(pool, instance.id, "search_user", false).await?;
+
+    let community_form = CommunityInsertForm::builder()
+      .name("search_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("removed search result".to_string())
+      .creator_id(user_view.person.id)
+      .community_id(community.id)
+      .removed(Some(true))
+      .build();
+    Post::create(pool, &post_form).await?;
+
+    let query = || {
+      Query(Search {
+        q: "removed search result".to_string(),
+        type_: Some(SearchType::Posts),
+        include_removed: Some(true),
+        ..Default::default()
+      })
+    };
+    let admin_res = search(query(), context.reset_request_count(), Some(admin_view)).await?;
+    assert_eq!(1, admin_res.posts.len());
+    assert_eq!(
+      SearchCounts {
+        posts: 1,
+        ..Default::default()
+      },
+      admin_res.counts
+    );
+    let user_res = search(query(), context.reset_request_count(), Some(user_view)).await?;
+    assert_eq!(0, user_res.posts.len());
+    assert_eq!(SearchCounts::default(), user_res.counts);
+    let anonymous_res = search(query(), context.reset_request_count(), None).await?;
+    assert_eq!(0, anonymous_res.posts.len());
+
+    Community::delete(pool, community.id).await?;
+    Site::delete(pool, site.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_nsfw_posts_are_hidden_from_anonymous_users() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    // The site allows nsfw content, but has no content warning
+    let site = create_site(pool, instance.id).await?;
+    let user_view = create_local_user(pool, instance.id, "search_nsfw_user", false).await?;
+
+    let community_form = CommunityInsertForm::builder()
+      .name("search_nsfw_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("nsfw search result".to_string())
+      .creator_id(user_view.person.id)
+      .community_id(community.id)
+      .nsfw(Some(true))
+      .build();
+    Post::create(pool, &post_form).await?;
+
+    let query = |type_| {
+      Query(Search {
+        q: "nsfw search result".to_string(),
+        type_: Some(type_),
+        ..Default::default()
+      })
+    };
+    for type_ in [SearchType::Posts, SearchType::All] {
+      let anonymous_res = search(query(type_), context.reset_request_count(), None).await?;
+      assert_eq!(0, anonymous_res.posts.len());
+    }
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, user_view.person.id).await?;
+    Site::delete(pool, site.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_published_date_range() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let site = create_site(pool, instance.id).await?;
+    let user_view = create_local_user(pool, instance.id, "search_date_user", false).await?;
+
+    let community_form = CommunityInsertForm::builder()
+      .name("search_date_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let date =
+      |year| DateTime::parse_from_rfc3339(&format!("{year}-01-01T00:00:00Z")).map(|d| d.to_utc());
+    let post_form = |name: &str, published| {
+      PostInsertForm::builder()
+        .name(name.to_string())
+        .creator_id(user_view.person.id)
+        .community_id(community.id)
+        .published(Some(published))
+        .build()
+    };
+    let old_post = Post::create(pool, &post_form("date range old", date(2020)?)).await?;
+    let new_post = Post::create(pool, &post_form("date range new", date(2022)?)).await?;
+    let comment_form = |content: &str, published| {
+      CommentInsertForm::builder()
+        .creator_id(user_view.person.id)
+        .post_id(old_post.id)
+        .content(content.to_string())
+        .published(Some(published))
+        .build()
+    };
+    Comment::create(pool, &comment_form("date range old", date(2020)?), None).await?;
+    let new_comment =
+      Comment::create(pool, &comment_form("date range new", date(2022)?), None).await?;
+
+    let query = |after, before| {
+      Query(Search {
+        q: "date range".to_string(),
+        published_after: Some(after),
+        published_before: Some(before),
+        ..Default::default()
+      })
+    };
+    let res = search(
+      query(date(2021)?, date(2023)?),
+      context.reset_request_count(),
+      None,
+    )
+    .await?;
+    assert_eq!(
+      vec![new_post.id],
+      res.posts.iter().map(|p| p.post.id).collect::<Vec<_>>()
+    );
+    assert_eq!(
+      vec![new_comment.id],
+      res
+        .comments
+        .iter()
+        .map(|c| c.comment.id)
+        .collect::<Vec<_>>()
+    );
+
+    let invalid = search(
+      query(date(2023)?, date(2021)?),
+      context.reset_request_count(),
+      None,
+    )
+    .await;
+    assert_eq!(
+      Some(LemmyErrorType::InvalidDateRange),
+      invalid.err().map(|e| e.error_type)
+    );
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, user_view.person.id).await?;
+    Site::delete(pool, site.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_merge_results() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let person = Person::create(
+      pool,
+      &PersonInsertForm::test_form(instance.id, "merge_user"),
+    )
+    .await?;
+    let community_form = CommunityInsertForm::builder()
+      .name("merge_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let post_form = PostInsertForm::builder()
+      .name("merge post".to_string())
+      .creator_id(person.id)
+      .community_id(community.id)
+      .build();
+    let post = Post::create(pool, &post_form).await?;
+    let comment_form = CommentInsertForm::builder()
+      .creator_id(person.id)
+      .post_id(post.id)
+      .content("merge comment".to_string())
+      .build();
+    let comment = Comment::create(pool, &comment_form, None).await?;
+
+    // Read the views and set the values which are used for sorting
+    let date =
+      |year| DateTime::parse_from_rfc3339(&format!("{year}-01-01T00:00:00Z")).map(|d| d.to_utc());
+    let mut post_view = PostView::read(pool, post.id, None, false).await?.unwrap();
+    post_view.post.published = date(2022)?;
+    post_view.counts.score = 5;
+    let mut comment_view = CommentView::read(pool, comment.id, None).await?.unwrap();
+    comment_view.comment.published = date(2023)?;
+    comment_view.counts.score = 1;
+    let mut community_view = CommunityView::read(pool, community.id, None, false)
+      .await?
+      .unwrap();
+    community_view.community.published = date(2021)?;
+    community_view.counts.subscribers = 10;
+    let mut person_view = PersonView::read(pool, person.id).await?.unwrap();
+    person_view.person.published = date(2020)?;
+    person_view.counts.post_score = 2;
+    person_view.counts.comment_score = 1;
+
+    let merged = |sort| {
+      merge_results(
+        &[post_view.clone()],
+        &[comment_view.clone()],
+        &[community_view.clone()],
+        &[person_view.clone()],
+        Some(sort),
+      )
+      .iter()
+      .map(|item| match item {
+        SearchResultItem::Post(_) => "post",
+        SearchResultItem::Comment(_) => "comment",
+        SearchResultItem::Community(_) => "community",
+        SearchResultItem::Person(_) => "person",
+      })
+      .collect::<Vec<_>>()
+    };
+    assert_eq!(
+      vec!["comment", "post", "community", "person"],
+      merged(SortType::New)
+    );
+    assert_eq!(
+      vec!["person", "community", "post", "comment"],
+      merged(SortType::Old)
+    );
+    // Communities are ranked by subscribers and users by their total score
+    assert_eq!(
+      vec!["community", "post", "person", "comment"],
+      merged(SortType::TopAll)
+    );
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, person.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
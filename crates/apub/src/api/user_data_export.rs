@@ -0,0 +1,320 @@
+use activitypub_federation::config::Data;
+use actix_web::{
+  http::header::CONTENT_DISPOSITION,
+  web::{Bytes, Query},
+  HttpResponse,
+};
+use futures::{stream, StreamExt};
+use lemmy_api_common::{context::LemmyContext, person::ExportUserData, utils::is_admin};
+use lemmy_db_schema::{
+  source::{
+    moderator::{AdminExportUserData, AdminExportUserDataForm},
+    site::Site,
+  },
+  traits::Crud,
+  CommentSortType,
+  SortType,
+};
+use lemmy_db_views::{
+  comment_view::CommentQuery,
+  post_view::PostQuery,
+  private_message_view::PrivateMessageQuery,
+  structs::{LocalUserView, SiteView},
+};
+use lemmy_db_views_actor::structs::CommunityFollowerView;
+use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use serde::Serialize;
+use tracing::warn;
+
+/// Number of items which are read from the database at once, so that the whole export never has
+/// to be held in memory.
+const EXPORT_PAGE_SIZE: i64 = 50;
+
+#[derive(Clone, Copy)]
+enum ExportSection {
+  Posts,
+  Comments,
+  PrivateMessages,
+}
+
+const EXPORT_SECTIONS: [ExportSection; 3] = [
+  ExportSection::Posts,
+  ExportSection::Comments,
+  ExportSection::PrivateMessages,
+];
+
+impl ExportSection {
+  fn name(self) -> &'static str {
+    match self {
+      ExportSection::Posts => "posts",
+      ExportSection::Comments => "comments",
+      ExportSection::PrivateMessages => "private_messages",
+    }
+  }
+}
+
+/// Streams all data of a user as a JSON object. The profile and follows are written first, then
+/// posts, comments and private messages are read and written page by page.
+#[tracing::instrument(skip(context))]
+pub async fn export_user_data(
+  data: Query<ExportUserData>,
+  context: Data<LemmyContext>,
+  local_user_view: LocalUserView,
+) -> LemmyResult<HttpResponse> {
+  let target = match data.person_id {
+    Some(person_id) if person_id != local_user_view.person.id => {
+      is_admin(&local_user_view)?;
+      let target = LocalUserView::read_person(&mut context.pool(), person_id)
+        .await?
+        .ok_or(LemmyErrorType::CouldntFindPerson)?;
+
+      // Mod tables
+      let form = AdminExportUserDataForm {
+        admin_person_id: local_user_view.person.id,
+        other_person_id: target.person.id,
+      };
+      AdminExportUserData::create(&mut context.pool(), &form).await?;
+
+      target
+    }
+    _ => local_user_view,
+  };
+  let site = SiteView::read_local(&mut context.pool())
+    .await?
+    .ok_or(LemmyErrorType::LocalSiteNotSetup)?
+    .site;
+
+  // Usernames only contain alphanumeric characters and underscores, so this is a valid file name
+  let content_disposition = format!(r#"attachment; filename="{}.json""#, target.person.name);
+  let follows = CommunityFollowerView::for_person(&mut context.pool(), target.person.id).await?;
+  let header = format!(
+    r#"{{"local_user_view":{},"follows":{},"#,
+    serde_json::to_string(&target)?,
+    serde_json::to_string(&follows)?
+  );
+
+  let sections = stream::unfold(Some((0, 1)), move |state: Option<(usize, i64)>| {
+    let context = context.clone();
+    let target = target.clone();
+    let site = site.clone();
+    async move {
+      let (index, page) = state?;
+      let section = *EXPORT_SECTIONS.get(index)?;
+      let items = match export_page(section, page, &target, &site, &context).await {
+        Ok(items) => items,
+        Err(e) => {
+          // The response status was already sent, so close the open section and report the error
+          // inside the JSON object instead. The stream ends after this chunk.
+          warn!("Failed to export data of user {}: {e}", target.person.name);
+          let mut chunk = String::new();
+          if page > 1 {
+            chunk.push_str("],");
+          }
+          chunk.push_str(&format!(
+            r#""error":{}"#,
+            serde_json::to_string(&e.error_type).unwrap_or_default()
+          ));
+          return Some((Ok(Bytes::from(chunk)), None));
+        }
+      };
+
+      let mut chunk = String::new();
+      if page == 1 {
+        chunk.push_str(&format!(r#""{}":["#, section.name()));
+      } else if !items.is_empty() {
+        chunk.push(',');
+      }
+      chunk.push_str(&items.join(","));
+      let next = if items.len() < EXPORT_PAGE_SIZE as usize {
+        chunk.push(']');
+        if index + 1 < EXPORT_SECTIONS.len() {
+          chunk.push(',');
+        }
+        (index + 1, 1)
+      } else {
+        (index, page + 1)
+      };
+      Some((Ok::<_, actix_web::Error>(Bytes::from(chunk)), Some(next)))
+    }
+  });
+  let body = stream::once(async move { Ok(Bytes::from(header)) })
+    .chain(sections)
+    .chain(stream::once(async { Ok(Bytes::from_static(b"}")) }));
+
+  Ok(
+    HttpResponse::Ok()
+      .content_type("application/json")
+      .insert_header((CONTENT_DISPOSITION, content_disposition))
+      .streaming(body),
+  )
+}
+
+/// Reads a single page of the given section, with each item serialized as JSON. All content of
+/// the user is included, regardless of their own settings and blocks.
+async fn export_page(
+  section: ExportSection,
+  page: i64,
+  target: &LocalUserView,
+  site: &Site,
+  context: &LemmyContext,
+) -> LemmyResult<Vec<String>> {
+  let pool = &mut context.pool();
+  let person_id = target.person.id;
+  match section {
+    ExportSection::Posts => to_json(
+      PostQuery {
+        creator_id: Some(person_id),
+        local_user: Some(target),
+        sort: Some(SortType::New),
+        include_removed: true,
+        include_deleted: true,
+        show_hidden: true,
+        skip_viewer_filters: true,
+        page: Some(page),
+        limit: Some(EXPORT_PAGE_SIZE),
+        ..Default::default()
+      }
+      .list(site, pool)
+      .await?,
+    ),
+    ExportSection::Comments => to_json(
+      CommentQuery {
+        creator_id: Some(person_id),
+        local_user: Some(target),
+        sort: Some(CommentSortType::New),
+        include_removed: true,
+        include_deleted: true,
+        skip_viewer_filters: true,
+        page: Some(page),
+        limit: Some(EXPORT_PAGE_SIZE),
+        ..Default::default()
+      }
+      .list(pool)
+      .await?,
+    ),
+    ExportSection::PrivateMessages => to_json(
+      PrivateMessageQuery {
+        page: Some(page),
+        limit: Some(EXPORT_PAGE_SIZE),
+        ..Default::default()
+      }
+      .list(pool, person_id)
+      .await?,
+    ),
+  }
+}
+
+fn to_json<T: Serialize>(items: Vec<T>) -> LemmyResult<Vec<String>> {
+  Ok(
+    items
+      .iter()
+      .map(serde_json::to_string)
+      .collect::<Result<_, _>>()?,
+  )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+  use super::{export_page, ExportSection};
+  use lemmy_api_common::context::LemmyContext;
+  use lemmy_db_schema::{
+    source::{
+      comment::{Comment, CommentInsertForm},
+      community::{Community, CommunityInsertForm},
+      community_block::{CommunityBlock, CommunityBlockForm},
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm},
+      site::{Site, SiteInsertForm},
+    },
+    traits::{Blockable, Crud},
+  };
+  use lemmy_db_views::structs::LocalUserView;
+  use lemmy_utils::{error::LemmyResult, LemmyErrorType};
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_export_ignores_own_filters() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let pool = &mut context.pool();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(instance.id)
+      .build();
+    let site = Site::create(pool, &site_form).await?;
+
+    let person =
+      Person::create(pool, &PersonInsertForm::test_form(instance.id, "exporter")).await?;
+    // Nsfw content is hidden for the user
+    let local_user_form = LocalUserInsertForm::builder()
+      .person_id(person.id)
+      .password_encrypted(String::new())
+      .show_nsfw(Some(false))
+      .build();
+    let local_user = LocalUser::create(pool, &local_user_form, vec![]).await?;
+    let local_user_view = LocalUserView::read(pool, local_user.id)
+      .await?
+      .ok_or(LemmyErrorType::CouldntFindLocalUser)?;
+
+    let community_form = CommunityInsertForm::builder()
+      .name("export_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(instance.id)
+      .nsfw(Some(true))
+      .build();
+    let community = Community::create(pool, &community_form).await?;
+    let block_form = CommunityBlockForm {
+      person_id: person.id,
+      community_id: community.id,
+    };
+    CommunityBlock::block(pool, &block_form).await?;
+
+    let post_form = |name: &str, removed, deleted| {
+      PostInsertForm::builder()
+        .name(name.to_string())
+        .creator_id(person.id)
+        .community_id(community.id)
+        .removed(Some(removed))
+        .deleted(Some(deleted))
+        .build()
+    };
+    let post = Post::create(pool, &post_form("nsfw post", false, false)).await?;
+    Post::create(pool, &post_form("removed post", true, false)).await?;
+    Post::create(pool, &post_form("deleted post", false, true)).await?;
+    let comment_form = CommentInsertForm::builder()
+      .creator_id(person.id)
+      .post_id(post.id)
+      .content("deleted comment".to_string())
+      .deleted(Some(true))
+      .build();
+    Comment::create(pool, &comment_form, None).await?;
+
+    let posts = export_page(ExportSection::Posts, 1, &local_user_view, &site, &context).await?;
+    assert_eq!(3, posts.len());
+    let comments = export_page(
+      ExportSection::Comments,
+      1,
+      &local_user_view,
+      &site,
+      &context,
+    )
+    .await?;
+    assert_eq!(1, comments.len());
+    // The content of deleted comments is kept
+    assert!(comments[0].contains("deleted comment"));
+
+    Community::delete(pool, community.id).await?;
+    Person::delete(pool, person.id).await?;
+    Site::delete(pool, site.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    Ok(())
+  }
+}
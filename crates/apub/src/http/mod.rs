@@ -1,16 +1,20 @@
 use crate::{
   activity_lists::SharedInboxActivities,
-  fetcher::user_or_community::UserOrCommunity,
+  fetcher::site_or_community_or_user::{SiteOrCommunityOrUser, SiteOrPersonOrGroup},
   protocol::objects::tombstone::Tombstone,
   FEDERATION_CONTEXT,
 };
 use activitypub_federation::{
   actix_web::inbox::receive_activity,
   config::Data,
+  error::Error as ActivityPubError,
+  fetch::object_id::ObjectId,
   protocol::context::WithContext,
+  traits::{Actor, Object},
   FEDERATION_CONTENT_TYPE,
 };
 use actix_web::{web, web::Bytes, HttpRequest, HttpResponse};
+use chrono::{DateTime, TimeDelta, Utc};
 use http::{header::LOCATION, StatusCode};
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
@@ -18,9 +22,11 @@ use lemmy_db_schema::{
   source::{activity::SentActivity, community::Community},
   CommunityVisibility,
 };
-use lemmy_utils::error::{LemmyErrorType, LemmyResult};
+use lemmy_utils::error::{LemmyError, LemmyErrorType, LemmyResult};
+use moka::future::Cache;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use std::ops::Deref;
+use std::{ops::Deref, time::Duration};
 use url::Url;
 
 mod comment;
@@ -35,8 +41,185 @@ pub async fn shared_inbox(
   body: Bytes,
   data: Data<LemmyContext>,
 ) -> LemmyResult<HttpResponse> {
-  receive_activity::<SharedInboxActivities, UserOrCommunity, LemmyContext>(request, body, &data)
+  let res = receive_activity::<SharedInboxActivities, SiteOrCommunityOrUser, LemmyContext>(
+    request.clone(),
+    body.clone(),
+    &data,
+  )
+  .await;
+
+  // The actor may have replaced its keypair since we last fetched it, eg after an admin rotated
+  // the instance key. The signature is checked before the activity is handled, so it is safe to
+  // receive it again.
+  if res.as_ref().is_err_and(is_signature_invalid) {
+    if let Ok(activity) = serde_json::from_slice::<ActivityActor>(&body) {
+      // Activities which were signed shortly before a rotation can still arrive afterwards
+      if PREVIOUS_KEYS.contains_key(activity.actor.inner()) {
+        let res = receive_activity::<SharedInboxActivities, PreviousKeyActor, LemmyContext>(
+          request.clone(),
+          body.clone(),
+          &data,
+        )
+        .await;
+        if !res.as_ref().is_err_and(is_signature_invalid) {
+          return res;
+        }
+      }
+      if refetch_actor_key(&activity.actor, &data).await? {
+        return receive_activity::<SharedInboxActivities, SiteOrCommunityOrUser, LemmyContext>(
+          request, body, &data,
+        )
+        .await;
+      }
+    }
+  }
+  res
+}
+
+#[derive(Deserialize)]
+struct ActivityActor {
+  actor: ObjectId<SiteOrCommunityOrUser>,
+}
+
+fn is_signature_invalid(error: &LemmyError) -> bool {
+  matches!(
+    error.inner.downcast_ref::<ActivityPubError>(),
+    Some(ActivityPubError::ActivitySignatureInvalid)
+  )
+}
+
+/// Actors which were fetched more recently aren't refetched because of an invalid signature.
+const KEY_REFETCH_MIN_AGE: Duration = Duration::from_secs(60);
+
+/// Each actor is refetched at most once in this interval because of invalid signatures, so that
+/// wrongly signed activities can't be used to send lots of requests to the actor's instance.
+const KEY_REFETCH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// How long the previous key of an actor is accepted after it was replaced.
+const PREVIOUS_KEY_VALIDITY: Duration = Duration::from_secs(10 * 60);
+
+static REFETCHED_ACTORS: Lazy<Cache<Url, ()>> = Lazy::new(|| {
+  Cache::builder()
+    .max_capacity(10000)
+    .time_to_live(KEY_REFETCH_INTERVAL)
+    .build()
+});
+
+/// Public keys which actors used before they were last refetched.
+static PREVIOUS_KEYS: Lazy<Cache<Url, String>> = Lazy::new(|| {
+  Cache::builder()
+    .max_capacity(10000)
+    .time_to_live(PREVIOUS_KEY_VALIDITY)
+    .build()
+});
+
+/// Refetches a remote actor after a signature check with its stored key failed, unless the key
+/// is too new or the actor was refetched recently. Returns true if the key changed.
+async fn refetch_actor_key(
+  actor_id: &ObjectId<SiteOrCommunityOrUser>,
+  data: &Data<LemmyContext>,
+) -> LemmyResult<bool> {
+  if actor_id.is_local(data) {
+    return Ok(false);
+  }
+  // Actors which weren't known before were just fetched
+  let Ok(actor) = actor_id.dereference_local(data).await else {
+    return Ok(false);
+  };
+  if !may_refetch_actor(actor_id.inner(), actor.last_refreshed_at()).await {
+    return Ok(false);
+  }
+
+  let previous_key = actor.public_key_pem().to_string();
+  let actor = actor_id.dereference_forced(data).await?;
+  if actor.public_key_pem() == previous_key {
+    return Ok(false);
+  }
+  PREVIOUS_KEYS
+    .insert(actor_id.inner().clone(), previous_key)
+    .await;
+  Ok(true)
+}
+
+async fn may_refetch_actor(actor_id: &Url, last_refreshed_at: Option<DateTime<Utc>>) -> bool {
+  let min_age = TimeDelta::from_std(KEY_REFETCH_MIN_AGE).unwrap_or_default();
+  if last_refreshed_at.is_some_and(|last_refreshed_at| Utc::now() - last_refreshed_at < min_age) {
+    return false;
+  }
+  REFETCHED_ACTORS
+    .entry(actor_id.clone())
+    .or_insert(())
     .await
+    .is_fresh()
+}
+
+/// An actor with the public key which it used before it was last refetched, see
+/// [PREVIOUS_KEYS].
+#[derive(Debug)]
+struct PreviousKeyActor {
+  actor: SiteOrCommunityOrUser,
+  public_key: String,
+}
+
+#[async_trait::async_trait]
+impl Object for PreviousKeyActor {
+  type DataType = LemmyContext;
+  type Kind = SiteOrPersonOrGroup;
+  type Error = LemmyError;
+
+  fn last_refreshed_at(&self) -> Option<DateTime<Utc>> {
+    self.actor.last_refreshed_at()
+  }
+
+  async fn read_from_id(object_id: Url, data: &Data<Self::DataType>) -> LemmyResult<Option<Self>> {
+    let actor = SiteOrCommunityOrUser::read_from_id(object_id.clone(), data).await?;
+    let public_key = PREVIOUS_KEYS.get(&object_id).await;
+    Ok(
+      actor
+        .zip(public_key)
+        .map(|(actor, public_key)| PreviousKeyActor { actor, public_key }),
+    )
+  }
+
+  async fn delete(self, data: &Data<Self::DataType>) -> LemmyResult<()> {
+    self.actor.delete(data).await
+  }
+
+  async fn into_json(self, _data: &Data<Self::DataType>) -> LemmyResult<Self::Kind> {
+    unimplemented!()
+  }
+
+  async fn verify(
+    apub: &Self::Kind,
+    expected_domain: &Url,
+    data: &Data<Self::DataType>,
+  ) -> LemmyResult<()> {
+    SiteOrCommunityOrUser::verify(apub, expected_domain, data).await
+  }
+
+  async fn from_json(apub: Self::Kind, data: &Data<Self::DataType>) -> LemmyResult<Self> {
+    let actor = SiteOrCommunityOrUser::from_json(apub, data).await?;
+    let public_key = actor.public_key_pem().to_string();
+    Ok(PreviousKeyActor { actor, public_key })
+  }
+}
+
+impl Actor for PreviousKeyActor {
+  fn id(&self) -> Url {
+    self.actor.id()
+  }
+
+  fn public_key_pem(&self) -> &str {
+    &self.public_key
+  }
+
+  fn private_key_pem(&self) -> Option<String> {
+    self.actor.private_key_pem()
+  }
+
+  fn inbox(&self) -> Url {
+    unimplemented!()
+  }
 }
 
 /// Convert the data to json and turn it into an HTTP Response with the correct ActivityPub
@@ -119,3 +302,134 @@ fn check_community_public(community: &Community) -> LemmyResult<()> {
   }
   Ok(())
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+  use super::*;
+  use crate::{
+    objects::instance::{tests::parse_lemmy_instance, ApubSite},
+    protocol::{activities::instance::update::UpdateInstance, tests::file_to_json_object},
+  };
+  use activitypub_federation::{
+    activity_sending::SendActivityTask,
+    config::FederationConfig,
+    http_signatures::generate_actor_keypair,
+  };
+  use actix_web::test::TestRequest;
+  use lemmy_db_schema::{source::site::Site, traits::Crud};
+  use serial_test::serial;
+  use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::TcpListener,
+  };
+  use uuid::Uuid;
+
+  /// Signs the activity like for sending it to a remote inbox, and returns the request which
+  /// arrived there.
+  async fn signed_request(
+    activity: &UpdateInstance,
+    actor: &ApubSite,
+    context: &Data<LemmyContext>,
+  ) -> LemmyResult<(HttpRequest, Bytes)> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let inbox = Url::parse(&format!(
+      "http://localhost:{}/inbox",
+      listener.local_addr()?.port()
+    ))?;
+    let config = FederationConfig::builder()
+      .domain(context.settings().hostname.clone())
+      .app_data(context.deref().clone())
+      .debug(true)
+      .build()
+      .await?;
+    let data = config.to_request_data();
+    let tasks = SendActivityTask::prepare(activity, actor, vec![inbox], &data).await?;
+    let [task] = tasks.as_slice() else {
+      panic!("no task for inbox");
+    };
+
+    let send = async { LemmyResult::Ok(task.sign_and_send(&data).await?) };
+    let receive = async {
+      let (stream, _) = listener.accept().await?;
+      let mut stream = BufReader::new(stream);
+      let mut request_line = String::new();
+      stream.read_line(&mut request_line).await?;
+      let mut request = TestRequest::post().uri("/inbox");
+      let mut content_length = 0;
+      loop {
+        let mut line = String::new();
+        stream.read_line(&mut line).await?;
+        let Some((name, value)) = line.trim_end().split_once(": ") else {
+          break;
+        };
+        if name.eq_ignore_ascii_case("content-length") {
+          content_length = value.parse()?;
+        }
+        request = request.insert_header((name.to_string(), value.to_string()));
+      }
+      let mut body = vec![0; content_length];
+      stream.read_exact(&mut body).await?;
+      stream
+        .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+        .await?;
+      LemmyResult::Ok((request.to_http_request(), Bytes::from(body)))
+    };
+    let (sent, received) = tokio::join!(send, receive);
+    sent?;
+    received
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_shared_inbox_previous_key() -> LemmyResult<()> {
+    let context = LemmyContext::init_test_context().await;
+    let site = parse_lemmy_instance(&context).await?;
+    let mut activity: UpdateInstance =
+      file_to_json_object("assets/lemmy/activities/instance/update_instance.json")?;
+    activity.id = Url::parse(&format!(
+      "https://enterprise.lemmy.ml/activities/update/{}",
+      Uuid::new_v4()
+    ))?;
+
+    // Sign with a key that differs from the stored one
+    let keypair = generate_actor_keypair()?;
+    let mut signer = site.deref().clone();
+    signer.private_key = Some(keypair.private_key);
+    let signer: ApubSite = signer.into();
+
+    // The instance was just fetched, so it isn't refetched for its current key
+    let (request, body) = signed_request(&activity, &signer, &context).await?;
+    let res = shared_inbox(request, body, context.reset_request_count()).await;
+    assert!(res.is_err_and(|e| is_signature_invalid(&e)));
+
+    // After a key rotation the previous key is still accepted
+    let actor_id = site.id();
+    PREVIOUS_KEYS
+      .insert(actor_id.clone(), keypair.public_key)
+      .await;
+    let (request, body) = signed_request(&activity, &signer, &context).await?;
+    let res = shared_inbox(request, body, context.reset_request_count()).await;
+    assert!(res.is_ok());
+
+    PREVIOUS_KEYS.invalidate(&actor_id).await;
+    Site::delete(&mut context.pool(), site.id).await?;
+    Ok(())
+  }
+
+  #[tokio::test]
+  async fn test_may_refetch_actor() -> LemmyResult<()> {
+    let actor_id = Url::parse("https://enterprise.lemmy.ml/u/test_may_refetch_actor")?;
+    let last_refreshed_at = Some(Utc::now() - TimeDelta::days(1));
+
+    // Recently fetched keys are assumed to be current
+    assert!(!may_refetch_actor(&actor_id, Some(Utc::now())).await);
+    assert!(may_refetch_actor(&actor_id, last_refreshed_at).await);
+    // Only one refetch per interval
+    assert!(!may_refetch_actor(&actor_id, last_refreshed_at).await);
+
+    let other_actor_id = Url::parse("https://enterprise.lemmy.ml/u/test_may_refetch_actor_2")?;
+    assert!(may_refetch_actor(&other_actor_id, last_refreshed_at).await);
+    Ok(())
+  }
+}
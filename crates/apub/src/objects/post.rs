@@ -289,7 +289,7 @@ impl Object for ApubPost {
     // write mod log entry for lock
     if Page::is_locked_changed(&old_post, &page.comments_enabled) {
       let form = ModLockPostForm {
-        mod_person_id: creator.id,
+        mod_person_id: Some(creator.id),
         post_id: post.id,
         locked: Some(post.locked),
       };
@@ -0,0 +1,18 @@
+pub mod update;
+
+#[cfg(test)]
+mod tests {
+  use crate::protocol::{
+    activities::instance::update::UpdateInstance,
+    tests::test_parse_lemmy_item,
+  };
+  use lemmy_utils::error::LemmyResult;
+
+  #[test]
+  fn test_parse_lemmy_instance_activities() -> LemmyResult<()> {
+    test_parse_lemmy_item::<UpdateInstance>(
+      "assets/lemmy/activities/instance/update_instance.json",
+    )?;
+    Ok(())
+  }
+}
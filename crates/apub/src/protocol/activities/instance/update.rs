@@ -0,0 +1,21 @@
+use crate::{objects::instance::ApubSite, protocol::objects::instance::Instance};
+use activitypub_federation::{
+  fetch::object_id::ObjectId,
+  kinds::activity::UpdateType,
+  protocol::helpers::deserialize_one_or_many,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+/// Sent to all known instances after the instance actor changed, eg when its keypair was rotated.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInstance {
+  pub(crate) actor: ObjectId<ApubSite>,
+  #[serde(deserialize_with = "deserialize_one_or_many")]
+  pub(crate) to: Vec<Url>,
+  pub(crate) object: Box<Instance>,
+  #[serde(rename = "type")]
+  pub(crate) kind: UpdateType,
+  pub(crate) id: Url,
+}
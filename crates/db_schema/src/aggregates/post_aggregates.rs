@@ -2,14 +2,21 @@ use crate::{
   aggregates::structs::PostAggregates,
   diesel::OptionalExtension,
   newtypes::PostId,
-  schema::{community_aggregates, post, post_aggregates},
+  schema::{community, community_aggregates, post, post_aggregates},
   utils::{
-    functions::{hot_rank, scaled_rank},
+    functions::{hot_rank, scaled_rank, scores_hidden, visible_score},
     get_conn,
     DbPool,
   },
 };
-use diesel::{result::Error, ExpressionMethods, JoinOnDsl, QueryDsl};
+use diesel::{
+  dsl::not,
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  JoinOnDsl,
+  QueryDsl,
+};
 use diesel_async::RunQueryDsl;
 
 impl PostAggregates {
@@ -35,15 +42,17 @@ impl PostAggregates {
       .first::<i64>(conn)
       .await?;
 
+    // Hidden scores are left out of the ranks, so that the order of posts doesn't reveal them
+    let score = || visible_score(post_aggregates::score, post_aggregates::scores_hidden);
     diesel::update(post_aggregates::table.find(post_id))
       .set((
-        post_aggregates::hot_rank.eq(hot_rank(post_aggregates::score, post_aggregates::published)),
+        post_aggregates::hot_rank.eq(hot_rank(score(), post_aggregates::published)),
         post_aggregates::hot_rank_active.eq(hot_rank(
-          post_aggregates::score,
+          score(),
           post_aggregates::newest_comment_time_necro,
         )),
         post_aggregates::scaled_rank.eq(scaled_rank(
-          post_aggregates::score,
+          score(),
           post_aggregates::published,
           users_active_month,
         )),
@@ -51,6 +60,25 @@ impl PostAggregates {
       .get_result::<Self>(conn)
       .await
   }
+
+  /// Clears the scores_hidden flag of posts which are now old enough for their community to show
+  /// the score. Returns the number of updated posts.
+  pub async fn unhide_expired_scores(pool: &mut DbPool<'_>) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let hide_scores_until_hours = community::table
+      .filter(community::id.eq(post_aggregates::community_id))
+      .select(community::hide_scores_until_hours)
+      .single_value();
+    diesel::update(
+      post_aggregates::table.filter(post_aggregates::scores_hidden.and(not(scores_hidden(
+        post_aggregates::published,
+        hide_scores_until_hours,
+      )))),
+    )
+    .set(post_aggregates::scores_hidden.eq(false))
+    .execute(conn)
+    .await
+  }
 }
 
 #[cfg(test)]
@@ -60,16 +88,21 @@ mod tests {
 
   use crate::{
     aggregates::post_aggregates::PostAggregates,
+    newtypes::PostId,
+    schema::post_aggregates,
     source::{
       comment::{Comment, CommentInsertForm, CommentUpdateForm},
-      community::{Community, CommunityInsertForm},
+      community::{Community, CommunityInsertForm, CommunityUpdateForm},
       instance::Instance,
       person::{Person, PersonInsertForm},
       post::{Post, PostInsertForm, PostLike, PostLikeForm},
     },
     traits::{Crud, Likeable},
-    utils::build_db_pool_for_tests,
+    utils::{build_db_pool_for_tests, get_conn, DbPool},
   };
+  use chrono::{TimeDelta, Utc};
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
 
@@ -338,4 +371,105 @@ mod tests {
       .unwrap();
     Instance::delete(pool, inserted_instance.id).await.unwrap();
   }
+
+  async fn scores_hidden(pool: &mut DbPool<'_>, post_id: PostId) -> bool {
+    PostAggregates::read(pool, post_id)
+      .await
+      .unwrap()
+      .unwrap()
+      .scores_hidden
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_scores_hidden() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "thommy_community_agg");
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("TIL_community_agg".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .hide_scores_until_hours(Some(24))
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    let old_post = PostInsertForm::builder()
+      .name("An old test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .published(Some(Utc::now() - TimeDelta::try_days(2).unwrap()))
+      .build();
+    let inserted_old_post = Post::create(pool, &old_post).await.unwrap();
+
+    assert!(scores_hidden(pool, inserted_post.id).await);
+    assert!(!scores_hidden(pool, inserted_old_post.id).await);
+
+    // The ranks are calculated without the hidden score
+    let post_like = PostLikeForm {
+      post_id: inserted_post.id,
+      person_id: inserted_person.id,
+      score: 1,
+    };
+    PostLike::like(pool, &post_like).await.unwrap();
+    let hidden_ranks = PostAggregates::update_ranks(pool, inserted_post.id)
+      .await
+      .unwrap();
+
+    // Changing the community setting updates the flag
+    let form = CommunityUpdateForm {
+      hide_scores_until_hours: Some(None),
+      ..Default::default()
+    };
+    Community::update(pool, inserted_community.id, &form)
+      .await
+      .unwrap();
+    assert!(!scores_hidden(pool, inserted_post.id).await);
+    let visible_ranks = PostAggregates::update_ranks(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert!(visible_ranks.hot_rank > hidden_ranks.hot_rank);
+    assert!(visible_ranks.scaled_rank > hidden_ranks.scaled_rank);
+
+    let form = CommunityUpdateForm {
+      hide_scores_until_hours: Some(Some(24)),
+      ..Default::default()
+    };
+    Community::update(pool, inserted_community.id, &form)
+      .await
+      .unwrap();
+    assert!(scores_hidden(pool, inserted_post.id).await);
+    assert!(!scores_hidden(pool, inserted_old_post.id).await);
+
+    // Posts which are old enough get their flag cleared
+    let conn = &mut get_conn(pool).await.unwrap();
+    diesel::update(post_aggregates::table.find(inserted_old_post.id))
+      .set(post_aggregates::scores_hidden.eq(true))
+      .execute(conn)
+      .await
+      .unwrap();
+    let unhidden = PostAggregates::unhide_expired_scores(&mut conn.into())
+      .await
+      .unwrap();
+    assert_eq!(1, unhidden);
+    assert!(!scores_hidden(pool, inserted_old_post.id).await);
+    assert!(scores_hidden(pool, inserted_post.id).await);
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
 }
@@ -144,6 +144,10 @@ pub struct PostAggregates {
   /// A rank that amplifies smaller communities
   #[serde(skip)]
   pub scaled_rank: f64,
+  /// If the community currently hides the score. Only used for sorting, the score itself is hidden
+  /// by the views.
+  #[serde(skip)]
+  pub scores_hidden: bool,
 }
 
 #[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone)]
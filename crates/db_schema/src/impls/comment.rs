@@ -1,24 +1,36 @@
 use crate::{
   diesel::{DecoratableTarget, OptionalExtension},
   newtypes::{CommentId, DbUrl, PersonId},
-  schema::comment,
-  source::comment::{
-    Comment,
-    CommentInsertForm,
-    CommentLike,
-    CommentLikeForm,
-    CommentSaved,
-    CommentSavedForm,
-    CommentUpdateForm,
+  schema::{comment, community, post},
+  source::{
+    comment::{
+      Comment,
+      CommentInsertForm,
+      CommentLike,
+      CommentLikeForm,
+      CommentSaved,
+      CommentSavedForm,
+      CommentUpdateForm,
+    },
+    community::Community,
   },
   traits::{Crud, Likeable, Saveable},
-  utils::{functions::coalesce, get_conn, naive_now, DbPool, DELETED_REPLACEMENT_TEXT},
+  utils::{
+    functions::coalesce,
+    fuzzy_search,
+    get_conn,
+    naive_now,
+    now,
+    DbPool,
+    DELETED_REPLACEMENT_TEXT,
+  },
 };
 use chrono::{DateTime, Utc};
 use diesel::{
-  dsl::{insert_into, sql_query},
+  dsl::{self, count_star, insert_into, sql_query, IntervalDsl},
   result::Error,
   ExpressionMethods,
+  PgTextExpressionMethods,
   QueryDsl,
 };
 use diesel_async::RunQueryDsl;
@@ -162,6 +174,38 @@ where ca.comment_id = c.id"
       .optional()
   }
 
+  /// Counts the comments of a person in the last 24 hours, and returns the time of the oldest one.
+  pub async fn count_last_day(
+    pool: &mut DbPool<'_>,
+    for_creator_id: PersonId,
+  ) -> Result<(i64, Option<DateTime<Utc>>), Error> {
+    let conn = &mut get_conn(pool).await?;
+    comment::table
+      .filter(comment::creator_id.eq(for_creator_id))
+      .filter(comment::published.gt(now() - 1.days()))
+      .select((count_star(), dsl::min(comment::published)))
+      .first(conn)
+      .await
+  }
+
+  /// Comments which aren't removed yet and contain the search term, together with their
+  /// community. Meant for purging spam, so no other filters are applied.
+  pub async fn list_unremoved_matching(
+    pool: &mut DbPool<'_>,
+    search_term: &str,
+    limit: i64,
+  ) -> Result<Vec<(Self, Community)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    comment::table
+      .inner_join(post::table.inner_join(community::table))
+      .filter(comment::removed.eq(false))
+      .filter(comment::content.ilike(fuzzy_search(search_term)))
+      .select((comment::all_columns, community::all_columns))
+      .limit(limit)
+      .load::<(Self, Community)>(conn)
+      .await
+  }
+
   pub fn parent_comment_id(&self) -> Option<CommentId> {
     let mut ltree_split: Vec<&str> = self.path.0.split('.').collect();
     ltree_split.remove(0); // The first is always 0
@@ -172,6 +216,20 @@ where ca.comment_id = c.id"
       None
     }
   }
+
+  /// The ids of all comments above this one in the tree, starting with the top-level comment.
+  pub fn ancestor_comment_ids(&self) -> Vec<CommentId> {
+    let mut ids: Vec<CommentId> = self
+      .path
+      .0
+      .split('.')
+      .skip(1) // The first is always 0
+      .filter_map(|p| p.parse::<i32>().map(CommentId).ok())
+      .collect();
+    // The last one is the comment itself
+    ids.pop();
+    ids
+  }
 }
 
 #[async_trait]
@@ -427,6 +485,11 @@ mod tests {
       format!("0.{}.{}", expected_comment.id, inserted_child_comment.id),
       inserted_child_comment.path.0,
     );
+    assert_eq!(
+      vec![inserted_comment.id],
+      inserted_child_comment.ancestor_comment_ids()
+    );
+    assert!(inserted_comment.ancestor_comment_ids().is_empty());
     assert_eq!(1, like_removed);
     assert_eq!(1, saved_removed);
     assert_eq!(1, num_deleted);
@@ -1,7 +1,7 @@
 use crate::{
   diesel::{DecoratableTarget, OptionalExtension},
-  newtypes::{CommunityId, DbUrl, PersonId},
-  schema::{community, community_follower, instance},
+  newtypes::{CommunityFlairId, CommunityId, DbUrl, PersonId},
+  schema::{community, community_follower, instance, local_user},
   source::{
     actor_language::CommunityLanguage,
     community::{
@@ -19,13 +19,15 @@ use crate::{
   },
   traits::{ApubActor, Bannable, Crud, Followable, Joinable},
   utils::{
-    functions::{coalesce, lower},
+    functions::{coalesce, lower, similarity, TrgmSimilar},
     get_conn,
     DbPool,
+    SITEMAP_LIMIT,
   },
+  CommunityVisibility,
   SubscribedType,
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeDelta, Utc};
 use diesel::{
   deserialize,
   dsl,
@@ -37,6 +39,7 @@ use diesel::{
   update,
   BoolExpressionMethods,
   ExpressionMethods,
+  IntoSql,
   NullableExpressionMethods,
   QueryDsl,
   Queryable,
@@ -192,6 +195,271 @@ impl Community {
       .await?;
     Ok(())
   }
+
+  /// Public local communities, with their last modification time.
+  pub async fn list_for_sitemap(
+    pool: &mut DbPool<'_>,
+  ) -> Result<Vec<(DbUrl, DateTime<Utc>)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community::table
+      .select((
+        community::actor_id,
+        coalesce(community::updated, community::published),
+      ))
+      .into_boxed()
+      .filter(community::local.eq(true))
+      .filter(community::deleted.eq(false))
+      .filter(community::removed.eq(false))
+      .filter(community::nsfw.eq(false))
+      .filter(community::visibility.eq(CommunityVisibility::Public))
+      .order(community::published.desc())
+      .limit(SITEMAP_LIMIT)
+      .load::<(DbUrl, DateTime<Utc>)>(conn)
+      .await
+  }
+
+  /// Names of public communities which are similar to the given text, most similar first. Used
+  /// to suggest alternatives for searches with few results. Nsfw communities are only included if
+  /// show_nsfw is true.
+  pub async fn similar_names(
+    pool: &mut DbPool<'_>,
+    text: &str,
+    limit: usize,
+    show_nsfw: bool,
+  ) -> Result<Vec<String>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = community::table.into_boxed();
+    if !show_nsfw {
+      query = query.filter(community::nsfw.eq(false));
+    }
+    let names = query
+      .filter(TrgmSimilar::new(
+        community::name,
+        text.into_sql::<sql_types::Text>(),
+      ))
+      .filter(community::deleted.eq(false))
+      .filter(community::removed.eq(false))
+      .filter(community::visibility.eq(CommunityVisibility::Public))
+      .order_by(similarity(community::name, text).desc())
+      .select(community::name)
+      // Communities on different instances often have the same name, these are deduplicated below
+      .limit((limit * 5).try_into().unwrap_or(i64::MAX))
+      .load::<String>(conn)
+      .await?;
+
+    let mut suggestions: Vec<String> = Vec::with_capacity(limit);
+    for name in names {
+      if !suggestions.contains(&name) && name != text {
+        suggestions.push(name);
+      }
+    }
+    suggestions.truncate(limit);
+    Ok(suggestions)
+  }
+
+  /// Whether the score of a post or comment published at the given time is only shown to mods.
+  pub fn hides_scores(&self, published: DateTime<Utc>) -> bool {
+    self
+      .hide_scores_until_hours
+      .and_then(|hours| TimeDelta::try_hours(hours.into()))
+      .is_some_and(|duration| published > Utc::now() - duration)
+  }
+
+  /// Returns the communities in which the person can see hidden scores, or None if they can see
+  /// them everywhere because they are an admin.
+  pub async fn hidden_scores_visible_in(
+    pool: &mut DbPool<'_>,
+    for_person_id: PersonId,
+  ) -> Result<Option<Vec<CommunityId>>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let is_admin = local_user::table
+      .filter(local_user::person_id.eq(for_person_id))
+      .select(local_user::admin)
+      .first::<bool>(conn)
+      .await
+      .optional()?
+      .unwrap_or_default();
+    if is_admin {
+      Ok(None)
+    } else {
+      CommunityModerator::get_person_moderated_communities(&mut conn.into(), for_person_id)
+        .await
+        .map(Some)
+    }
+  }
+
+  /// Whether the given community, or any community if none is given, hides the scores of new
+  /// posts and comments. Comment listings sorted by score only need the slower
+  /// [hidden_scores_sort_bigint] ordering if so.
+  ///
+  /// [hidden_scores_sort_bigint]: crate::utils::functions::hidden_scores_sort_bigint
+  pub async fn any_hides_scores(
+    pool: &mut DbPool<'_>,
+    for_community_id: Option<CommunityId>,
+  ) -> Result<bool, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let mut query = community::table
+      .filter(community::hide_scores_until_hours.is_not_null())
+      .into_boxed();
+    if let Some(for_community_id) = for_community_id {
+      query = query.filter(community::id.eq(for_community_id));
+    }
+    select(exists(query)).get_result(conn).await
+  }
+
+  /// Moves all posts, local followers and moderators of a community into another one, and marks
+  /// it as removed. Remote followers stay with the removed community, because their instances
+  /// don't know about the new follow. Callers should refuse to merge communities which have any.
+  ///
+  /// Returns the number of moved posts, followers and moderators.
+  pub async fn merge_into(
+    pool: &mut DbPool<'_>,
+    from_community_id: CommunityId,
+    into_community_id: CommunityId,
+  ) -> Result<(usize, usize, usize), Error> {
+    use crate::schema::{
+      community_aggregates,
+      community_moderator,
+      community_person_ban,
+      person,
+      post,
+      post_aggregates,
+    };
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          // Flairs belong to the old community, so they are dropped from the moved posts. Posts
+          // which are featured in the old community stay featured in the new one.
+          let posts_moved = update(post::table.filter(post::community_id.eq(from_community_id)))
+            .set((
+              post::community_id.eq(into_community_id),
+              post::flair_id.eq(None::<CommunityFlairId>),
+            ))
+            .execute(conn)
+            .await?;
+          update(
+            post_aggregates::table.filter(post_aggregates::community_id.eq(from_community_id)),
+          )
+          .set(post_aggregates::community_id.eq(into_community_id))
+          .execute(conn)
+          .await?;
+
+          // The post and comment counts are only updated by triggers when posts are created or
+          // deleted, so move them over manually
+          let (from_posts, from_comments) = community_aggregates::table
+            .find(from_community_id)
+            .select((community_aggregates::posts, community_aggregates::comments))
+            .first::<(i64, i64)>(conn)
+            .await?;
+          update(community_aggregates::table.find(into_community_id))
+            .set((
+              community_aggregates::posts.eq(community_aggregates::posts + from_posts),
+              community_aggregates::comments.eq(community_aggregates::comments + from_comments),
+            ))
+            .execute(conn)
+            .await?;
+          update(community_aggregates::table.find(from_community_id))
+            .set((
+              community_aggregates::posts.eq(0),
+              community_aggregates::comments.eq(0),
+            ))
+            .execute(conn)
+            .await?;
+
+          // Followers and moderators are inserted and deleted, so that the subscriber counts are
+          // updated by triggers. Existing entries of the target community are kept.
+          let follower_forms = community_follower::table
+            .inner_join(person::table)
+            .filter(community_follower::community_id.eq(from_community_id))
+            .filter(person::local.eq(true))
+            .select((community_follower::person_id, community_follower::pending))
+            .load::<(PersonId, bool)>(conn)
+            .await?
+            .into_iter()
+            .map(|(person_id, pending)| CommunityFollowerForm {
+              community_id: into_community_id,
+              person_id,
+              pending,
+            })
+            .collect::<Vec<_>>();
+          let followers_moved = insert_into(community_follower::table)
+            .values(&follower_forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          diesel::delete(
+            community_follower::table
+              .filter(community_follower::community_id.eq(from_community_id))
+              .filter(
+                community_follower::person_id.eq_any(
+                  person::table
+                    .filter(person::local.eq(true))
+                    .select(person::id),
+                ),
+              ),
+          )
+          .execute(conn)
+          .await?;
+
+          let moderator_forms = community_moderator::table
+            .filter(community_moderator::community_id.eq(from_community_id))
+            .order_by(community_moderator::published)
+            .select(community_moderator::person_id)
+            .load::<PersonId>(conn)
+            .await?
+            .into_iter()
+            .map(|person_id| CommunityModeratorForm {
+              community_id: into_community_id,
+              person_id,
+            })
+            .collect::<Vec<_>>();
+          let moderators_moved = insert_into(community_moderator::table)
+            .values(&moderator_forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+          diesel::delete(
+            community_moderator::table
+              .filter(community_moderator::community_id.eq(from_community_id)),
+          )
+          .execute(conn)
+          .await?;
+
+          // Bans are copied instead of moved, so that they still apply if the old community is
+          // restored. Existing bans in the target community are kept.
+          let ban_forms = community_person_ban::table
+            .filter(community_person_ban::community_id.eq(from_community_id))
+            .select((
+              community_person_ban::person_id,
+              community_person_ban::expires,
+            ))
+            .load::<(PersonId, Option<DateTime<Utc>>)>(conn)
+            .await?
+            .into_iter()
+            .map(|(person_id, expires)| CommunityPersonBanForm {
+              community_id: into_community_id,
+              person_id,
+              expires: Some(expires),
+            })
+            .collect::<Vec<_>>();
+          insert_into(community_person_ban::table)
+            .values(&ban_forms)
+            .on_conflict_do_nothing()
+            .execute(conn)
+            .await?;
+
+          update(community::table.find(from_community_id))
+            .set(community::removed.eq(true))
+            .execute(conn)
+            .await?;
+
+          Ok((posts_moved, followers_moved, moderators_moved))
+        }) as _
+      })
+      .await
+  }
 }
 
 impl CommunityModerator {
@@ -401,6 +669,9 @@ impl ApubActor for Community {
 mod tests {
 
   use crate::{
+    aggregates::structs::{CommunityAggregates, PostAggregates},
+    newtypes::PersonId,
+    schema::community_person_ban,
     source::{
       community::{
         Community,
@@ -413,13 +684,18 @@ mod tests {
         CommunityPersonBanForm,
         CommunityUpdateForm,
       },
+      community_flair::{CommunityFlair, CommunityFlairInsertForm},
       instance::Instance,
       person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm, PostUpdateForm},
     },
     traits::{Bannable, Crud, Followable, Joinable},
-    utils::build_db_pool_for_tests,
+    utils::{build_db_pool_for_tests, get_conn},
     CommunityVisibility,
   };
+  use chrono::{TimeDelta, Utc};
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
   use pretty_assertions::assert_eq;
   use serial_test::serial;
 
@@ -476,6 +752,8 @@ mod tests {
       posting_restricted_to_mods: false,
       instance_id: inserted_instance.id,
       visibility: CommunityVisibility::Public,
+      default_sort_type: None,
+      hide_scores_until_hours: None,
     };
 
     let community_follower_form = CommunityFollowerForm {
@@ -566,5 +844,199 @@ mod tests {
     assert_eq!(1, unban);
     // assert_eq!(2, loaded_count);
     assert_eq!(1, num_deleted);
+
+    assert!(!expected_community.hides_scores(Utc::now()));
+    let hiding_community = Community {
+      hide_scores_until_hours: Some(2),
+      ..expected_community
+    };
+    assert!(hiding_community.hides_scores(Utc::now()));
+    assert!(!hiding_community.hides_scores(Utc::now() - TimeDelta::try_hours(3).unwrap()));
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_merge_into() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("merge_mod".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let community_form = |name: &str| {
+      CommunityInsertForm::builder()
+        .name(name.into())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build()
+    };
+    let from_community = Community::create(pool, &community_form("merge_from"))
+      .await
+      .unwrap();
+    let into_community = Community::create(pool, &community_form("merge_into"))
+      .await
+      .unwrap();
+
+    let follower_form = CommunityFollowerForm {
+      community_id: from_community.id,
+      person_id: inserted_person.id,
+      pending: false,
+    };
+    CommunityFollower::follow(pool, &follower_form)
+      .await
+      .unwrap();
+    let remote_person_form = PersonInsertForm::builder()
+      .name("merge_remote_follower".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .local(Some(false))
+      .build();
+    let remote_person = Person::create(pool, &remote_person_form).await.unwrap();
+    let remote_follower_form = CommunityFollowerForm {
+      person_id: remote_person.id,
+      ..follower_form
+    };
+    CommunityFollower::follow(pool, &remote_follower_form)
+      .await
+      .unwrap();
+    let moderator_form = CommunityModeratorForm {
+      community_id: from_community.id,
+      person_id: inserted_person.id,
+    };
+    CommunityModerator::join(pool, &moderator_form)
+      .await
+      .unwrap();
+    let flair_form = CommunityFlairInsertForm {
+      community_id: from_community.id,
+      name: "Discussion".into(),
+    };
+    let flair = CommunityFlair::create(pool, &flair_form).await.unwrap();
+    let post_form = PostInsertForm::builder()
+      .name("A test post".into())
+      .creator_id(inserted_person.id)
+      .community_id(from_community.id)
+      .flair_id(Some(flair.id))
+      .build();
+    let inserted_post = Post::create(pool, &post_form).await.unwrap();
+    let feature_form = PostUpdateForm {
+      featured_community: Some(true),
+      ..Default::default()
+    };
+    Post::update(pool, inserted_post.id, &feature_form)
+      .await
+      .unwrap();
+    let ban_form = CommunityPersonBanForm {
+      community_id: from_community.id,
+      person_id: remote_person.id,
+      expires: None,
+    };
+    CommunityPersonBan::ban(pool, &ban_form).await.unwrap();
+
+    let moved = Community::merge_into(pool, from_community.id, into_community.id)
+      .await
+      .unwrap();
+    assert_eq!((1, 1, 1), moved);
+
+    let moved_post = Post::read(pool, inserted_post.id).await.unwrap().unwrap();
+    assert_eq!(into_community.id, moved_post.community_id);
+    assert_eq!(None, moved_post.flair_id);
+    assert!(moved_post.featured_community);
+    let moved_post_aggregates = PostAggregates::read(pool, inserted_post.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(into_community.id, moved_post_aggregates.community_id);
+    assert!(moved_post_aggregates.featured_community);
+    let into_bans = community_person_ban::table
+      .filter(community_person_ban::community_id.eq(into_community.id))
+      .select(community_person_ban::person_id)
+      .load::<PersonId>(&mut get_conn(pool).await.unwrap())
+      .await
+      .unwrap();
+    assert_eq!(vec![remote_person.id], into_bans);
+    let read_from_community = Community::read(pool, from_community.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert!(read_from_community.removed);
+
+    let into_aggregates = CommunityAggregates::read(pool, into_community.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(1, into_aggregates.posts);
+    assert_eq!(1, into_aggregates.subscribers);
+    let from_aggregates = CommunityAggregates::read(pool, from_community.id)
+      .await
+      .unwrap()
+      .unwrap();
+    assert_eq!(0, from_aggregates.posts);
+    // The remote follower isn't moved
+    assert_eq!(1, from_aggregates.subscribers);
+
+    Community::delete(pool, from_community.id).await.unwrap();
+    Community::delete(pool, into_community.id).await.unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Person::delete(pool, remote_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_similar_names() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_community = CommunityInsertForm::builder()
+      .name("programming".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+    let nsfw_community_form = CommunityInsertForm::builder()
+      .name("cooking_nsfw".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .nsfw(Some(true))
+      .build();
+    let nsfw_community = Community::create(pool, &nsfw_community_form).await.unwrap();
+
+    let suggestions = Community::similar_names(pool, "programing", 5, false)
+      .await
+      .unwrap();
+    assert_eq!(vec!["programming".to_string()], suggestions);
+    // The search text itself is not suggested
+    let suggestions = Community::similar_names(pool, "programming", 5, false)
+      .await
+      .unwrap();
+    assert!(suggestions.is_empty());
+    let suggestions = Community::similar_names(pool, "cooking", 5, false)
+      .await
+      .unwrap();
+    assert!(suggestions.is_empty());
+    let suggestions = Community::similar_names(pool, "cooking", 5, true)
+      .await
+      .unwrap();
+    assert_eq!(vec!["cooking_nsfw".to_string()], suggestions);
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Community::delete(pool, nsfw_community.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
   }
 }
@@ -0,0 +1,140 @@
+use crate::{
+  newtypes::{CommunityFlairId, CommunityId},
+  schema::community_flair,
+  source::community_flair::{CommunityFlair, CommunityFlairInsertForm, CommunityFlairUpdateForm},
+  traits::Crud,
+  utils::{get_conn, DbPool},
+};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+#[async_trait]
+impl Crud for CommunityFlair {
+  type InsertForm = CommunityFlairInsertForm;
+  type UpdateForm = CommunityFlairUpdateForm;
+  type IdType = CommunityFlairId;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    insert_into(community_flair::table)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    flair_id: CommunityFlairId,
+    form: &Self::UpdateForm,
+  ) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(community_flair::table.find(flair_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+impl CommunityFlair {
+  pub async fn list_for_community(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_flair::table
+      .filter(community_flair::community_id.eq(community_id))
+      .order_by(community_flair::name)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    newtypes::CommunityId,
+    source::{
+      community::{Community, CommunityInsertForm},
+      community_flair::{CommunityFlair, CommunityFlairInsertForm, CommunityFlairUpdateForm},
+      instance::Instance,
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, map_unique_violation},
+  };
+  use lemmy_utils::error::LemmyErrorType;
+  use pretty_assertions::{assert_eq, assert_ne};
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_crud() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("flair_community".into())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let flair_form = CommunityFlairInsertForm {
+      community_id: inserted_community.id,
+      name: "Discussion".to_string(),
+    };
+    let inserted_flair = CommunityFlair::create(pool, &flair_form).await.unwrap();
+
+    // Flair names are unique per community
+    let duplicate = CommunityFlair::create(pool, &flair_form).await;
+    assert_eq!(
+      Some(LemmyErrorType::FlairAlreadyExists),
+      map_unique_violation(duplicate, LemmyErrorType::FlairAlreadyExists)
+        .err()
+        .map(|e| e.error_type)
+    );
+    // Other errors are not reported as duplicates
+    let missing_community_form = CommunityFlairInsertForm {
+      community_id: CommunityId(-1),
+      name: "Discussion".to_string(),
+    };
+    let missing_community = CommunityFlair::create(pool, &missing_community_form).await;
+    assert_ne!(
+      Some(LemmyErrorType::FlairAlreadyExists),
+      map_unique_violation(missing_community, LemmyErrorType::FlairAlreadyExists)
+        .err()
+        .map(|e| e.error_type)
+    );
+
+    let update_form = CommunityFlairUpdateForm {
+      name: Some("News".to_string()),
+      ..Default::default()
+    };
+    CommunityFlair::update(pool, inserted_flair.id, &update_form)
+      .await
+      .unwrap();
+
+    let flairs = CommunityFlair::list_for_community(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert_eq!(1, flairs.len());
+    assert_eq!("News", flairs[0].name);
+
+    // Flairs are removed together with their community
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    assert!(CommunityFlair::read(pool, inserted_flair.id)
+      .await
+      .unwrap()
+      .is_none());
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
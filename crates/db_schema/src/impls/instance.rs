@@ -67,6 +67,21 @@ impl Instance {
       }
     }
   }
+
+  /// Read the instance with the given domain, without creating it if it doesn't exist.
+  pub async fn read_from_domain(
+    pool: &mut DbPool<'_>,
+    domain_: &str,
+  ) -> Result<Option<Self>, Error> {
+    use crate::schema::instance::domain;
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .filter(lower(domain).eq(domain_.to_lowercase()))
+      .first(conn)
+      .await
+      .optional()
+  }
+
   pub async fn update(
     pool: &mut DbPool<'_>,
     instance_id: InstanceId,
@@ -94,6 +109,19 @@ impl Instance {
       .await
   }
 
+  /// The number of known instances, not including the local one.
+  pub async fn count_federated(
+    pool: &mut DbPool<'_>,
+    local_instance_id: InstanceId,
+  ) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    instance::table
+      .filter(instance::id.ne(local_instance_id))
+      .count()
+      .get_result(conn)
+      .await
+  }
+
   #[cfg(test)]
   pub async fn delete_all(pool: &mut DbPool<'_>) -> Result<usize, Error> {
     let conn = &mut get_conn(pool).await?;
@@ -14,6 +14,7 @@ use crate::{
   },
 };
 use bcrypt::{hash, DEFAULT_COST};
+use chrono::{TimeDelta, Utc};
 use diesel::{
   dsl::{insert_into, not, IntervalDsl},
   result::Error,
@@ -63,6 +64,37 @@ impl LocalUser {
       .await
   }
 
+  /// Records a visit of the user. A new visit only starts after an hour of inactivity, so that
+  /// content stays new since the previous visit while the user is browsing.
+  ///
+  /// The visit is written at most once per minute, as this is called on every page load.
+  pub async fn update_last_visit(
+    pool: &mut DbPool<'_>,
+    local_user: &LocalUser,
+  ) -> Result<LocalUser, Error> {
+    let now = Utc::now();
+    let since_last_visit = local_user.last_visit.map(|last_visit| now - last_visit);
+    if since_last_visit
+      .is_some_and(|since| since < TimeDelta::try_minutes(1).expect("TimeDelta out of bounds"))
+    {
+      return Ok(local_user.clone());
+    }
+    let previous_visit = match since_last_visit {
+      Some(since) if since > TimeDelta::try_hours(1).expect("TimeDelta out of bounds") => {
+        local_user.last_visit
+      }
+      _ => local_user.previous_visit,
+    };
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(local_user::table.find(local_user.id))
+      .set((
+        local_user::last_visit.eq(now),
+        local_user::previous_visit.eq(previous_visit),
+      ))
+      .get_result::<Self>(conn)
+      .await
+  }
+
   pub async fn delete(pool: &mut DbPool<'_>, id: LocalUserId) -> Result<usize, Error> {
     let conn = &mut *get_conn(pool).await?;
     diesel::delete(local_user::table.find(id))
@@ -228,3 +260,91 @@ pub struct UserBackupLists {
   pub blocked_users: Vec<DbUrl>,
   pub blocked_instances: Vec<String>,
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+
+  use crate::{
+    schema::local_user,
+    source::{
+      instance::Instance,
+      local_user::{LocalUser, LocalUserInsertForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, get_conn, DbPool},
+  };
+  use chrono::{DateTime, TimeDelta, Utc};
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  async fn set_last_visit(
+    pool: &mut DbPool<'_>,
+    local_user: &LocalUser,
+    last_visit: DateTime<Utc>,
+  ) -> LocalUser {
+    let conn = &mut get_conn(pool).await.unwrap();
+    diesel::update(local_user::table.find(local_user.id))
+      .set(local_user::last_visit.eq(last_visit))
+      .get_result::<LocalUser>(conn)
+      .await
+      .unwrap()
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_update_last_visit() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let person_form = PersonInsertForm::test_form(inserted_instance.id, "last_visit");
+    let person = Person::create(pool, &person_form).await.unwrap();
+    let local_user_form = LocalUserInsertForm::test_form(person.id);
+    let local_user = LocalUser::create(pool, &local_user_form, vec![])
+      .await
+      .unwrap();
+
+    // The first visit has no previous one
+    let local_user = LocalUser::update_last_visit(pool, &local_user)
+      .await
+      .unwrap();
+    assert!(local_user.last_visit.is_some());
+    assert_eq!(None, local_user.previous_visit);
+
+    // Pretend that the user was last active two hours ago
+    let local_user = set_last_visit(
+      pool,
+      &local_user,
+      Utc::now() - TimeDelta::try_hours(2).unwrap(),
+    )
+    .await;
+    let previous_activity = local_user.last_visit;
+
+    // This starts a new visit, so the last activity of the old one becomes the previous visit
+    let local_user = LocalUser::update_last_visit(pool, &local_user)
+      .await
+      .unwrap();
+    assert_eq!(previous_activity, local_user.previous_visit);
+    assert!(local_user.last_visit > previous_activity);
+
+    // Activity within the same visit keeps the previous visit
+    let local_user = set_last_visit(
+      pool,
+      &local_user,
+      Utc::now() - TimeDelta::try_minutes(10).unwrap(),
+    )
+    .await;
+    let local_user = LocalUser::update_last_visit(pool, &local_user)
+      .await
+      .unwrap();
+    assert_eq!(previous_activity, local_user.previous_visit);
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
@@ -6,6 +6,7 @@ pub mod comment_reply;
 pub mod comment_report;
 pub mod community;
 pub mod community_block;
+pub mod community_flair;
 pub mod custom_emoji;
 pub mod email_verification;
 pub mod federation_allowlist;
@@ -27,10 +28,12 @@ pub mod person;
 pub mod person_block;
 pub mod person_mention;
 pub mod post;
+pub mod post_edit_history;
 pub mod post_report;
 pub mod private_message;
 pub mod private_message_report;
 pub mod registration_application;
 pub mod secret;
 pub mod site;
+pub mod site_stats_history;
 pub mod tagline;
@@ -1,5 +1,7 @@
 use crate::{
   source::moderator::{
+    AdminExportUserData,
+    AdminExportUserDataForm,
     AdminPurgeComment,
     AdminPurgeCommentForm,
     AdminPurgeCommunity,
@@ -8,6 +10,8 @@ use crate::{
     AdminPurgePersonForm,
     AdminPurgePost,
     AdminPurgePostForm,
+    AdminRotateFederationKey,
+    AdminRotateFederationKeyForm,
     ModAdd,
     ModAddCommunity,
     ModAddCommunityForm,
@@ -464,6 +468,64 @@ impl Crud for AdminPurgeComment {
   }
 }
 
+#[async_trait]
+impl Crud for AdminRotateFederationKey {
+  type InsertForm = AdminRotateFederationKeyForm;
+  type UpdateForm = AdminRotateFederationKeyForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    use crate::schema::admin_rotate_federation_key::dsl::admin_rotate_federation_key;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(admin_rotate_federation_key)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: i32,
+    form: &Self::InsertForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::admin_rotate_federation_key::dsl::admin_rotate_federation_key;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(admin_rotate_federation_key.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
+#[async_trait]
+impl Crud for AdminExportUserData {
+  type InsertForm = AdminExportUserDataForm;
+  type UpdateForm = AdminExportUserDataForm;
+  type IdType = i32;
+
+  async fn create(pool: &mut DbPool<'_>, form: &Self::InsertForm) -> Result<Self, Error> {
+    use crate::schema::admin_export_user_data::dsl::admin_export_user_data;
+    let conn = &mut get_conn(pool).await?;
+    insert_into(admin_export_user_data)
+      .values(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  async fn update(
+    pool: &mut DbPool<'_>,
+    from_id: i32,
+    form: &Self::InsertForm,
+  ) -> Result<Self, Error> {
+    use crate::schema::admin_export_user_data::dsl::admin_export_user_data;
+    let conn = &mut get_conn(pool).await?;
+    diesel::update(admin_export_user_data.find(from_id))
+      .set(form)
+      .get_result::<Self>(conn)
+      .await
+  }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 #[allow(clippy::indexing_slicing)]
@@ -582,7 +644,7 @@ mod tests {
     // lock post
 
     let mod_lock_post_form = ModLockPostForm {
-      mod_person_id: inserted_mod.id,
+      mod_person_id: Some(inserted_mod.id),
       post_id: inserted_post.id,
       locked: None,
     };
@@ -596,7 +658,7 @@ mod tests {
     let expected_mod_lock_post = ModLockPost {
       id: inserted_mod_lock_post.id,
       post_id: inserted_post.id,
-      mod_person_id: inserted_mod.id,
+      mod_person_id: Some(inserted_mod.id),
       locked: true,
       when_: inserted_mod_lock_post.when_,
     };
@@ -1,19 +1,41 @@
 use crate::{
   diesel::OptionalExtension,
-  newtypes::{CommunityId, DbUrl, InstanceId, PersonId},
-  schema::{comment, community, instance, local_user, person, person_follower, post},
-  source::person::{
-    Person,
-    PersonFollower,
-    PersonFollowerForm,
-    PersonInsertForm,
-    PersonUpdateForm,
+  newtypes::{CommunityId, DbUrl, InstanceId, LocalUserId, PersonId},
+  schema::{
+    comment,
+    community,
+    email_verification,
+    federation_queue_state,
+    instance,
+    local_user,
+    password_reset_request,
+    person,
+    person_follower,
+    post,
+    sent_activity,
+  },
+  source::{
+    comment_reply::CommentReply,
+    person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm, PersonUpdateForm},
+    person_mention::PersonMention,
+    private_message::PrivateMessage,
   },
   traits::{ApubActor, Crud, Followable},
   utils::{functions::lower, get_conn, naive_now, DbPool},
+  NotificationType,
+};
+use diesel::{
+  dsl::{exists, insert_into, not},
+  result::Error,
+  BoolExpressionMethods,
+  CombineDsl,
+  ExpressionMethods,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
 };
-use diesel::{dsl::insert_into, result::Error, CombineDsl, ExpressionMethods, JoinOnDsl, QueryDsl};
 use diesel_async::RunQueryDsl;
+use lemmy_utils::error::{LemmyErrorExt, LemmyErrorType, LemmyResult};
 
 #[async_trait]
 impl Crud for Person {
@@ -70,11 +92,31 @@ impl Person {
     let conn = &mut get_conn(pool).await?;
 
     // Set the local user info to none
-    diesel::update(local_user::table.filter(local_user::person_id.eq(person_id)))
-      .set(local_user::email.eq::<Option<String>>(None))
-      .execute(conn)
-      .await?;
+    let local_user_ids =
+      diesel::update(local_user::table.filter(local_user::person_id.eq(person_id)))
+        .set((
+          local_user::email.eq::<Option<String>>(None),
+          local_user::totp_2fa_secret.eq::<Option<String>>(None),
+        ))
+        .returning(local_user::id)
+        .get_results::<LocalUserId>(conn)
+        .await?;
+
+    // Remove pending tokens which were sent by email
+    diesel::delete(
+      password_reset_request::table
+        .filter(password_reset_request::local_user_id.eq_any(&local_user_ids)),
+    )
+    .execute(conn)
+    .await?;
+    diesel::delete(
+      email_verification::table.filter(email_verification::local_user_id.eq_any(&local_user_ids)),
+    )
+    .execute(conn)
+    .await?;
 
+    // The private key is still needed to sign the federated delete activity. It is removed by
+    // scrub_sent_private_keys() once the activity was sent.
     diesel::update(person::table.find(person_id))
       .set((
         person::display_name.eq::<Option<String>>(None),
@@ -89,6 +131,42 @@ impl Person {
       .await
   }
 
+  /// Removes the private keys of deleted local users, once all activities they sent since their
+  /// deletion were delivered to the given instances.
+  pub async fn scrub_sent_private_keys(
+    pool: &mut DbPool<'_>,
+    federating_instance_ids: Vec<InstanceId>,
+  ) -> Result<usize, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let sent_by_person =
+      || sent_activity::table.filter(sent_activity::actor_apub_id.eq(person::actor_id.nullable()));
+    // The delete activity is queued after the account was deleted, so wait until it exists
+    let delete_queued =
+      exists(sent_by_person().filter(sent_activity::published.nullable().ge(person::updated)));
+    let pending = exists(
+      sent_by_person().filter(exists(
+        federation_queue_state::table
+          .filter(federation_queue_state::instance_id.eq_any(federating_instance_ids))
+          .filter(
+            federation_queue_state::last_successful_id
+              .is_null()
+              .or(federation_queue_state::last_successful_id.lt(sent_activity::id.nullable())),
+          ),
+      )),
+    );
+    diesel::update(
+      person::table
+        .filter(person::local.eq(true))
+        .filter(person::deleted.eq(true))
+        .filter(person::private_key.is_not_null())
+        .filter(delete_queued)
+        .filter(not(pending)),
+    )
+    .set(person::private_key.eq::<Option<String>>(None))
+    .execute(conn)
+    .await
+  }
+
   /// Lists local community ids for all posts and comments for a given creator.
   pub async fn list_local_community_ids(
     pool: &mut DbPool<'_>,
@@ -111,6 +189,39 @@ impl Person {
       .load::<CommunityId>(conn)
       .await
   }
+
+  /// Marks the person's notifications of the given type as read, or all of them if no type is
+  /// given.
+  pub async fn mark_notifications_as_read(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    type_: Option<NotificationType>,
+  ) -> LemmyResult<()> {
+    let conn = &mut get_conn(pool).await?;
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          if type_.is_none() || type_ == Some(NotificationType::Replies) {
+            CommentReply::mark_all_as_read(&mut conn.into(), person_id)
+              .await
+              .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
+          }
+          if type_.is_none() || type_ == Some(NotificationType::Mentions) {
+            PersonMention::mark_all_as_read(&mut conn.into(), person_id)
+              .await
+              .with_lemmy_type(LemmyErrorType::CouldntUpdateComment)?;
+          }
+          if type_.is_none() || type_ == Some(NotificationType::PrivateMessages) {
+            PrivateMessage::mark_all_as_read(&mut conn.into(), person_id)
+              .await
+              .with_lemmy_type(LemmyErrorType::CouldntUpdatePrivateMessage)?;
+          }
+          Ok(())
+        }) as _
+      })
+      .await
+  }
 }
 
 impl PersonInsertForm {
@@ -219,15 +330,23 @@ impl PersonFollower {
 mod tests {
 
   use crate::{
+    newtypes::{ActivityId, PersonId},
+    schema::{federation_queue_state, person, sent_activity},
     source::{
+      activity::{ActorType, SentActivity, SentActivityForm},
+      federation_queue_state::FederationQueueState,
       instance::Instance,
       person::{Person, PersonFollower, PersonFollowerForm, PersonInsertForm, PersonUpdateForm},
     },
     traits::{Crud, Followable},
-    utils::build_db_pool_for_tests,
+    utils::{build_db_pool_for_tests, get_conn, DbPool},
   };
+  use diesel::QueryDsl;
+  use diesel_async::RunQueryDsl;
   use pretty_assertions::assert_eq;
+  use serde_json::json;
   use serial_test::serial;
+  use url::Url;
 
   #[tokio::test]
   #[serial]
@@ -333,4 +452,91 @@ mod tests {
     let unfollow = PersonFollower::unfollow(pool, &follow_form).await.unwrap();
     assert_eq!(1, unfollow);
   }
+
+  async fn read_private_key(pool: &mut DbPool<'_>, person_id: PersonId) -> Option<String> {
+    let conn = &mut get_conn(pool).await.unwrap();
+    person::table
+      .find(person_id)
+      .select(person::private_key)
+      .first::<Option<String>>(conn)
+      .await
+      .unwrap()
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_scrub_sent_private_keys() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let remote_instance = Instance::read_or_create(pool, "other_domain.tld".to_string())
+      .await
+      .unwrap();
+    let mut person_form = PersonInsertForm::test_form(inserted_instance.id, "scrub_key");
+    person_form.local = Some(true);
+    person_form.private_key = Some("private key".to_string());
+    let inserted_person = Person::create(pool, &person_form).await.unwrap();
+    Person::delete_account(pool, inserted_person.id)
+      .await
+      .unwrap();
+    let federating = vec![remote_instance.id];
+
+    // The delete activity isn't queued yet
+    let scrubbed = Person::scrub_sent_private_keys(pool, federating.clone())
+      .await
+      .unwrap();
+    assert_eq!(0, scrubbed);
+
+    let activity_form = SentActivityForm {
+      ap_id: Url::parse("http://my_domain.tld/activities/delete/scrub_key")
+        .unwrap()
+        .into(),
+      data: json!({}),
+      sensitive: false,
+      send_inboxes: vec![],
+      send_community_followers_of: None,
+      send_all_instances: true,
+      actor_type: ActorType::Person,
+      actor_apub_id: inserted_person.actor_id.clone(),
+    };
+    let activity = SentActivity::create(pool, activity_form).await.unwrap();
+
+    // The remote instance hasn't received it yet
+    let mut state = FederationQueueState {
+      instance_id: remote_instance.id,
+      last_successful_id: Some(ActivityId(activity.id.0 - 1)),
+      last_successful_published_time: None,
+      fail_count: 0,
+      last_retry: None,
+    };
+    FederationQueueState::upsert(pool, &state).await.unwrap();
+    let scrubbed = Person::scrub_sent_private_keys(pool, federating.clone())
+      .await
+      .unwrap();
+    assert_eq!(0, scrubbed);
+    assert!(read_private_key(pool, inserted_person.id).await.is_some());
+
+    state.last_successful_id = Some(activity.id);
+    FederationQueueState::upsert(pool, &state).await.unwrap();
+    let scrubbed = Person::scrub_sent_private_keys(pool, federating)
+      .await
+      .unwrap();
+    assert_eq!(1, scrubbed);
+    assert_eq!(None, read_private_key(pool, inserted_person.id).await);
+
+    let conn = &mut get_conn(pool).await.unwrap();
+    diesel::delete(sent_activity::table.find(activity.id))
+      .execute(conn)
+      .await
+      .unwrap();
+    diesel::delete(federation_queue_state::table.find(remote_instance.id))
+      .execute(conn)
+      .await
+      .unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+    Instance::delete(pool, remote_instance.id).await.unwrap();
+  }
 }
@@ -1,39 +1,59 @@
 use crate::{
-  diesel::OptionalExtension,
+  diesel::{
+    dsl::{self, count_star, exists, not, IntervalDsl},
+    OptionalExtension,
+  },
   newtypes::{CommunityId, DbUrl, PersonId, PostId},
-  schema::{post, post_hide, post_like, post_read, post_saved},
-  source::post::{
-    Post,
-    PostHide,
-    PostHideForm,
-    PostInsertForm,
-    PostLike,
-    PostLikeForm,
-    PostRead,
-    PostReadForm,
-    PostSaved,
-    PostSavedForm,
-    PostUpdateForm,
+  schema::{
+    community,
+    mod_lock_post,
+    post,
+    post_edit_history,
+    post_hide,
+    post_like,
+    post_read,
+    post_saved,
+  },
+  source::{
+    moderator::ModLockPostForm,
+    post::{
+      Post,
+      PostHide,
+      PostHideForm,
+      PostInsertForm,
+      PostLike,
+      PostLikeForm,
+      PostRead,
+      PostReadForm,
+      PostSaved,
+      PostSavedForm,
+      PostUpdateForm,
+    },
   },
   traits::{Crud, Likeable, Saveable},
   utils::{
     functions::coalesce,
+    fuzzy_search,
     get_conn,
     naive_now,
+    now,
     DbPool,
     DELETED_REPLACEMENT_TEXT,
     FETCH_LIMIT_MAX,
     SITEMAP_DAYS,
     SITEMAP_LIMIT,
   },
+  CommunityVisibility,
 };
 use ::url::Url;
 use chrono::{DateTime, Utc};
 use diesel::{
   dsl::insert_into,
   result::Error,
+  BoolExpressionMethods,
   DecoratableTarget,
   ExpressionMethods,
+  PgTextExpressionMethods,
   QueryDsl,
   TextExpressionMethods,
 };
@@ -116,15 +136,124 @@ impl Post {
       .await
   }
 
+  /// Returns a post in the community with the same url, published within the last hours.
+  pub async fn read_duplicate(
+    pool: &mut DbPool<'_>,
+    the_community_id: CommunityId,
+    url: &DbUrl,
+    window_hours: i32,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post::table
+      .filter(post::community_id.eq(the_community_id))
+      .filter(post::url.eq(url))
+      .filter(post::published.gt(now() - window_hours.hours()))
+      .filter(post::deleted.eq(false))
+      .filter(post::removed.eq(false))
+      .order(post::published.desc())
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+
+  /// Counts the posts of a person in the last 24 hours, and returns the time of the oldest one.
+  pub async fn count_last_day(
+    pool: &mut DbPool<'_>,
+    for_creator_id: PersonId,
+  ) -> Result<(i64, Option<DateTime<Utc>>), Error> {
+    let conn = &mut get_conn(pool).await?;
+    post::table
+      .filter(post::creator_id.eq(for_creator_id))
+      .filter(post::published.gt(now() - 1.days()))
+      .select((count_star(), dsl::min(post::published)))
+      .first(conn)
+      .await
+  }
+
+  /// Locks posts in local communities which are older than the given number of days, and writes a
+  /// modlog entry without a moderator for each of them. Posts which were locked or unlocked by a
+  /// mod before are skipped, so that mods can still reopen individual posts.
+  ///
+  /// Runs in batches, each in its own transaction, so that large numbers of old posts don't
+  /// exceed the limits of a single statement.
+  ///
+  /// Returns the ids of the locked posts.
+  pub async fn lock_older_than(pool: &mut DbPool<'_>, days: i32) -> Result<Vec<PostId>, Error> {
+    const BATCH_SIZE: i64 = 1000;
+
+    let conn = &mut get_conn(pool).await?;
+    let mut locked_post_ids = vec![];
+    loop {
+      let batch: Vec<PostId> = conn
+        .build_transaction()
+        .run(|conn| {
+          Box::pin(async move {
+            let local_communities = community::table
+              .filter(community::local.eq(true))
+              .select(community::id);
+            let old_post_ids: Vec<PostId> = post::table
+              .filter(post::locked.eq(false))
+              .filter(post::deleted.eq(false))
+              .filter(post::removed.eq(false))
+              .filter(post::published.lt(now() - days.days()))
+              .filter(post::community_id.eq_any(local_communities))
+              .filter(not(exists(
+                mod_lock_post::table.filter(mod_lock_post::post_id.eq(post::id)),
+              )))
+              .select(post::id)
+              .limit(BATCH_SIZE)
+              .for_update()
+              .get_results(conn)
+              .await?;
+            let locked_post_ids: Vec<PostId> =
+              diesel::update(post::table.filter(post::id.eq_any(&old_post_ids)))
+                .set(post::locked.eq(true))
+                .returning(post::id)
+                .get_results(conn)
+                .await?;
+
+            let forms: Vec<_> = locked_post_ids
+              .iter()
+              .map(|post_id| ModLockPostForm {
+                mod_person_id: None,
+                post_id: *post_id,
+                locked: Some(true),
+              })
+              .collect();
+            insert_into(mod_lock_post::table)
+              .values(&forms)
+              .execute(conn)
+              .await?;
+
+            Ok::<_, Error>(locked_post_ids)
+          }) as _
+        })
+        .await?;
+
+      let done = (batch.len() as i64) < BATCH_SIZE;
+      locked_post_ids.extend(batch);
+      if done {
+        return Ok(locked_post_ids);
+      }
+    }
+  }
+
   pub async fn list_for_sitemap(
     pool: &mut DbPool<'_>,
   ) -> Result<Vec<(DbUrl, chrono::DateTime<Utc>)>, Error> {
     let conn = &mut get_conn(pool).await?;
     post::table
+      .inner_join(community::table)
       .select((post::ap_id, coalesce(post::updated, post::published)))
+      .into_boxed()
       .filter(post::local.eq(true))
       .filter(post::deleted.eq(false))
       .filter(post::removed.eq(false))
+      .filter(post::nsfw.eq(false))
+      .filter(community::deleted.eq(false))
+      .filter(community::removed.eq(false))
+      .filter(community::nsfw.eq(false))
+      .filter(community::visibility.eq(CommunityVisibility::Public))
       .filter(
         post::published.ge(Utc::now().naive_utc() - SITEMAP_DAYS.expect("TimeDelta out of bounds")),
       )
@@ -139,16 +268,32 @@ impl Post {
     for_creator_id: PersonId,
   ) -> Result<Vec<Self>, Error> {
     let conn = &mut get_conn(pool).await?;
-
-    diesel::update(post::table.filter(post::creator_id.eq(for_creator_id)))
-      .set((
-        post::name.eq(DELETED_REPLACEMENT_TEXT),
-        post::url.eq(Option::<&str>::None),
-        post::body.eq(DELETED_REPLACEMENT_TEXT),
-        post::deleted.eq(true),
-        post::updated.eq(naive_now()),
-      ))
-      .get_results::<Self>(conn)
+    conn
+      .build_transaction()
+      .run(|conn| {
+        Box::pin(async move {
+          let posts = diesel::update(post::table.filter(post::creator_id.eq(for_creator_id)))
+            .set((
+              post::name.eq(DELETED_REPLACEMENT_TEXT),
+              post::url.eq(Option::<&str>::None),
+              post::body.eq(DELETED_REPLACEMENT_TEXT),
+              post::deleted.eq(true),
+              post::updated.eq(naive_now()),
+            ))
+            .get_results::<Self>(conn)
+            .await?;
+
+          // The previous versions would still contain the deleted content
+          let post_ids: Vec<_> = posts.iter().map(|p| p.id).collect();
+          diesel::delete(
+            post_edit_history::table.filter(post_edit_history::post_id.eq_any(post_ids)),
+          )
+          .execute(conn)
+          .await?;
+
+          Ok(posts)
+        }) as _
+      })
       .await
   }
 
@@ -173,6 +318,27 @@ impl Post {
       .await
   }
 
+  /// Posts which aren't removed yet, and contain the search term in their title or body. Meant for
+  /// purging spam, so no other filters are applied.
+  pub async fn list_unremoved_matching(
+    pool: &mut DbPool<'_>,
+    search_term: &str,
+    limit: i64,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let searcher = fuzzy_search(search_term);
+    post::table
+      .filter(post::removed.eq(false))
+      .filter(
+        post::name
+          .ilike(searcher.clone())
+          .or(post::body.ilike(searcher)),
+      )
+      .limit(limit)
+      .load::<Self>(conn)
+      .await
+  }
+
   pub fn is_post_creator(person_id: PersonId, post_creator_id: PersonId) -> bool {
     person_id == post_creator_id
   }
@@ -385,9 +551,11 @@ impl PostHide {
 mod tests {
 
   use crate::{
+    newtypes::DbUrl,
     source::{
       community::{Community, CommunityInsertForm},
       instance::Instance,
+      moderator::{ModLockPost, ModLockPostForm},
       person::{Person, PersonInsertForm},
       post::{
         Post,
@@ -403,6 +571,8 @@ mod tests {
     traits::{Crud, Likeable, Saveable},
     utils::build_db_pool_for_tests,
   };
+  use ::url::Url;
+  use chrono::{TimeDelta, Utc};
   use pretty_assertions::assert_eq;
   use serial_test::serial;
   use std::collections::HashSet;
@@ -455,6 +625,7 @@ mod tests {
       url: None,
       body: None,
       alt_text: None,
+      flair_id: None,
       creator_id: inserted_person.id,
       community_id: inserted_community.id,
       published: inserted_post.published,
@@ -555,4 +726,146 @@ mod tests {
     assert_eq!(expected_post_like, inserted_post_like);
     assert_eq!(expected_post_saved, inserted_post_saved);
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_lock_older_than() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::builder()
+      .name("lock_older_than".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let new_community = CommunityInsertForm::builder()
+      .name("lock_older_than".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let post_form = |name: &str, days_old| {
+      PostInsertForm::builder()
+        .name(name.into())
+        .creator_id(inserted_person.id)
+        .community_id(inserted_community.id)
+        .published(Some(Utc::now() - TimeDelta::try_days(days_old).unwrap()))
+        .build()
+    };
+    let old_post = Post::create(pool, &post_form("old", 10)).await.unwrap();
+    let new_post = Post::create(pool, &post_form("new", 1)).await.unwrap();
+    // Was unlocked by a mod, so it stays open
+    let reopened_post = Post::create(pool, &post_form("reopened", 10))
+      .await
+      .unwrap();
+    let unlock_form = ModLockPostForm {
+      mod_person_id: Some(inserted_person.id),
+      post_id: reopened_post.id,
+      locked: Some(false),
+    };
+    ModLockPost::create(pool, &unlock_form).await.unwrap();
+
+    let locked = Post::lock_older_than(pool, 5).await.unwrap();
+    assert_eq!(vec![old_post.id], locked);
+    assert!(Post::read(pool, old_post.id).await.unwrap().unwrap().locked);
+    assert!(!Post::read(pool, new_post.id).await.unwrap().unwrap().locked);
+    assert!(
+      !Post::read(pool, reopened_post.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .locked
+    );
+
+    // The lock is in the modlog, so it isn't repeated
+    let locked_again = Post::lock_older_than(pool, 5).await.unwrap();
+    assert!(locked_again.is_empty());
+
+    Community::delete(pool, inserted_community.id)
+      .await
+      .unwrap();
+    Person::delete(pool, inserted_person.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_read_duplicate() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let new_person = PersonInsertForm::test_form(inserted_instance.id, "read_duplicate");
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+    let community_form = |name: &str| {
+      CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .build()
+    };
+    let community = Community::create(pool, &community_form("read_duplicate"))
+      .await
+      .unwrap();
+    let other_community = Community::create(pool, &community_form("read_duplicate_other"))
+      .await
+      .unwrap();
+
+    let url: DbUrl = Url::parse("https://example.com/duplicate").unwrap().into();
+    let post_form = |community_id, hours_old| {
+      PostInsertForm::builder()
+        .name("duplicate".into())
+        .url(Some(url.clone()))
+        .creator_id(inserted_person.id)
+        .community_id(community_id)
+        .published(Some(Utc::now() - TimeDelta::try_hours(hours_old).unwrap()))
+        .build()
+    };
+    let old_post = Post::create(pool, &post_form(community.id, 30))
+      .await
+      .unwrap();
+    // Posts in other communities and removed posts don't count as duplicates
+    Post::create(pool, &post_form(other_community.id, 1))
+      .await
+      .unwrap();
+    let removed_post = Post::create(pool, &post_form(community.id, 1))
+      .await
+      .unwrap();
+    let removed_form = PostUpdateForm {
+      removed: Some(true),
+      ..Default::default()
+    };
+    Post::update(pool, removed_post.id, &removed_form)
+      .await
+      .unwrap();
+
+    let duplicate = Post::read_duplicate(pool, community.id, &url, 24)
+      .await
+      .unwrap();
+    assert!(duplicate.is_none());
+    let duplicate = Post::read_duplicate(pool, community.id, &url, 48)
+      .await
+      .unwrap();
+    assert_eq!(Some(old_post.id), duplicate.map(|p| p.id));
+
+    // The newest matching post is returned
+    let new_post = Post::create(pool, &post_form(community.id, 2))
+      .await
+      .unwrap();
+    let duplicate = Post::read_duplicate(pool, community.id, &url, 48)
+      .await
+      .unwrap();
+    assert_eq!(Some(new_post.id), duplicate.map(|p| p.id));
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
 }
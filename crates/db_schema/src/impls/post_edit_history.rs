@@ -0,0 +1,110 @@
+use crate::{
+  newtypes::PostId,
+  schema::post_edit_history,
+  source::post_edit_history::PostEditHistory,
+  utils::{get_conn, DbPool},
+};
+use diesel::{result::Error, ExpressionMethods, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl PostEditHistory {
+  /// Lists the previous versions of a post, oldest first.
+  pub async fn list_for_post(pool: &mut DbPool<'_>, post_id: PostId) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    post_edit_history::table
+      .filter(post_edit_history::post_id.eq(post_id))
+      .order_by(post_edit_history::id)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+      post::{Post, PostInsertForm, PostUpdateForm},
+      post_edit_history::PostEditHistory,
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_edit_history() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+
+    let new_person = PersonInsertForm::builder()
+      .name("history_person".into())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_person = Person::create(pool, &new_person).await.unwrap();
+
+    let new_community = CommunityInsertForm::builder()
+      .name("history_community".to_string())
+      .title("nada".to_owned())
+      .public_key("pubkey".to_string())
+      .instance_id(inserted_instance.id)
+      .build();
+    let inserted_community = Community::create(pool, &new_community).await.unwrap();
+
+    let new_post = PostInsertForm::builder()
+      .name("Original title".into())
+      .creator_id(inserted_person.id)
+      .community_id(inserted_community.id)
+      .build();
+    let inserted_post = Post::create(pool, &new_post).await.unwrap();
+
+    // Changing other fields doesn't create a history entry
+    let lock_form = PostUpdateForm {
+      locked: Some(true),
+      ..Default::default()
+    };
+    Post::update(pool, inserted_post.id, &lock_form)
+      .await
+      .unwrap();
+    let history = PostEditHistory::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(0, history.len());
+
+    let edit_form = PostUpdateForm {
+      name: Some("Edited title".into()),
+      ..Default::default()
+    };
+    Post::update(pool, inserted_post.id, &edit_form)
+      .await
+      .unwrap();
+    let history = PostEditHistory::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(1, history.len());
+    assert_eq!("Original title", history[0].name);
+
+    // Permanently deleting the post also removes its previous versions
+    Post::permadelete_for_creator(pool, inserted_person.id)
+      .await
+      .unwrap();
+    let history = PostEditHistory::list_for_post(pool, inserted_post.id)
+      .await
+      .unwrap();
+    assert_eq!(0, history.len());
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
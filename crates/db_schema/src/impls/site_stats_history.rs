@@ -0,0 +1,155 @@
+use crate::{
+  schema::{comment, person, post, site_stats_history},
+  source::site_stats_history::{SiteStatsHistory, SiteStatsHistoryForm},
+  utils::{get_conn, DbPool},
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use diesel::{dsl::insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
+use diesel_async::RunQueryDsl;
+
+impl SiteStatsHistory {
+  /// Counts the new local users, posts and comments of the day starting at `day`, and stores
+  /// them. Running it again for the same day updates the counts.
+  pub async fn record_day(pool: &mut DbPool<'_>, day: DateTime<Utc>) -> Result<Self, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let day_end = day + TimeDelta::try_days(1).expect("TimeDelta out of bounds");
+
+    let users = person::table
+      .filter(person::local.eq(true))
+      .filter(person::published.ge(day))
+      .filter(person::published.lt(day_end))
+      .count()
+      .get_result::<i64>(conn)
+      .await?;
+    let posts = post::table
+      .filter(post::local.eq(true))
+      .filter(post::published.ge(day))
+      .filter(post::published.lt(day_end))
+      .count()
+      .get_result::<i64>(conn)
+      .await?;
+    let comments = comment::table
+      .filter(comment::local.eq(true))
+      .filter(comment::published.ge(day))
+      .filter(comment::published.lt(day_end))
+      .count()
+      .get_result::<i64>(conn)
+      .await?;
+
+    let form = SiteStatsHistoryForm {
+      day,
+      users,
+      posts,
+      comments,
+    };
+    insert_into(site_stats_history::table)
+      .values(&form)
+      .on_conflict(site_stats_history::day)
+      .do_update()
+      .set(&form)
+      .get_result::<Self>(conn)
+      .await
+  }
+
+  /// Records every day from `from` up to and including `to`, eg to fill in the days which were
+  /// missed while the server was down.
+  pub async fn record_days(
+    pool: &mut DbPool<'_>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+  ) -> Result<Vec<Self>, Error> {
+    let mut recorded = vec![];
+    let mut day = from;
+    while day <= to {
+      recorded.push(Self::record_day(pool, day).await?);
+      day += TimeDelta::try_days(1).expect("TimeDelta out of bounds");
+    }
+    Ok(recorded)
+  }
+
+  /// Returns the most recent recorded day, if any.
+  pub async fn latest_day(pool: &mut DbPool<'_>) -> Result<Option<DateTime<Utc>>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    site_stats_history::table
+      .select(site_stats_history::day)
+      .order_by(site_stats_history::day.desc())
+      .first(conn)
+      .await
+      .optional()
+  }
+
+  /// Lists the recorded days in the given time range, oldest first.
+  pub async fn list(
+    pool: &mut DbPool<'_>,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    site_stats_history::table
+      .filter(site_stats_history::day.ge(from))
+      .filter(site_stats_history::day.lt(to))
+      .order_by(site_stats_history::day)
+      .load::<Self>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    schema::site_stats_history,
+    source::site_stats_history::SiteStatsHistory,
+    utils::{build_db_pool_for_tests, get_conn},
+  };
+  use chrono::{TimeDelta, TimeZone, Utc};
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_record_day() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    // Nothing was created on this day
+    let day = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+    let recorded = SiteStatsHistory::record_day(pool, day).await.unwrap();
+    assert_eq!(0, recorded.posts);
+
+    // Recording the same day again doesn't fail
+    let recorded_again = SiteStatsHistory::record_day(pool, day).await.unwrap();
+    assert_eq!(recorded, recorded_again);
+
+    let week_end = day + TimeDelta::try_days(7).unwrap();
+    let history = SiteStatsHistory::list(pool, day, week_end).await.unwrap();
+    assert_eq!(1, history.len());
+    assert_eq!(day, history[0].day);
+
+    // The days after it are filled in, including the last one
+    let last_day = day + TimeDelta::try_days(3).unwrap();
+    let recorded =
+      SiteStatsHistory::record_days(pool, day + TimeDelta::try_days(1).unwrap(), last_day)
+        .await
+        .unwrap();
+    assert_eq!(3, recorded.len());
+    let history = SiteStatsHistory::list(pool, day, week_end).await.unwrap();
+    assert_eq!(4, history.len());
+    assert_eq!(last_day, history[3].day);
+    assert!(SiteStatsHistory::latest_day(pool).await.unwrap() >= Some(last_day));
+
+    let conn = &mut get_conn(pool).await.unwrap();
+    diesel::delete(
+      site_stats_history::table
+        .filter(site_stats_history::day.ge(day))
+        .filter(site_stats_history::day.lt(week_end)),
+    )
+    .execute(conn)
+    .await
+    .unwrap();
+  }
+}
@@ -2,9 +2,9 @@ use crate::{
   newtypes::LocalSiteId,
   schema::tagline::dsl::{local_site_id, tagline},
   source::tagline::{Tagline, TaglineForm},
-  utils::{get_conn, DbPool},
+  utils::{functions::random, get_conn, DbPool},
 };
-use diesel::{insert_into, result::Error, ExpressionMethods, QueryDsl};
+use diesel::{insert_into, result::Error, ExpressionMethods, OptionalExtension, QueryDsl};
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
 
 impl Tagline {
@@ -55,4 +55,72 @@ impl Tagline {
       .get_results::<Self>(conn)
       .await
   }
+
+  /// Picks one of the taglines at random, or none if the site has no taglines.
+  pub async fn get_random(
+    pool: &mut DbPool<'_>,
+    for_local_site_id: LocalSiteId,
+  ) -> Result<Option<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    tagline
+      .filter(local_site_id.eq(for_local_site_id))
+      .order_by(random())
+      .first::<Self>(conn)
+      .await
+      .optional()
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::{
+    source::{
+      instance::Instance,
+      local_site::{LocalSite, LocalSiteInsertForm},
+      site::{Site, SiteInsertForm},
+      tagline::Tagline,
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+  };
+  use lemmy_utils::error::LemmyResult;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_get_random() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let instance = Instance::read_or_create(pool, "my_domain.tld".to_string()).await?;
+    let site_form = SiteInsertForm::builder()
+      .name("test site".to_string())
+      .instance_id(instance.id)
+      .build();
+    let site = Site::create(pool, &site_form).await?;
+    let local_site_form = LocalSiteInsertForm::builder().site_id(site.id).build();
+    let local_site = LocalSite::create(pool, &local_site_form).await?;
+
+    // No taglines yet
+    assert!(Tagline::get_random(pool, local_site.id).await?.is_none());
+
+    let taglines = Tagline::replace(
+      pool,
+      local_site.id,
+      Some(vec!["first".to_string(), "second".to_string()]),
+    )
+    .await?;
+    let random = Tagline::get_random(pool, local_site.id).await?.unwrap();
+    assert!(taglines.contains(&random));
+
+    Tagline::replace(pool, local_site.id, Some(vec![])).await?;
+    Site::delete(pool, site.id).await?;
+    Instance::delete(pool, instance.id).await?;
+    LocalSite::delete(pool).await?;
+
+    Ok(())
+  }
 }
@@ -81,6 +81,9 @@ pub enum SortType {
   TopNineMonths,
   Controversial,
   Scaled,
+  /// Ranks search results by how well they match the search term. Only has an effect with full
+  /// text search, otherwise it is the same as Hot.
+  Relevance,
 }
 
 #[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
@@ -204,6 +207,8 @@ pub enum ModlogActionType {
   AdminPurgeCommunity,
   AdminPurgePost,
   AdminPurgeComment,
+  AdminRotateFederationKey,
+  AdminExportUserData,
 }
 
 #[derive(
@@ -241,6 +246,55 @@ pub enum CommunityVisibility {
   LocalOnly,
 }
 
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
+)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The size of the time buckets for the site stats history.
+pub enum StatsGranularity {
+  #[default]
+  Day,
+  Week,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
+)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The order of modlog entries.
+pub enum ModlogOrder {
+  /// Newest first.
+  #[default]
+  Desc,
+  /// Oldest first.
+  Asc,
+}
+
+#[derive(
+  EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Hash,
+)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The format in which the modlog is returned.
+pub enum ModlogFormat {
+  #[default]
+  Json,
+  /// An RSS feed of post, comment and community removals, post locks and bans.
+  Rss,
+}
+
+#[derive(EnumString, Display, Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "full", derive(TS))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A kind of notification in the inbox.
+pub enum NotificationType {
+  Replies,
+  Mentions,
+  PrivateMessages,
+}
+
 /// Wrapper for assert_eq! macro. Checks that vec matches the given length, and prints the
 /// vec on failure.
 #[macro_export]
@@ -1,33 +1,42 @@
 // @generated automatically by Diesel CLI.
 
 pub mod sql_types {
-    #[derive(diesel::sql_types::SqlType)]
-    #[diesel(postgres_type(name = "actor_type_enum"))]
-    pub struct ActorTypeEnum;
+  #[derive(diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "actor_type_enum"))]
+  pub struct ActorTypeEnum;
 
-    #[derive(diesel::sql_types::SqlType)]
-    #[diesel(postgres_type(name = "community_visibility"))]
-    pub struct CommunityVisibility;
+  #[derive(diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "community_visibility"))]
+  pub struct CommunityVisibility;
 
-    #[derive(diesel::sql_types::SqlType)]
-    #[diesel(postgres_type(name = "listing_type_enum"))]
-    pub struct ListingTypeEnum;
+  #[derive(diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "listing_type_enum"))]
+  pub struct ListingTypeEnum;
 
-    #[derive(diesel::sql_types::SqlType)]
-    #[diesel(postgres_type(name = "ltree"))]
-    pub struct Ltree;
+  #[derive(diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "ltree"))]
+  pub struct Ltree;
 
-    #[derive(diesel::sql_types::SqlType)]
-    #[diesel(postgres_type(name = "post_listing_mode_enum"))]
-    pub struct PostListingModeEnum;
+  #[derive(diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "post_listing_mode_enum"))]
+  pub struct PostListingModeEnum;
 
-    #[derive(diesel::sql_types::SqlType)]
-    #[diesel(postgres_type(name = "registration_mode_enum"))]
-    pub struct RegistrationModeEnum;
+  #[derive(diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "registration_mode_enum"))]
+  pub struct RegistrationModeEnum;
 
-    #[derive(diesel::sql_types::SqlType)]
-    #[diesel(postgres_type(name = "sort_type_enum"))]
-    pub struct SortTypeEnum;
+  #[derive(diesel::sql_types::SqlType)]
+  #[diesel(postgres_type(name = "sort_type_enum"))]
+  pub struct SortTypeEnum;
+}
+
+diesel::table! {
+    admin_export_user_data (id) {
+        id -> Int4,
+        admin_person_id -> Int4,
+        other_person_id -> Int4,
+        when_ -> Timestamptz,
+    }
 }
 
 diesel::table! {
@@ -68,6 +77,14 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    admin_rotate_federation_key (id) {
+        id -> Int4,
+        admin_person_id -> Int4,
+        when_ -> Timestamptz,
+    }
+}
+
 diesel::table! {
     captcha_answer (uuid) {
         uuid -> Uuid,
@@ -156,6 +173,7 @@ diesel::table! {
 diesel::table! {
     use diesel::sql_types::*;
     use super::sql_types::CommunityVisibility;
+    use super::sql_types::SortTypeEnum;
 
     community (id) {
         id -> Int4,
@@ -191,6 +209,8 @@ diesel::table! {
         #[max_length = 255]
         featured_url -> Nullable<Varchar>,
         visibility -> CommunityVisibility,
+        default_sort_type -> Nullable<SortTypeEnum>,
+        hide_scores_until_hours -> Nullable<Int4>,
     }
 }
 
@@ -218,6 +238,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    community_flair (id) {
+        id -> Int4,
+        community_id -> Int4,
+        #[max_length = 50]
+        name -> Varchar,
+        published -> Timestamptz,
+        updated -> Nullable<Timestamptz>,
+    }
+}
+
 diesel::table! {
     community_follower (person_id, community_id) {
         community_id -> Int4,
@@ -384,6 +415,18 @@ diesel::table! {
         federation_signed_fetch -> Bool,
         default_post_listing_mode -> PostListingModeEnum,
         default_sort_type -> SortTypeEnum,
+        new_user_badge_days -> Int4,
+        duplicate_post_window_hours -> Int4,
+        auto_lock_posts_after_days -> Int4,
+        daily_post_limit -> Int4,
+        daily_comment_limit -> Int4,
+        trusted_account_days -> Int4,
+        trusted_account_min_score -> Int4,
+        downvote_min_score -> Int4,
+        downvote_min_account_days -> Int4,
+        post_title_max_length -> Int4,
+        post_body_max_length -> Int4,
+        comment_max_length -> Int4,
     }
 }
 
@@ -406,6 +449,8 @@ diesel::table! {
         updated -> Nullable<Timestamptz>,
         import_user_settings -> Int4,
         import_user_settings_per_second -> Int4,
+        export_user_data -> Int4,
+        export_user_data_per_second -> Int4,
     }
 }
 
@@ -453,6 +498,8 @@ diesel::table! {
         enable_keyboard_navigation -> Bool,
         enable_animated_images -> Bool,
         collapse_bot_comments -> Bool,
+        last_visit -> Nullable<Timestamptz>,
+        previous_visit -> Nullable<Timestamptz>,
     }
 }
 
@@ -554,7 +601,7 @@ diesel::table! {
 diesel::table! {
     mod_lock_post (id) {
         id -> Int4,
-        mod_person_id -> Int4,
+        mod_person_id -> Nullable<Int4>,
         post_id -> Int4,
         locked -> Bool,
         when_ -> Timestamptz,
@@ -725,6 +772,7 @@ diesel::table! {
         featured_local -> Bool,
         url_content_type -> Nullable<Text>,
         alt_text -> Nullable<Text>,
+        flair_id -> Nullable<Int4>,
     }
 }
 
@@ -747,6 +795,18 @@ diesel::table! {
         controversy_rank -> Float8,
         instance_id -> Int4,
         scaled_rank -> Float8,
+        scores_hidden -> Bool,
+    }
+}
+
+diesel::table! {
+    post_edit_history (id) {
+        id -> Int4,
+        post_id -> Int4,
+        #[max_length = 200]
+        name -> Varchar,
+        body -> Nullable<Text>,
+        published -> Timestamptz,
     }
 }
 
@@ -926,6 +986,15 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    site_stats_history (day) {
+        day -> Timestamptz,
+        users -> Int8,
+        posts -> Int8,
+        comments -> Int8,
+    }
+}
+
 diesel::table! {
     tagline (id) {
         id -> Int4,
@@ -942,6 +1011,7 @@ diesel::joinable!(admin_purge_community -> person (admin_person_id));
 diesel::joinable!(admin_purge_person -> person (admin_person_id));
 diesel::joinable!(admin_purge_post -> community (community_id));
 diesel::joinable!(admin_purge_post -> person (admin_person_id));
+diesel::joinable!(admin_rotate_federation_key -> person (admin_person_id));
 diesel::joinable!(comment -> language (language_id));
 diesel::joinable!(comment -> person (creator_id));
 diesel::joinable!(comment -> post (post_id));
@@ -958,6 +1028,7 @@ diesel::joinable!(community -> instance (instance_id));
 diesel::joinable!(community_aggregates -> community (community_id));
 diesel::joinable!(community_block -> community (community_id));
 diesel::joinable!(community_block -> person (person_id));
+diesel::joinable!(community_flair -> community (community_id));
 diesel::joinable!(community_follower -> community (community_id));
 diesel::joinable!(community_follower -> person (person_id));
 diesel::joinable!(community_language -> community (community_id));
@@ -1006,12 +1077,14 @@ diesel::joinable!(person_mention -> person (recipient_id));
 diesel::joinable!(person_post_aggregates -> person (person_id));
 diesel::joinable!(person_post_aggregates -> post (post_id));
 diesel::joinable!(post -> community (community_id));
+diesel::joinable!(post -> community_flair (flair_id));
 diesel::joinable!(post -> language (language_id));
 diesel::joinable!(post -> person (creator_id));
 diesel::joinable!(post_aggregates -> community (community_id));
 diesel::joinable!(post_aggregates -> instance (instance_id));
 diesel::joinable!(post_aggregates -> person (creator_id));
 diesel::joinable!(post_aggregates -> post (post_id));
+diesel::joinable!(post_edit_history -> post (post_id));
 diesel::joinable!(post_hide -> person (person_id));
 diesel::joinable!(post_hide -> post (post_id));
 diesel::joinable!(post_like -> person (person_id));
@@ -1031,76 +1104,81 @@ diesel::joinable!(site_language -> site (site_id));
 diesel::joinable!(tagline -> local_site (local_site_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
-    admin_purge_comment,
-    admin_purge_community,
-    admin_purge_person,
-    admin_purge_post,
-    captcha_answer,
-    comment,
-    comment_aggregates,
-    comment_like,
-    comment_reply,
-    comment_report,
-    comment_saved,
-    community,
-    community_aggregates,
-    community_block,
-    community_follower,
-    community_language,
-    community_moderator,
-    community_person_ban,
-    custom_emoji,
-    custom_emoji_keyword,
-    email_verification,
-    federation_allowlist,
-    federation_blocklist,
-    federation_queue_state,
-    instance,
-    instance_block,
-    language,
-    local_image,
-    local_site,
-    local_site_rate_limit,
-    local_site_url_blocklist,
-    local_user,
-    local_user_language,
-    local_user_vote_display_mode,
-    login_token,
-    mod_add,
-    mod_add_community,
-    mod_ban,
-    mod_ban_from_community,
-    mod_feature_post,
-    mod_hide_community,
-    mod_lock_post,
-    mod_remove_comment,
-    mod_remove_community,
-    mod_remove_post,
-    mod_transfer_community,
-    password_reset_request,
-    person,
-    person_aggregates,
-    person_ban,
-    person_block,
-    person_follower,
-    person_mention,
-    person_post_aggregates,
-    post,
-    post_aggregates,
-    post_hide,
-    post_like,
-    post_read,
-    post_report,
-    post_saved,
-    private_message,
-    private_message_report,
-    received_activity,
-    registration_application,
-    remote_image,
-    secret,
-    sent_activity,
-    site,
-    site_aggregates,
-    site_language,
-    tagline,
+  admin_export_user_data,
+  admin_purge_comment,
+  admin_purge_community,
+  admin_purge_person,
+  admin_purge_post,
+  admin_rotate_federation_key,
+  captcha_answer,
+  comment,
+  comment_aggregates,
+  comment_like,
+  comment_reply,
+  comment_report,
+  comment_saved,
+  community,
+  community_aggregates,
+  community_block,
+  community_flair,
+  community_follower,
+  community_language,
+  community_moderator,
+  community_person_ban,
+  custom_emoji,
+  custom_emoji_keyword,
+  email_verification,
+  federation_allowlist,
+  federation_blocklist,
+  federation_queue_state,
+  instance,
+  instance_block,
+  language,
+  local_image,
+  local_site,
+  local_site_rate_limit,
+  local_site_url_blocklist,
+  local_user,
+  local_user_language,
+  local_user_vote_display_mode,
+  login_token,
+  mod_add,
+  mod_add_community,
+  mod_ban,
+  mod_ban_from_community,
+  mod_feature_post,
+  mod_hide_community,
+  mod_lock_post,
+  mod_remove_comment,
+  mod_remove_community,
+  mod_remove_post,
+  mod_transfer_community,
+  password_reset_request,
+  person,
+  person_aggregates,
+  person_ban,
+  person_block,
+  person_follower,
+  person_mention,
+  person_post_aggregates,
+  post,
+  post_aggregates,
+  post_edit_history,
+  post_hide,
+  post_like,
+  post_read,
+  post_report,
+  post_saved,
+  private_message,
+  private_message_report,
+  received_activity,
+  registration_application,
+  remote_image,
+  secret,
+  sent_activity,
+  site,
+  site_aggregates,
+  site_language,
+  site_stats_history,
+  tagline,
 );
@@ -4,6 +4,7 @@ use crate::{
   newtypes::{CommunityId, DbUrl, InstanceId, PersonId},
   source::placeholder_apub_url,
   CommunityVisibility,
+  SortType,
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -68,6 +69,11 @@ pub struct Community {
   #[serde(skip)]
   pub featured_url: Option<DbUrl>,
   pub visibility: CommunityVisibility,
+  /// Sort type for posts in the community, if the user doesn't specify one.
+  pub default_sort_type: Option<SortType>,
+  /// Scores of posts and comments younger than this many hours are only shown to mods. Until then,
+  /// score sorts order them by publish time, and score filters don't apply to them.
+  pub hide_scores_until_hours: Option<i32>,
 }
 
 #[derive(Debug, Clone, TypedBuilder, Default)]
@@ -102,6 +108,8 @@ pub struct CommunityInsertForm {
   #[builder(!default)]
   pub instance_id: InstanceId,
   pub visibility: Option<CommunityVisibility>,
+  pub default_sort_type: Option<SortType>,
+  pub hide_scores_until_hours: Option<i32>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -130,6 +138,8 @@ pub struct CommunityUpdateForm {
   pub hidden: Option<bool>,
   pub posting_restricted_to_mods: Option<bool>,
   pub visibility: Option<CommunityVisibility>,
+  pub default_sort_type: Option<Option<SortType>>,
+  pub hide_scores_until_hours: Option<Option<i32>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
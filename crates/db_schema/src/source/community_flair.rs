@@ -0,0 +1,46 @@
+use crate::newtypes::{CommunityFlairId, CommunityId};
+#[cfg(feature = "full")]
+use crate::schema::community_flair;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(PartialEq, Eq, Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(
+  feature = "full",
+  derive(Queryable, Selectable, Associations, Identifiable, TS)
+)]
+#[cfg_attr(feature = "full", diesel(table_name = community_flair))]
+#[cfg_attr(
+  feature = "full",
+  diesel(belongs_to(crate::source::community::Community))
+)]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// A flair which can be assigned to posts of a community. Defined by the community mods.
+pub struct CommunityFlair {
+  pub id: CommunityFlairId,
+  pub community_id: CommunityId,
+  pub name: String,
+  pub published: DateTime<Utc>,
+  pub updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_flair))]
+pub struct CommunityFlairInsertForm {
+  pub community_id: CommunityId,
+  pub name: String,
+}
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "full", derive(AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = community_flair))]
+pub struct CommunityFlairUpdateForm {
+  pub name: Option<String>,
+  pub updated: Option<Option<DateTime<Utc>>>,
+}
@@ -70,6 +70,33 @@ pub struct LocalSite {
   pub default_post_listing_mode: PostListingMode,
   /// Default value for [LocalUser.post_listing_mode]
   pub default_sort_type: SortType,
+  /// Accounts younger than this many days are marked as new in post and comment views.
+  /// Zero disables the marker.
+  pub new_user_badge_days: i32,
+  /// Reject posts linking to the same url as another post in the same community within this many
+  /// hours. Zero disables the check.
+  pub duplicate_post_window_hours: i32,
+  /// Posts in local communities are automatically locked after this many days. Zero disables it.
+  pub auto_lock_posts_after_days: i32,
+  /// Maximum number of posts a user can create within 24 hours. Zero disables the limit.
+  pub daily_post_limit: i32,
+  /// Maximum number of comments a user can create within 24 hours. Zero disables the limit.
+  pub daily_comment_limit: i32,
+  /// Accounts at least this many days old, and with at least [LocalSite.trusted_account_min_score],
+  /// get higher daily post and comment limits. Zero disables it.
+  pub trusted_account_days: i32,
+  /// Minimum combined post and comment score for trusted accounts.
+  pub trusted_account_min_score: i32,
+  /// Minimum combined post and comment score a user needs to downvote. Zero disables the check.
+  pub downvote_min_score: i32,
+  /// Minimum account age in days a user needs to downvote. Zero disables the check.
+  pub downvote_min_account_days: i32,
+  /// Maximum length of post titles. Can't be more than 200.
+  pub post_title_max_length: i32,
+  /// Maximum length of post bodies.
+  pub post_body_max_length: i32,
+  /// Maximum length of comments.
+  pub comment_max_length: i32,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -101,6 +128,18 @@ pub struct LocalSiteInsertForm {
   pub federation_signed_fetch: Option<bool>,
   pub default_post_listing_mode: Option<PostListingMode>,
   pub default_sort_type: Option<SortType>,
+  pub new_user_badge_days: Option<i32>,
+  pub duplicate_post_window_hours: Option<i32>,
+  pub auto_lock_posts_after_days: Option<i32>,
+  pub daily_post_limit: Option<i32>,
+  pub daily_comment_limit: Option<i32>,
+  pub trusted_account_days: Option<i32>,
+  pub trusted_account_min_score: Option<i32>,
+  pub downvote_min_score: Option<i32>,
+  pub downvote_min_account_days: Option<i32>,
+  pub post_title_max_length: Option<i32>,
+  pub post_body_max_length: Option<i32>,
+  pub comment_max_length: Option<i32>,
 }
 
 #[derive(Clone, Default)]
@@ -130,4 +169,16 @@ pub struct LocalSiteUpdateForm {
   pub federation_signed_fetch: Option<bool>,
   pub default_post_listing_mode: Option<PostListingMode>,
   pub default_sort_type: Option<SortType>,
+  pub new_user_badge_days: Option<i32>,
+  pub duplicate_post_window_hours: Option<i32>,
+  pub auto_lock_posts_after_days: Option<i32>,
+  pub daily_post_limit: Option<i32>,
+  pub daily_comment_limit: Option<i32>,
+  pub trusted_account_days: Option<i32>,
+  pub trusted_account_min_score: Option<i32>,
+  pub downvote_min_score: Option<i32>,
+  pub downvote_min_account_days: Option<i32>,
+  pub post_title_max_length: Option<i32>,
+  pub post_body_max_length: Option<i32>,
+  pub comment_max_length: Option<i32>,
 }
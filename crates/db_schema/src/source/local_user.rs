@@ -6,6 +6,7 @@ use crate::{
   PostListingMode,
   SortType,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_with::skip_serializing_none;
 #[cfg(feature = "full")]
@@ -65,6 +66,11 @@ pub struct LocalUser {
   pub enable_animated_images: bool,
   /// Whether to auto-collapse bot comments.
   pub collapse_bot_comments: bool,
+  /// When the user last loaded the site.
+  pub last_visit: Option<DateTime<Utc>>,
+  /// When the user was last active during their previous visit. Content newer than this is new
+  /// since the last visit.
+  pub previous_visit: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, TypedBuilder)]
@@ -10,6 +10,7 @@ pub mod comment_reply;
 pub mod comment_report;
 pub mod community;
 pub mod community_block;
+pub mod community_flair;
 pub mod custom_emoji;
 pub mod custom_emoji_keyword;
 pub mod email_verification;
@@ -32,12 +33,14 @@ pub mod person;
 pub mod person_block;
 pub mod person_mention;
 pub mod post;
+pub mod post_edit_history;
 pub mod post_report;
 pub mod private_message;
 pub mod private_message_report;
 pub mod registration_application;
 pub mod secret;
 pub mod site;
+pub mod site_stats_history;
 pub mod tagline;
 
 /// Default value for columns like [community::Community.inbox_url] which are marked as serde(skip).
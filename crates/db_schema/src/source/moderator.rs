@@ -1,10 +1,12 @@
 use crate::newtypes::{CommentId, CommunityId, PersonId, PostId};
 #[cfg(feature = "full")]
 use crate::schema::{
+  admin_export_user_data,
   admin_purge_comment,
   admin_purge_community,
   admin_purge_person,
   admin_purge_post,
+  admin_rotate_federation_key,
   mod_add,
   mod_add_community,
   mod_ban,
@@ -48,6 +50,7 @@ pub struct ModRemovePostForm {
   pub removed: Option<bool>,
 }
 
+#[skip_serializing_none]
 #[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 #[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
 #[cfg_attr(feature = "full", diesel(table_name = mod_lock_post))]
@@ -56,7 +59,8 @@ pub struct ModRemovePostForm {
 /// When a moderator locks a post (prevents new comments being made).
 pub struct ModLockPost {
   pub id: i32,
-  pub mod_person_id: PersonId,
+  /// Empty for posts which were locked automatically because of their age.
+  pub mod_person_id: Option<PersonId>,
   pub post_id: PostId,
   pub locked: bool,
   pub when_: DateTime<Utc>,
@@ -65,7 +69,7 @@ pub struct ModLockPost {
 #[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
 #[cfg_attr(feature = "full", diesel(table_name = mod_lock_post))]
 pub struct ModLockPostForm {
-  pub mod_person_id: PersonId,
+  pub mod_person_id: Option<PersonId>,
   pub post_id: PostId,
   pub locked: Option<bool>,
 }
@@ -380,3 +384,41 @@ pub struct AdminPurgeCommentForm {
   pub post_id: PostId,
   pub reason: Option<String>,
 }
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_rotate_federation_key))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin replaces the federation keypair of the instance.
+pub struct AdminRotateFederationKey {
+  pub id: i32,
+  pub admin_person_id: PersonId,
+  pub when_: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_rotate_federation_key))]
+pub struct AdminRotateFederationKeyForm {
+  pub admin_person_id: PersonId,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_export_user_data))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin exports the data of another user.
+pub struct AdminExportUserData {
+  pub id: i32,
+  pub admin_person_id: PersonId,
+  pub other_person_id: PersonId,
+  pub when_: DateTime<Utc>,
+}
+
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = admin_export_user_data))]
+pub struct AdminExportUserDataForm {
+  pub admin_person_id: PersonId,
+  pub other_person_id: PersonId,
+}
@@ -1,4 +1,4 @@
-use crate::newtypes::{CommunityId, DbUrl, LanguageId, PersonId, PostId};
+use crate::newtypes::{CommunityFlairId, CommunityId, DbUrl, LanguageId, PersonId, PostId};
 #[cfg(feature = "full")]
 use crate::schema::{post, post_hide, post_like, post_read, post_saved};
 use chrono::{DateTime, Utc};
@@ -58,6 +58,8 @@ pub struct Post {
   pub url_content_type: Option<String>,
   /// An optional alt_text, usable for image posts.
   pub alt_text: Option<String>,
+  /// A flair of the post's community.
+  pub flair_id: Option<CommunityFlairId>,
 }
 
 #[derive(Debug, Clone, TypedBuilder)]
@@ -90,6 +92,7 @@ pub struct PostInsertForm {
   pub featured_local: Option<bool>,
   pub url_content_type: Option<String>,
   pub alt_text: Option<String>,
+  pub flair_id: Option<CommunityFlairId>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -116,6 +119,7 @@ pub struct PostUpdateForm {
   pub featured_local: Option<bool>,
   pub url_content_type: Option<Option<String>>,
   pub alt_text: Option<Option<String>>,
+  pub flair_id: Option<Option<CommunityFlairId>>,
 }
 
 #[derive(PartialEq, Eq, Debug)]
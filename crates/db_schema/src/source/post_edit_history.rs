@@ -0,0 +1,24 @@
+use crate::newtypes::PostId;
+#[cfg(feature = "full")]
+use crate::schema::post_edit_history;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_with::skip_serializing_none;
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[skip_serializing_none]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = post_edit_history))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The title and body of a post before it was edited. Written by a database trigger.
+pub struct PostEditHistory {
+  pub id: i32,
+  pub post_id: PostId,
+  pub name: String,
+  pub body: Option<String>,
+  /// When the post was edited.
+  pub published: DateTime<Utc>,
+}
@@ -0,0 +1,30 @@
+#[cfg(feature = "full")]
+use crate::schema::site_stats_history;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "full")]
+use ts_rs::TS;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "full", derive(Queryable, Selectable, Identifiable, TS))]
+#[cfg_attr(feature = "full", diesel(table_name = site_stats_history))]
+#[cfg_attr(feature = "full", diesel(primary_key(day)))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// The number of new local users, posts and comments within a period, starting at `day`.
+pub struct SiteStatsHistory {
+  pub day: DateTime<Utc>,
+  pub users: i64,
+  pub posts: i64,
+  pub comments: i64,
+}
+
+#[derive(Clone)]
+#[cfg_attr(feature = "full", derive(Insertable, AsChangeset))]
+#[cfg_attr(feature = "full", diesel(table_name = site_stats_history))]
+pub struct SiteStatsHistoryForm {
+  pub day: DateTime<Utc>,
+  pub users: i64,
+  pub posts: i64,
+  pub comments: i64,
+}
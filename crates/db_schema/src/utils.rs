@@ -1,7 +1,9 @@
 use crate::{
+  aggregates::structs::{post_aggregates_keys, PostAggregates},
   diesel::Connection,
   diesel_migrations::MigrationHarness,
   newtypes::DbUrl,
+  schema::post_aggregates,
   CommentSortType,
   SortType,
 };
@@ -9,6 +11,8 @@ use anyhow::Context;
 use chrono::{DateTime, TimeDelta, Utc};
 use deadpool::Runtime;
 use diesel::{
+  dsl::sql,
+  expression::BoxableExpression,
   helper_types::AsExprOf,
   pg::Pg,
   query_builder::{Query, QueryFragment},
@@ -16,7 +20,8 @@ use diesel::{
   result::{
     ConnectionError,
     ConnectionResult,
-    Error::{self as DieselError, QueryBuilderError},
+    DatabaseErrorKind,
+    Error::{self as DieselError, DatabaseError, QueryBuilderError},
   },
   sql_types::{self, Timestamptz},
   IntoSql,
@@ -30,6 +35,7 @@ use diesel_async::{
     AsyncDieselConnectionManager,
     ManagerConfig,
   },
+  scoped_futures::ScopedBoxFuture,
   SimpleAsyncConnection,
 };
 use diesel_migrations::EmbeddedMigrations;
@@ -120,6 +126,43 @@ impl<'a> From<&'a ActualDbPool> for DbPool<'a> {
   }
 }
 
+/// Runs the query in a transaction which Postgres cancels once the timeout has passed. Unlike
+/// dropping the future, this also stops the statement on the server. Use [is_statement_timeout]
+/// to tell the cancellation apart from other errors.
+pub async fn run_with_statement_timeout<'a, T, F>(
+  pool: &mut DbPool<'_>,
+  timeout: Duration,
+  query: F,
+) -> Result<T, DieselError>
+where
+  F: for<'r> FnOnce(&'r mut AsyncPgConnection) -> ScopedBoxFuture<'a, 'r, Result<T, DieselError>>
+    + Send
+    + 'a,
+  T: 'a,
+{
+  // Zero would disable the timeout
+  let timeout_ms = timeout.as_millis().max(1);
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .build_transaction()
+    .run(|conn| {
+      Box::pin(async move {
+        conn
+          .batch_execute(&format!("SET LOCAL statement_timeout = {timeout_ms}"))
+          .await?;
+        query(conn).await
+      }) as _
+    })
+    .await
+}
+
+pub fn is_statement_timeout(err: &DieselError) -> bool {
+  matches!(
+    err,
+    DieselError::DatabaseError(_, info) if info.message().contains("statement timeout")
+  )
+}
+
 /// Runs multiple async functions that take `&mut DbPool<'_>` as input and return `Result`. Only works when the  `futures` crate is listed in `Cargo.toml`.
 ///
 /// `$pool` is the value given to each function.
@@ -175,6 +218,42 @@ where
   }
 }
 
+/// Wraps a score based sort key with `hidden_scores_sort()`, which moves posts with hidden scores
+/// to the front. They are then ordered by the following keys.
+pub struct HiddenScoresKey<K>(pub K);
+
+macro_rules! hidden_scores_key {
+  ($key:ident, $sort_fn:ident, $sql_type:ty) => {
+    impl CursorKey<PostAggregates> for HiddenScoresKey<post_aggregates_keys::$key> {
+      type SqlType = $sql_type;
+      type CursorValue = functions::$sort_fn::HelperType<
+        <post_aggregates_keys::$key as CursorKey<PostAggregates>>::CursorValue,
+        AsExprOf<bool, sql_types::Bool>,
+      >;
+      type SqlValue =
+        functions::$sort_fn::HelperType<post_aggregates::$key, post_aggregates::scores_hidden>;
+
+      fn get_cursor_value(cursor: &PostAggregates) -> Self::CursorValue {
+        functions::$sort_fn(
+          post_aggregates_keys::$key::get_cursor_value(cursor),
+          cursor.scores_hidden.into_sql::<sql_types::Bool>(),
+        )
+      }
+
+      fn get_sql_value() -> Self::SqlValue {
+        functions::$sort_fn(post_aggregates::$key, post_aggregates::scores_hidden)
+      }
+    }
+  };
+}
+
+hidden_scores_key!(score, post_hidden_scores_sort_bigint, sql_types::BigInt);
+hidden_scores_key!(
+  controversy_rank,
+  post_hidden_scores_sort_double,
+  sql_types::Double
+);
+
 /// Includes an SQL comment before `T`, which can be used to label auto_explain output
 #[derive(QueryId)]
 pub struct Commented<T> {
@@ -245,6 +324,37 @@ pub fn fuzzy_search(q: &str) -> String {
   format!("%{replaced}%")
 }
 
+/// The full text search documents of posts and comments. They must be identical to the indexed
+/// expressions of the full_text_search migration, otherwise the indexes aren't used.
+pub const POST_FTS_DOCUMENT: &str =
+  "to_tsvector('english', post.name || ' ' || coalesce(post.body, ''))";
+pub const COMMENT_FTS_DOCUMENT: &str = "to_tsvector('english', comment.content)";
+
+/// Unlike fuzzy_search, this also matches if the words are in a different order or have a
+/// different ending.
+pub fn fts_matches<QS>(
+  document: &str,
+  search_term: &str,
+) -> Box<dyn BoxableExpression<QS, Pg, SqlType = sql_types::Bool>> {
+  Box::new(
+    sql::<sql_types::Bool>(&format!("{document} @@ plainto_tsquery('english', "))
+      .bind::<sql_types::Text, _>(search_term.to_string())
+      .sql(")"),
+  )
+}
+
+/// How well the document matches the search term, for sorting by relevance.
+pub fn fts_rank<QS>(
+  document: &str,
+  search_term: &str,
+) -> Box<dyn BoxableExpression<QS, Pg, SqlType = sql_types::Float>> {
+  Box::new(
+    sql::<sql_types::Float>(&format!("ts_rank({document}, plainto_tsquery('english', "))
+      .bind::<sql_types::Text, _>(search_term.to_string())
+      .sql("))"),
+  )
+}
+
 pub fn limit_and_offset(
   page: Option<i64>,
   limit: Option<i64>,
@@ -297,6 +407,19 @@ pub fn diesel_option_overwrite(opt: Option<String>) -> Option<Option<String>> {
   }
 }
 
+/// Like diesel_option_overwrite, for values which have no empty representation such as ids or
+/// enums. These are erased if `clear` is true, which takes precedence over a new value.
+pub fn diesel_option_overwrite_or_clear<T>(
+  opt: Option<T>,
+  clear: Option<bool>,
+) -> Option<Option<T>> {
+  if clear.unwrap_or_default() {
+    Some(None)
+  } else {
+    opt.map(Some)
+  }
+}
+
 pub fn diesel_option_overwrite_to_url(opt: &Option<String>) -> LemmyResult<Option<Option<DbUrl>>> {
   match opt.as_ref().map(String::as_str) {
     // An empty string is an erase
@@ -319,6 +442,18 @@ pub fn diesel_option_overwrite_to_url_create(opt: &Option<String>) -> LemmyResul
   }
 }
 
+/// Uses `error_type` if the query failed because of a unique constraint, eg for a duplicate name.
+/// Other errors are passed through unchanged.
+pub fn map_unique_violation<T>(
+  res: Result<T, DieselError>,
+  error_type: LemmyErrorType,
+) -> LemmyResult<T> {
+  match res {
+    Err(DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => Err(error_type.into()),
+    res => Ok(res?),
+  }
+}
+
 fn establish_connection(config: &str) -> BoxFuture<ConnectionResult<AsyncPgConnection>> {
   let fut = async {
     let rustls_config = rustls::ClientConfig::builder()
@@ -422,7 +557,9 @@ pub fn naive_now() -> DateTime<Utc> {
 
 pub fn post_to_comment_sort_type(sort: SortType) -> CommentSortType {
   match sort {
-    SortType::Active | SortType::Hot | SortType::Scaled => CommentSortType::Hot,
+    SortType::Active | SortType::Hot | SortType::Scaled | SortType::Relevance => {
+      CommentSortType::Hot
+    }
     SortType::New | SortType::NewComments | SortType::MostComments => CommentSortType::New,
     SortType::Old => CommentSortType::Old,
     SortType::Controversial => CommentSortType::Controversial,
@@ -446,7 +583,7 @@ static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
 });
 
 pub mod functions {
-  use diesel::sql_types::{BigInt, Text, Timestamptz};
+  use diesel::sql_types::{BigInt, Double, Text, Timestamptz};
 
   sql_function! {
     fn hot_rank(score: BigInt, time: Timestamptz) -> Double;
@@ -464,6 +601,41 @@ pub mod functions {
 
   sql_function!(fn lower(x: Text) -> Text);
 
+  sql_function!(fn random() -> Double);
+
+  // Provided by the pg_trgm extension
+  sql_function!(fn similarity(x: Text, y: Text) -> Float);
+
+  // The pg_trgm similarity operator, which unlike `similarity()` can use a trigram index
+  diesel::infix_operator!(TrgmSimilar, " % ", backend: diesel::pg::Pg);
+
+  sql_function!(fn person_is_new(published: Timestamptz) -> Bool);
+
+  sql_function!(fn scores_hidden(published: Timestamptz, hide_scores_until_hours: diesel::sql_types::Nullable<diesel::sql_types::Integer>) -> Bool);
+
+  sql_function! {
+    #[sql_name = "hidden_scores_sort"]
+    fn hidden_scores_sort_bigint(value: BigInt, published: Timestamptz, community_id: diesel::sql_types::Integer) -> BigInt;
+  }
+
+  sql_function! {
+    #[sql_name = "hidden_scores_sort"]
+    fn hidden_scores_sort_double(value: Double, published: Timestamptz, community_id: diesel::sql_types::Integer) -> Double;
+  }
+
+  // Variants for posts, which store whether their score is hidden in post_aggregates
+  sql_function! {
+    #[sql_name = "hidden_scores_sort"]
+    fn post_hidden_scores_sort_bigint(value: BigInt, scores_hidden: diesel::sql_types::Bool) -> BigInt;
+  }
+
+  sql_function! {
+    #[sql_name = "hidden_scores_sort"]
+    fn post_hidden_scores_sort_double(value: Double, scores_hidden: diesel::sql_types::Bool) -> Double;
+  }
+
+  sql_function!(fn visible_score(score: BigInt, scores_hidden: diesel::sql_types::Bool) -> BigInt);
+
   // really this function is variadic, this just adds the two-argument version
   sql_function!(fn coalesce<T: diesel::sql_types::SqlType + diesel::sql_types::SingleValue>(x: diesel::sql_types::Nullable<T>, y: T) -> T);
 }
@@ -542,7 +714,9 @@ impl<RF, LF> Queries<RF, LF> {
 mod tests {
 
   use super::*;
+  use diesel_async::RunQueryDsl;
   use pretty_assertions::assert_eq;
+  use serial_test::serial;
 
   #[test]
   fn test_fuzzy_search() {
@@ -569,6 +743,27 @@ mod tests {
     );
   }
 
+  #[test]
+  fn test_diesel_option_overwrite_or_clear() {
+    assert_eq!(diesel_option_overwrite_or_clear::<i32>(None, None), None);
+    assert_eq!(
+      diesel_option_overwrite_or_clear(Some(1), None),
+      Some(Some(1))
+    );
+    assert_eq!(
+      diesel_option_overwrite_or_clear(Some(1), Some(false)),
+      Some(Some(1))
+    );
+    assert_eq!(
+      diesel_option_overwrite_or_clear::<i32>(None, Some(true)),
+      Some(None)
+    );
+    assert_eq!(
+      diesel_option_overwrite_or_clear(Some(1), Some(true)),
+      Some(None)
+    );
+  }
+
   #[test]
   fn test_diesel_option_overwrite_to_url() {
     assert!(matches!(diesel_option_overwrite_to_url(&None), Ok(None)));
@@ -583,4 +778,34 @@ mod tests {
       Ok(Some(Some(url))) if url == Url::parse(example_url).unwrap().into()
     ));
   }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_run_with_statement_timeout() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let res = run_with_statement_timeout(pool, Duration::from_millis(50), |conn| {
+      Box::pin(async move {
+        diesel::sql_query("SELECT pg_sleep(1)")
+          .execute(conn)
+          .await
+          .map(|_| ())
+      }) as _
+    })
+    .await;
+    assert!(res.is_err_and(|e| is_statement_timeout(&e)));
+
+    // Queries which finish in time return normally
+    let res = run_with_statement_timeout(pool, Duration::from_secs(5), |conn| {
+      Box::pin(async move {
+        diesel::sql_query("SELECT pg_sleep(0.1)")
+          .execute(conn)
+          .await
+          .map(|_| ())
+      }) as _
+    })
+    .await;
+    assert!(res.is_ok());
+  }
 }
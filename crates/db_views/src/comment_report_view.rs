@@ -439,6 +439,8 @@ mod tests {
         featured_url: inserted_community.featured_url,
         instance_id: inserted_instance.id,
         visibility: CommunityVisibility::Public,
+        default_sort_type: None,
+        hide_scores_until_hours: None,
       },
       creator: Person {
         id: inserted_jessica.id,
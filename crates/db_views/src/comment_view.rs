@@ -1,7 +1,7 @@
 use crate::structs::{CommentView, LocalUserView};
 use chrono::{DateTime, Utc};
 use diesel::{
-  dsl::{exists, not},
+  dsl::{exists, not, Eq, InnerJoin, InnerJoinOn, IntoBoxed},
   pg::Pg,
   result::Error,
   sql_types,
@@ -35,12 +35,57 @@ use lemmy_db_schema::{
     person_block,
     post,
   },
-  utils::{fuzzy_search, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
+  source::community::Community,
+  utils::{
+    fts_matches,
+    fts_rank,
+    functions::{
+      hidden_scores_sort_bigint,
+      hidden_scores_sort_double,
+      person_is_new,
+      scores_hidden,
+    },
+    fuzzy_search,
+    get_conn,
+    limit_and_offset,
+    DbConn,
+    DbPool,
+    ListFn,
+    Queries,
+    ReadFn,
+    COMMENT_FTS_DOCUMENT,
+  },
   CommentSortType,
   CommunityVisibility,
   ListingType,
 };
 
+/// The maximum number of comments returned by a single tree fetch (ie when `max_depth` is given).
+const COMMENT_TREE_FETCH_LIMIT_MAX: i64 = 300;
+
+type BoxedCommentQuery<'a> = IntoBoxed<
+  'a,
+  InnerJoin<
+    InnerJoinOn<
+      InnerJoin<InnerJoin<comment::table, person::table>, post::table>,
+      community::table,
+      Eq<post::community_id, community::id>,
+    >,
+    comment_aggregates::table,
+  >,
+  Pg,
+>;
+
+/// Joins the tables which are needed for both filtering and selecting comments.
+fn joined_query<'a>() -> BoxedCommentQuery<'a> {
+  comment::table
+    .inner_join(person::table)
+    .inner_join(post::table)
+    .inner_join(community::table.on(post::community_id.eq(community::id)))
+    .inner_join(comment_aggregates::table)
+    .into_boxed()
+}
+
 fn queries<'a>() -> Queries<
   impl ReadFn<'a, CommentView, (CommentId, Option<PersonId>)>,
   impl ListFn<'a, CommentView, CommentQuery<'a>>,
@@ -122,7 +167,7 @@ fn queries<'a>() -> Queries<
     ),
   );
 
-  let all_joins = move |query: comment::BoxedQuery<'a, Pg>, my_person_id: Option<PersonId>| {
+  let all_joins = move |query: BoxedCommentQuery<'a>, my_person_id: Option<PersonId>| {
     let is_local_user_banned_from_community_selection: Box<
       dyn BoxableExpression<_, Pg, SqlType = sql_types::Bool>,
     > = if let Some(person_id) = my_person_id {
@@ -162,31 +207,32 @@ fn queries<'a>() -> Queries<
         Box::new(false.into_sql::<sql_types::Bool>())
       };
 
-    query
-      .inner_join(person::table)
-      .inner_join(post::table)
-      .inner_join(community::table.on(post::community_id.eq(community::id)))
-      .inner_join(comment_aggregates::table)
-      .select((
-        comment::all_columns,
-        person::all_columns,
-        post::all_columns,
-        community::all_columns,
-        comment_aggregates::all_columns,
-        is_creator_banned_from_community,
-        is_local_user_banned_from_community_selection,
-        creator_is_moderator,
-        creator_is_admin,
-        subscribed_type_selection,
-        is_saved_selection.is_not_null(),
-        is_creator_blocked_selection,
-        score_selection,
-      ))
+    query.select((
+      comment::all_columns,
+      person::all_columns,
+      post::all_columns,
+      community::all_columns,
+      comment_aggregates::all_columns,
+      is_creator_banned_from_community,
+      is_local_user_banned_from_community_selection,
+      creator_is_moderator,
+      creator_is_admin,
+      person_is_new(person::published),
+      subscribed_type_selection,
+      is_saved_selection.is_not_null(),
+      is_creator_blocked_selection,
+      score_selection,
+      // Set by hide_new_scores() if necessary
+      false.into_sql::<sql_types::Bool>(),
+    ))
   };
 
   let read = move |mut conn: DbConn<'a>,
                    (comment_id, my_person_id): (CommentId, Option<PersonId>)| async move {
-    let mut query = all_joins(comment::table.find(comment_id).into_boxed(), my_person_id);
+    let mut query = all_joins(
+      joined_query().filter(comment::id.eq(comment_id)),
+      my_person_id,
+    );
     // Hide local only communities from unauthenticated users
     if my_person_id.is_none() {
       query = query.filter(community::visibility.eq(CommunityVisibility::Public));
@@ -196,35 +242,283 @@ fn queries<'a>() -> Queries<
 
   let list = move |mut conn: DbConn<'a>, options: CommentQuery<'a>| async move {
     let my_person_id = options.local_user.map(|l| l.person.id);
-    let my_local_user_id = options.local_user.map(|l| l.local_user.id);
+    let mut query = all_joins(options.filtered_query(), my_person_id);
+
+    // Saved comments are ordered by the saved time, not the comment creation time.
+    if let (true, Some(person_id)) = (options.saved_only, my_person_id) {
+      query = query.then_order_by(is_saved(person_id).desc());
+    }
+
+    // A Max depth given means its a tree fetch
+    let (limit, offset) = if options.max_depth.is_some() {
+      // only order if filtering by a post id, or parent_path. DOS potential otherwise and max_depth + !post_id isn't used anyways (afaik)
+      if options.post_id.is_some() || options.parent_path.is_some() {
+        // Always order by the parent path first
+        query = query.then_order_by(subpath(comment::path, 0, -1));
+      }
+
+      // Limiting the number of children at each level isn't possible with ltree, so tree fetches
+      // are paginated over the flattened tree instead. As it is ordered by parent path, clients
+      // can lazy-load the remaining comments of large threads page by page.
+      // https://stackoverflow.com/questions/72983614/postgres-ltree-how-to-limit-the-max-number-of-children-at-any-given-level
+      //
+      // Don't use the regular error-checking one, many more comments must often be fetched.
+      // Without an explicit limit the whole tree is returned, bounded to prevent attacks.
+      let limit = options
+        .limit
+        .unwrap_or(COMMENT_TREE_FETCH_LIMIT_MAX)
+        .clamp(1, COMMENT_TREE_FETCH_LIMIT_MAX);
+      let page = options.page.unwrap_or(1).max(1);
+      (limit, limit.saturating_mul(page - 1))
+    } else {
+      // limit_and_offset_unlimited(options.page, options.limit)
+      limit_and_offset(options.page, options.limit)?
+    };
+
+    // distinguished comments should go first when viewing post
+    if options.post_id.is_some() || options.parent_path.is_some() {
+      query = query.then_order_by(comment::distinguished.desc());
+    }
+
+    if options.sort_by_relevance && options.use_fts {
+      if let Some(search_term) = &options.search_term {
+        query = query.then_order_by(fts_rank(COMMENT_FTS_DOCUMENT, search_term).desc());
+      }
+    }
+
+    // Like posts, comments with hidden scores are sorted by their publish time instead, for all
+    // users. See PostQuery::list().
+    let sort = options.sort.unwrap_or(CommentSortType::Hot);
+    let hides_scores = matches!(sort, CommentSortType::Controversial | CommentSortType::Top)
+      && Community::any_hides_scores(&mut (&mut conn).into(), options.community_id).await?;
+    query = match sort {
+      CommentSortType::Hot => query
+        .then_order_by(comment_aggregates::hot_rank.desc())
+        .then_order_by(comment_aggregates::score.desc()),
+      CommentSortType::Controversial if hides_scores => query
+        .then_order_by(
+          hidden_scores_sort_double(
+            comment_aggregates::controversy_rank,
+            comment::published,
+            post::community_id,
+          )
+          .desc(),
+        )
+        .then_order_by(comment::published.desc()),
+      CommentSortType::Controversial => {
+        query.then_order_by(comment_aggregates::controversy_rank.desc())
+      }
+      CommentSortType::New => query.then_order_by(comment::published.desc()),
+      CommentSortType::Old => query.then_order_by(comment::published.asc()),
+      CommentSortType::Top if hides_scores => query
+        .then_order_by(
+          hidden_scores_sort_bigint(
+            comment_aggregates::score,
+            comment::published,
+            post::community_id,
+          )
+          .desc(),
+        )
+        .then_order_by(comment::published.desc()),
+      CommentSortType::Top => query.then_order_by(comment_aggregates::score.desc()),
+    };
+
+    // Note: deleted and removed comments are done on the front side
+    query
+      .limit(limit)
+      .offset(offset)
+      .load::<CommentView>(&mut conn)
+      .await
+  };
+
+  Queries::new(read, list)
+}
+
+impl CommentView {
+  pub async fn read(
+    pool: &mut DbPool<'_>,
+    comment_id: CommentId,
+    my_person_id: Option<PersonId>,
+  ) -> Result<Option<Self>, Error> {
+    // If a person is given, then my_vote (res.9), if None, should be 0, not null
+    // Necessary to differentiate between other person's votes
+    if let Ok(Some(res)) = queries().read(pool, (comment_id, my_person_id)).await {
+      let mut new_view = res.clone();
+      if my_person_id.is_some() && res.my_vote.is_none() {
+        new_view.my_vote = Some(0);
+      }
+      if res.comment.deleted || res.comment.removed {
+        new_view.comment.content = String::new();
+      }
+      hide_new_scores(pool, std::slice::from_mut(&mut new_view), my_person_id).await?;
+      Ok(Some(new_view))
+    } else {
+      Ok(None)
+    }
+  }
+
+  fn hide_scores(&mut self) {
+    self.counts.score = 0;
+    self.counts.upvotes = 0;
+    self.counts.downvotes = 0;
+    self.scores_hidden = true;
+  }
+}
+
+/// Hides the scores of comments which are too new according to their community, unless the user
+/// is allowed to see them.
+async fn hide_new_scores(
+  pool: &mut DbPool<'_>,
+  comments: &mut [CommentView],
+  my_person_id: Option<PersonId>,
+) -> Result<(), Error> {
+  if !comments
+    .iter()
+    .any(|c| c.community.hides_scores(c.comment.published))
+  {
+    return Ok(());
+  }
+  let visible_in = match my_person_id {
+    Some(person_id) => Community::hidden_scores_visible_in(pool, person_id).await?,
+    None => Some(vec![]),
+  };
+  if let Some(visible_in) = visible_in {
+    comments
+      .iter_mut()
+      .filter(|c| {
+        c.community.hides_scores(c.comment.published) && !visible_in.contains(&c.community.id)
+      })
+      .for_each(CommentView::hide_scores);
+  }
+  Ok(())
+}
+
+#[derive(Default)]
+pub struct CommentQuery<'a> {
+  pub listing_type: Option<ListingType>,
+  pub sort: Option<CommentSortType>,
+  pub community_id: Option<CommunityId>,
+  pub post_id: Option<PostId>,
+  pub parent_path: Option<Ltree>,
+  /// Only return the comments with these ids.
+  pub comment_ids: Option<Vec<CommentId>>,
+  pub creator_id: Option<PersonId>,
+  pub local_user: Option<&'a LocalUserView>,
+  pub search_term: Option<String>,
+  /// Match the search term with full text search instead of by substring
+  pub use_fts: bool,
+  /// Order by how well comments match the full text search term before applying the sort. This
+  /// isn't a CommentSortType, because those are also used for comment trees.
+  pub sort_by_relevance: bool,
+  pub saved_only: bool,
+  pub liked_only: bool,
+  pub disliked_only: bool,
+  pub page: Option<i64>,
+  pub limit: Option<i64>,
+  pub max_depth: Option<i32>,
+  pub min_score: Option<i64>,
+  pub max_score: Option<i64>,
+  pub exclude_community_ids: Option<Vec<CommunityId>>,
+  /// Only return comments published after this time.
+  pub published_after: Option<DateTime<Utc>>,
+  /// Only return comments published before this time.
+  pub published_before: Option<DateTime<Utc>>,
+  /// If false, hide comments on nsfw posts and in nsfw communities. By default all comments are
+  /// returned.
+  pub show_nsfw: Option<bool>,
+  /// Keep the content of removed comments. Callers must check permissions before setting this.
+  pub include_removed: bool,
+  /// Keep the content of comments deleted by their creator. Callers must check permissions before
+  /// setting this.
+  pub include_deleted: bool,
+  /// Don't filter by the settings of the local user, their blocks or the visibility of
+  /// communities. Callers must check permissions before setting this.
+  pub skip_viewer_filters: bool,
+  /// Leave out removed comments, instead of returning them without content.
+  pub exclude_removed: bool,
+  /// Leave out comments deleted by their creator, instead of returning them without content.
+  pub exclude_deleted: bool,
+}
+
+impl<'a> CommentQuery<'a> {
+  /// Builds the query with all filters of this listing applied, but without ordering and
+  /// pagination. Shared by list() and count() so that both match the same comments.
+  fn filtered_query(&self) -> BoxedCommentQuery<'a> {
+    let my_person_id = self.local_user.map(|l| l.person.id);
+    let my_local_user_id = self.local_user.map(|l| l.local_user.id);
 
     // The left join below will return None in this case
     let person_id_join = my_person_id.unwrap_or(PersonId(-1));
     let local_user_id_join = my_local_user_id.unwrap_or(LocalUserId(-1));
 
-    let mut query = all_joins(comment::table.into_boxed(), my_person_id);
+    let mut query = joined_query();
 
-    if let Some(creator_id) = options.creator_id {
+    if let Some(creator_id) = self.creator_id {
       query = query.filter(comment::creator_id.eq(creator_id));
     };
 
-    if let Some(post_id) = options.post_id {
+    if let Some(post_id) = self.post_id {
       query = query.filter(comment::post_id.eq(post_id));
     };
 
-    if let Some(parent_path) = options.parent_path.as_ref() {
+    if let Some(parent_path) = self.parent_path.clone() {
       query = query.filter(comment::path.contained_by(parent_path));
     };
 
-    if let Some(search_term) = options.search_term {
-      query = query.filter(comment::content.ilike(fuzzy_search(&search_term)));
+    if let Some(comment_ids) = self.comment_ids.clone() {
+      query = query.filter(comment::id.eq_any(comment_ids));
     };
 
-    if let Some(community_id) = options.community_id {
+    if let Some(search_term) = &self.search_term {
+      if self.use_fts {
+        query = query.filter(fts_matches(COMMENT_FTS_DOCUMENT, search_term));
+      } else {
+        query = query.filter(comment::content.ilike(fuzzy_search(search_term)));
+      }
+    }
+
+    // Comments with hidden scores always pass the score filters, so that the scores can't be
+    // narrowed down with them
+    let hidden = || scores_hidden(comment::published, community::hide_scores_until_hours);
+    if let Some(min_score) = self.min_score {
+      query = query.filter(comment_aggregates::score.ge(min_score).or(hidden()));
+    }
+
+    if let Some(max_score) = self.max_score {
+      query = query.filter(comment_aggregates::score.le(max_score).or(hidden()));
+    };
+
+    if let Some(published_after) = self.published_after {
+      query = query.filter(comment::published.gt(published_after));
+    }
+
+    if let Some(published_before) = self.published_before {
+      query = query.filter(comment::published.lt(published_before));
+    }
+
+    if self.show_nsfw == Some(false) && !self.skip_viewer_filters {
+      query = query
+        .filter(post::nsfw.eq(false))
+        .filter(community::nsfw.eq(false));
+    }
+
+    if self.exclude_removed {
+      query = query.filter(comment::removed.eq(false));
+    }
+
+    if self.exclude_deleted {
+      query = query.filter(comment::deleted.eq(false));
+    }
+
+    if let Some(community_id) = self.community_id {
       query = query.filter(post::community_id.eq(community_id));
     }
 
-    if let Some(listing_type) = options.listing_type {
+    if let Some(exclude_community_ids) = self.exclude_community_ids.clone() {
+      query = query.filter(post::community_id.ne_all(exclude_community_ids));
+    }
+
+    if let Some(listing_type) = self.listing_type {
       let is_subscribed = exists(
         community_follower::table.filter(
           post::community_id
@@ -253,29 +547,45 @@ fn queries<'a>() -> Queries<
       }
     }
 
-    // If its saved only, then filter, and order by the saved time, not the comment creation time.
-    if options.saved_only {
-      query = query
-        .filter(is_saved(person_id_join).is_not_null())
-        .then_order_by(is_saved(person_id_join).desc());
+    // If its saved only, then filter. The list is ordered by the saved time in this case.
+    if self.saved_only {
+      query = query.filter(exists(
+        comment_saved::table.filter(
+          comment::id
+            .eq(comment_saved::comment_id)
+            .and(comment_saved::person_id.eq(person_id_join)),
+        ),
+      ));
     }
 
-    if options.liked_only {
-      query = query.filter(score(person_id_join).eq(1));
-    } else if options.disliked_only {
-      query = query.filter(score(person_id_join).eq(-1));
+    let liked = |score: i16| {
+      exists(
+        comment_like::table.filter(
+          comment::id
+            .eq(comment_like::comment_id)
+            .and(comment_like::person_id.eq(person_id_join))
+            .and(comment_like::score.eq(score)),
+        ),
+      )
+    };
+    if self.liked_only {
+      query = query.filter(liked(1));
+    } else if self.disliked_only {
+      query = query.filter(liked(-1));
     }
 
-    if !options
-      .local_user
-      .map(|l| l.local_user.show_bot_accounts)
-      .unwrap_or(true)
+    if !self.skip_viewer_filters
+      && !self
+        .local_user
+        .map(|l| l.local_user.show_bot_accounts)
+        .unwrap_or(true)
     {
       query = query.filter(person::bot_account.eq(false));
     };
 
-    if options.local_user.is_some()
-      && options.listing_type.unwrap_or_default() != ListingType::ModeratorView
+    if self.local_user.is_some()
+      && self.listing_type.unwrap_or_default() != ListingType::ModeratorView
+      && !self.skip_viewer_filters
     {
       // Filter out the rows with missing languages
       query = query.filter(exists(
@@ -301,17 +611,23 @@ fn queries<'a>() -> Queries<
             .and(community_block::person_id.eq(person_id_join)),
         ),
       )));
-      query = query.filter(not(is_creator_blocked(person_id_join)));
+      query = query.filter(not(exists(
+        person_block::table.filter(
+          comment::creator_id
+            .eq(person_block::target_id)
+            .and(person_block::person_id.eq(person_id_join)),
+        ),
+      )));
     };
 
     // Hide comments in local only communities from unauthenticated users
-    if options.local_user.is_none() {
+    if self.local_user.is_none() && !self.skip_viewer_filters {
       query = query.filter(community::visibility.eq(CommunityVisibility::Public));
     }
 
-    // A Max depth given means its a tree fetch
-    let (limit, offset) = if let Some(max_depth) = options.max_depth {
-      let depth_limit = if let Some(parent_path) = options.parent_path.as_ref() {
+    // A max depth given means its a tree fetch
+    if let Some(max_depth) = self.max_depth {
+      let depth_limit = if let Some(parent_path) = self.parent_path.as_ref() {
         parent_path.0.split('.').count() as i32 + max_depth
         // Add one because of root "0"
       } else {
@@ -319,113 +635,34 @@ fn queries<'a>() -> Queries<
       };
 
       query = query.filter(nlevel(comment::path).le(depth_limit));
-
-      // only order if filtering by a post id, or parent_path. DOS potential otherwise and max_depth + !post_id isn't used anyways (afaik)
-      if options.post_id.is_some() || options.parent_path.is_some() {
-        // Always order by the parent path first
-        query = query.then_order_by(subpath(comment::path, 0, -1));
-      }
-
-      // TODO limit question. Limiting does not work for comment threads ATM, only max_depth
-      // For now, don't do any limiting for tree fetches
-      // https://stackoverflow.com/questions/72983614/postgres-ltree-how-to-limit-the-max-number-of-children-at-any-given-level
-
-      // Don't use the regular error-checking one, many more comments must ofter be fetched.
-      // This does not work for comment trees, and the limit should be manually set to a high number
-      //
-      // If a max depth is given, then you know its a tree fetch, and limits should be ignored
-      // TODO a kludge to prevent attacks. Limit comments to 300 for now.
-      // (i64::MAX, 0)
-      (300, 0)
-    } else {
-      // limit_and_offset_unlimited(options.page, options.limit)
-      limit_and_offset(options.page, options.limit)?
-    };
-
-    // distinguished comments should go first when viewing post
-    if options.post_id.is_some() || options.parent_path.is_some() {
-      query = query.then_order_by(comment::distinguished.desc());
     }
 
-    query = match options.sort.unwrap_or(CommentSortType::Hot) {
-      CommentSortType::Hot => query
-        .then_order_by(comment_aggregates::hot_rank.desc())
-        .then_order_by(comment_aggregates::score.desc()),
-      CommentSortType::Controversial => {
-        query.then_order_by(comment_aggregates::controversy_rank.desc())
-      }
-      CommentSortType::New => query.then_order_by(comment::published.desc()),
-      CommentSortType::Old => query.then_order_by(comment::published.asc()),
-      CommentSortType::Top => query.then_order_by(comment_aggregates::score.desc()),
-    };
-
-    // Note: deleted and removed comments are done on the front side
     query
-      .limit(limit)
-      .offset(offset)
-      .load::<CommentView>(&mut conn)
-      .await
-  };
-
-  Queries::new(read, list)
-}
-
-impl CommentView {
-  pub async fn read(
-    pool: &mut DbPool<'_>,
-    comment_id: CommentId,
-    my_person_id: Option<PersonId>,
-  ) -> Result<Option<Self>, Error> {
-    // If a person is given, then my_vote (res.9), if None, should be 0, not null
-    // Necessary to differentiate between other person's votes
-    if let Ok(Some(res)) = queries().read(pool, (comment_id, my_person_id)).await {
-      let mut new_view = res.clone();
-      if my_person_id.is_some() && res.my_vote.is_none() {
-        new_view.my_vote = Some(0);
-      }
-      if res.comment.deleted || res.comment.removed {
-        new_view.comment.content = String::new();
-      }
-      Ok(Some(new_view))
-    } else {
-      Ok(None)
-    }
   }
-}
-
-#[derive(Default)]
-pub struct CommentQuery<'a> {
-  pub listing_type: Option<ListingType>,
-  pub sort: Option<CommentSortType>,
-  pub community_id: Option<CommunityId>,
-  pub post_id: Option<PostId>,
-  pub parent_path: Option<Ltree>,
-  pub creator_id: Option<PersonId>,
-  pub local_user: Option<&'a LocalUserView>,
-  pub search_term: Option<String>,
-  pub saved_only: bool,
-  pub liked_only: bool,
-  pub disliked_only: bool,
-  pub page: Option<i64>,
-  pub limit: Option<i64>,
-  pub max_depth: Option<i32>,
-}
 
-impl<'a> CommentQuery<'a> {
   pub async fn list(self, pool: &mut DbPool<'_>) -> Result<Vec<CommentView>, Error> {
-    Ok(
-      queries()
-        .list(pool, self)
-        .await?
-        .into_iter()
-        .map(|mut c| {
-          if c.comment.deleted || c.comment.removed {
-            c.comment.content = String::new();
-          }
-          c
-        })
-        .collect(),
-    )
+    let my_person_id = self.local_user.map(|l| l.person.id);
+    let include_removed = self.include_removed;
+    let include_deleted = self.include_deleted;
+    let mut comments: Vec<CommentView> = queries()
+      .list(pool, self)
+      .await?
+      .into_iter()
+      .map(|mut c| {
+        if (c.comment.deleted && !include_deleted) || (c.comment.removed && !include_removed) {
+          c.comment.content = String::new();
+        }
+        c
+      })
+      .collect();
+    hide_new_scores(pool, &mut comments, my_person_id).await?;
+    Ok(comments)
+  }
+
+  /// Counts the comments matching the filters of this query, ignoring pagination.
+  pub async fn count(&self, pool: &mut DbPool<'_>) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    self.filtered_query().count().get_result(conn).await
   }
 }
 
@@ -808,6 +1045,25 @@ mod tests {
       .eq("Comment 3"));
     assert_length!(3, read_comment_views_parent_max_depth);
 
+    // Tree fetches can be paginated
+    let child_path = data.inserted_comment_1.path.clone();
+    let read_comment_views_parent_max_depth_page_2 = CommentQuery {
+      post_id: (Some(data.inserted_post.id)),
+      parent_path: (Some(child_path)),
+      max_depth: (Some(1)),
+      sort: (Some(CommentSortType::New)),
+      page: (Some(2)),
+      limit: (Some(2)),
+      ..Default::default()
+    }
+    .list(pool)
+    .await?;
+    assert_length!(1, read_comment_views_parent_max_depth_page_2);
+    assert_eq!(
+      read_comment_views_parent_max_depth[2].comment.id,
+      read_comment_views_parent_max_depth_page_2[0].comment.id
+    );
+
     cleanup(data, pool).await
   }
 
@@ -897,6 +1153,64 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_comment_ids() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let comment_ids = vec![data.inserted_comment_0.id, data.inserted_comment_2.id];
+    let comments = CommentQuery {
+      comment_ids: Some(comment_ids.clone()),
+      sort: Some(CommentSortType::Old),
+      ..Default::default()
+    }
+    .list(pool)
+    .await?;
+    assert_eq!(
+      comment_ids,
+      comments.iter().map(|c| c.comment.id).collect::<Vec<_>>()
+    );
+
+    cleanup(data, pool).await
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn test_hidden_scores() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    let top_query = || CommentQuery {
+      sort: Some(CommentSortType::Top),
+      post_id: Some(data.inserted_post.id),
+      ..Default::default()
+    };
+    let comments = top_query().list(pool).await?;
+    assert_eq!(6, comments.len());
+    assert_eq!(data.inserted_comment_0.id, comments[0].comment.id);
+
+    let form = CommunityUpdateForm {
+      hide_scores_until_hours: Some(Some(24)),
+      ..Default::default()
+    };
+    Community::update(pool, data.inserted_community.id, &form).await?;
+
+    // Comments with hidden scores are ordered by their publish time, and ignore the score filters
+    let comments = CommentQuery {
+      min_score: Some(1),
+      ..top_query()
+    }
+    .list(pool)
+    .await?;
+    assert_eq!(6, comments.len());
+    assert_eq!(data.inserted_comment_0.id, comments[5].comment.id);
+
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn test_creator_is_moderator() -> LemmyResult<()> {
@@ -994,6 +1308,64 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn test_exclude_removed_and_deleted() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    Comment::update(
+      pool,
+      data.inserted_comment_0.id,
+      &CommentUpdateForm {
+        removed: Some(true),
+        ..Default::default()
+      },
+    )
+    .await?;
+    Comment::update(
+      pool,
+      data.inserted_comment_1.id,
+      &CommentUpdateForm {
+        deleted: Some(true),
+        ..Default::default()
+      },
+    )
+    .await?;
+
+    // Removed and deleted comments are returned without content by default
+    let comments = CommentQuery::default().list(pool).await?;
+    assert_eq!(6, comments.len());
+    assert!(comments
+      .iter()
+      .filter(|c| c.comment.removed || c.comment.deleted)
+      .all(|c| c.comment.content.is_empty()));
+
+    let exclude_removed = CommentQuery {
+      exclude_removed: true,
+      ..Default::default()
+    };
+    assert_eq!(5, exclude_removed.count(pool).await?);
+    let comments = exclude_removed.list(pool).await?;
+    assert!(!comments
+      .iter()
+      .any(|c| c.comment.id == data.inserted_comment_0.id));
+
+    let exclude_both = CommentQuery {
+      exclude_removed: true,
+      exclude_deleted: true,
+      ..Default::default()
+    };
+    assert_eq!(4, exclude_both.count(pool).await?);
+    let comments = exclude_both.list(pool).await?;
+    assert!(!comments
+      .iter()
+      .any(|c| c.comment.removed || c.comment.deleted));
+
+    cleanup(data, pool).await
+  }
+
   async fn cleanup(data: Data, pool: &mut DbPool<'_>) -> LemmyResult<()> {
     CommentLike::remove(
       pool,
@@ -1022,10 +1394,12 @@ mod tests {
       banned_from_community: false,
       creator_is_moderator: false,
       creator_is_admin: true,
+      creator_is_new: false,
       my_vote: None,
       subscribed: SubscribedType::NotSubscribed,
       saved: false,
       creator_blocked: false,
+      scores_hidden: false,
       comment: Comment {
         id: data.inserted_comment_0.id,
         content: "Comment 0".into(),
@@ -1071,6 +1445,7 @@ mod tests {
         url: None,
         body: None,
         alt_text: None,
+        flair_id: None,
         published: data.inserted_post.published,
         updated: None,
         community_id: data.inserted_community.id,
@@ -1115,6 +1490,8 @@ mod tests {
         moderators_url: data.inserted_community.moderators_url.clone(),
         featured_url: data.inserted_community.featured_url.clone(),
         visibility: CommunityVisibility::Public,
+        default_sort_type: None,
+        hide_scores_until_hours: None,
       },
       counts: CommentAggregates {
         comment_id: data.inserted_comment_0.id,
@@ -1175,6 +1552,43 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn nsfw_community() -> LemmyResult<()> {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await;
+
+    Community::update(
+      pool,
+      data.inserted_community.id,
+      &CommunityUpdateForm {
+        nsfw: Some(true),
+        ..Default::default()
+      },
+    )
+    .await?;
+
+    // Comments in nsfw communities are returned by default
+    let default_query = CommentQuery {
+      ..Default::default()
+    }
+    .list(pool)
+    .await?;
+    assert_eq!(6, default_query.len());
+    assert_eq!(6, CommentQuery::default().count(pool).await?);
+
+    let hide_nsfw = CommentQuery {
+      show_nsfw: Some(false),
+      ..Default::default()
+    };
+    assert_eq!(0, hide_nsfw.count(pool).await?);
+    let hide_nsfw_query = hide_nsfw.list(pool).await?;
+    assert_eq!(0, hide_nsfw_query.len());
+
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn comment_listing_local_user_banned_from_community() -> LemmyResult<()> {
@@ -2,7 +2,7 @@ use crate::structs::{LocalUserView, PaginationCursor, PostView};
 use chrono::{DateTime, Utc};
 use diesel::{
   debug_query,
-  dsl::{exists, not, IntervalDsl},
+  dsl::{exists, not, InnerJoin, IntervalDsl, IntoBoxed},
   pg::Pg,
   query_builder::AsQuery,
   result::Error,
@@ -36,14 +36,17 @@ use lemmy_db_schema::{
     person_post_aggregates,
     post,
     post_aggregates,
+    post_edit_history,
     post_hide,
     post_like,
     post_read,
     post_saved,
   },
-  source::site::Site,
+  source::{community::Community, site::Site},
   utils::{
-    functions::coalesce,
+    fts_matches,
+    fts_rank,
+    functions::{coalesce, person_is_new},
     fuzzy_search,
     get_conn,
     limit_and_offset,
@@ -51,10 +54,12 @@ use lemmy_db_schema::{
     Commented,
     DbConn,
     DbPool,
+    HiddenScoresKey,
     ListFn,
     Queries,
     ReadFn,
     ReverseTimestampKey,
+    POST_FTS_DOCUMENT,
   },
   CommunityVisibility,
   ListingType,
@@ -62,6 +67,24 @@ use lemmy_db_schema::{
 };
 use tracing::debug;
 
+type BoxedPostQuery<'a> = IntoBoxed<
+  'a,
+  InnerJoin<
+    InnerJoin<InnerJoin<post_aggregates::table, person::table>, community::table>,
+    post::table,
+  >,
+  Pg,
+>;
+
+/// Joins the tables which are needed for both filtering and selecting posts.
+fn joined_query<'a>() -> BoxedPostQuery<'a> {
+  post_aggregates::table
+    .inner_join(person::table)
+    .inner_join(community::table)
+    .inner_join(post::table)
+    .into_boxed()
+}
+
 fn queries<'a>() -> Queries<
   impl ReadFn<'a, PostView, (PostId, Option<PersonId>, bool)>,
   impl ListFn<'a, PostView, (PostQuery<'a>, &'a Site)>,
@@ -152,8 +175,7 @@ fn queries<'a>() -> Queries<
       .single_value()
   };
 
-  let all_joins = move |query: post_aggregates::BoxedQuery<'a, Pg>,
-                        my_person_id: Option<PersonId>| {
+  let all_joins = move |query: BoxedPostQuery<'a>, my_person_id: Option<PersonId>| {
     let is_local_user_banned_from_community_selection: Box<
       dyn BoxableExpression<_, Pg, SqlType = sql_types::Bool>,
     > = if let Some(person_id) = my_person_id {
@@ -233,30 +255,29 @@ fn queries<'a>() -> Queries<
       Box::new(None::<i64>.into_sql::<sql_types::Nullable<sql_types::BigInt>>())
     };
 
-    query
-      .inner_join(person::table)
-      .inner_join(community::table)
-      .inner_join(post::table)
-      .select((
-        post::all_columns,
-        person::all_columns,
-        community::all_columns,
-        is_creator_banned_from_community,
-        is_local_user_banned_from_community_selection,
-        creator_is_moderator,
-        creator_is_admin,
-        post_aggregates::all_columns,
-        subscribed_type_selection,
-        is_saved_selection.is_not_null(),
-        is_read_selection,
-        is_hidden_selection,
-        is_creator_blocked_selection,
-        score_selection,
-        coalesce(
-          post_aggregates::comments.nullable() - read_comments,
-          post_aggregates::comments,
-        ),
-      ))
+    query.select((
+      post::all_columns,
+      person::all_columns,
+      community::all_columns,
+      is_creator_banned_from_community,
+      is_local_user_banned_from_community_selection,
+      creator_is_moderator,
+      creator_is_admin,
+      person_is_new(person::published),
+      post_aggregates::all_columns,
+      subscribed_type_selection,
+      is_saved_selection.is_not_null(),
+      is_read_selection,
+      is_hidden_selection,
+      is_creator_blocked_selection,
+      score_selection,
+      coalesce(
+        post_aggregates::comments.nullable() - read_comments,
+        post_aggregates::comments,
+      ),
+      // Set by hide_new_scores() if necessary
+      false.into_sql::<sql_types::Bool>(),
+    ))
   };
 
   let read =
@@ -266,9 +287,7 @@ fn queries<'a>() -> Queries<
       let person_id_join = my_person_id.unwrap_or(PersonId(-1));
 
       let mut query = all_joins(
-        post_aggregates::table
-          .filter(post_aggregates::post_id.eq(post_id))
-          .into_boxed(),
+        joined_query().filter(post_aggregates::post_id.eq(post_id)),
         my_person_id,
       );
 
@@ -311,193 +330,30 @@ fn queries<'a>() -> Queries<
 
   let list = move |mut conn: DbConn<'a>, (options, site): (PostQuery<'a>, &'a Site)| async move {
     let my_person_id = options.local_user.map(|l| l.person.id);
-    let my_local_user_id = options.local_user.map(|l| l.local_user.id);
-
-    // The left join below will return None in this case
-    let person_id_join = my_person_id.unwrap_or(PersonId(-1));
-    let local_user_id_join = my_local_user_id.unwrap_or(LocalUserId(-1));
-
-    let mut query = all_joins(post_aggregates::table.into_boxed(), my_person_id);
-
-    // hide posts from deleted communities
-    query = query.filter(community::deleted.eq(false));
 
-    // only show deleted posts to creator
-    if let Some(person_id) = my_person_id {
-      query = query.filter(post::deleted.eq(false).or(post::creator_id.eq(person_id)));
-    } else {
-      query = query.filter(post::deleted.eq(false));
-    }
+    let mut query = all_joins(options.filtered_query(site), my_person_id);
 
-    let is_admin = options
-      .local_user
-      .map(|l| l.local_user.admin)
-      .unwrap_or(false);
-    // only show removed posts to admin when viewing user profile
-    if !(options.creator_id.is_some() && is_admin) {
-      query = query
-        .filter(community::removed.eq(false))
-        .filter(post::removed.eq(false));
-    }
-    if let Some(community_id) = options.community_id {
-      query = query.filter(post_aggregates::community_id.eq(community_id));
-    }
-
-    if let Some(creator_id) = options.creator_id {
-      query = query.filter(post_aggregates::creator_id.eq(creator_id));
-    }
-
-    if let Some(listing_type) = options.listing_type {
-      if let Some(person_id) = my_person_id {
-        let is_subscribed = exists(
-          community_follower::table.filter(
-            post_aggregates::community_id
-              .eq(community_follower::community_id)
-              .and(community_follower::person_id.eq(person_id)),
-          ),
-        );
-        match listing_type {
-          ListingType::Subscribed => query = query.filter(is_subscribed),
-          ListingType::Local => {
-            query = query
-              .filter(community::local.eq(true))
-              .filter(community::hidden.eq(false).or(is_subscribed));
-          }
-          ListingType::All => query = query.filter(community::hidden.eq(false).or(is_subscribed)),
-          ListingType::ModeratorView => {
-            query = query.filter(exists(
-              community_moderator::table.filter(
-                post::community_id
-                  .eq(community_moderator::community_id)
-                  .and(community_moderator::person_id.eq(person_id)),
-              ),
-            ));
-          }
-        }
-      }
-      // If your person_id is missing, only show local
-      else {
-        match listing_type {
-          ListingType::Local => {
-            query = query
-              .filter(community::local.eq(true))
-              .filter(community::hidden.eq(false));
-          }
-          _ => query = query.filter(community::hidden.eq(false)),
-        }
-      }
-    } else {
-      query = query.filter(community::hidden.eq(false));
-    }
-
-    if let Some(url_search) = &options.url_search {
-      query = query.filter(post::url.eq(url_search));
-    }
-
-    if let Some(search_term) = &options.search_term {
-      let searcher = fuzzy_search(search_term);
-      query = query.filter(
-        post::name
-          .ilike(searcher.clone())
-          .or(post::body.ilike(searcher)),
-      );
-    }
-
-    // If there is a content warning, show nsfw content by default.
-    let has_content_warning = site.content_warning.is_some();
-    if !options
-      .local_user
-      .map(|l| l.local_user.show_nsfw)
-      .unwrap_or(has_content_warning)
-    {
-      query = query
-        .filter(post::nsfw.eq(false))
-        .filter(community::nsfw.eq(false));
-    };
-
-    if !options
-      .local_user
-      .map(|l| l.local_user.show_bot_accounts)
-      .unwrap_or(true)
-    {
-      query = query.filter(person::bot_account.eq(false));
-    };
-
-    // If its saved only, then filter, and order by the saved time, not the comment creation time.
+    // Saved posts are ordered by the saved time, not the post creation time.
     if let (true, Some(person_id)) = (options.saved_only, my_person_id) {
-      query = query
-        .filter(is_saved(person_id).is_not_null())
-        .then_order_by(is_saved(person_id).desc());
+      query = query.then_order_by(is_saved(person_id).desc());
     }
-    // Only hide the read posts, if the saved_only is false. Otherwise ppl with the hide_read
-    // setting wont be able to see saved posts.
-    else if !options
-      .local_user
-      .map(|l| l.local_user.show_read_posts)
-      .unwrap_or(true)
-    {
-      // Do not hide read posts when it is a user profile view
-      // Or, only hide read posts on non-profile views
-      if let (None, Some(person_id)) = (options.creator_id, my_person_id) {
-        query = query.filter(not(is_read(person_id)));
-      }
-    }
-
-    if !options.show_hidden {
-      // If a creator id isn't given (IE its on home or community pages), hide the hidden posts
-      if let (None, Some(person_id)) = (options.creator_id, my_person_id) {
-        query = query.filter(not(is_hidden(person_id)));
-      }
-    }
-
-    if let Some(person_id) = my_person_id {
-      if options.liked_only {
-        query = query.filter(score(person_id).eq(1));
-      } else if options.disliked_only {
-        query = query.filter(score(person_id).eq(-1));
-      }
-    };
-
-    // Hide posts in local only communities from unauthenticated users
-    if options.local_user.is_none() {
-      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
-    }
-
-    // Dont filter blocks or missing languages for moderator view type
-    if let (Some(person_id), false) = (
-      my_person_id,
-      options.listing_type.unwrap_or_default() == ListingType::ModeratorView,
-    ) {
-      // Filter out the rows with missing languages
-      query = query.filter(exists(
-        local_user_language::table.filter(
-          post::language_id
-            .eq(local_user_language::language_id)
-            .and(local_user_language::local_user_id.eq(local_user_id_join)),
-        ),
-      ));
 
-      // Don't show blocked instances, communities or persons
-      query = query.filter(not(exists(
-        community_block::table.filter(
-          post_aggregates::community_id
-            .eq(community_block::community_id)
-            .and(community_block::person_id.eq(person_id_join)),
-        ),
-      )));
-      query = query.filter(not(exists(
-        instance_block::table.filter(
-          post_aggregates::instance_id
-            .eq(instance_block::instance_id)
-            .and(instance_block::person_id.eq(person_id_join)),
-        ),
-      )));
-      query = query.filter(not(is_creator_blocked(person_id)));
+    // The rank isn't stored, so it can't be a pagination key. Instead it is ordered by before the
+    // keys, right after featured posts. Cursors are rejected for this sort in list().
+    if let Some(search_term) = options.relevance_search_term() {
+      query = if options.community_id.is_none() || options.community_id_just_for_prefetch {
+        query.then_order_by(post_aggregates::featured_local.desc())
+      } else {
+        query.then_order_by(post_aggregates::featured_community.desc())
+      };
+      query = query.then_order_by(fts_rank(POST_FTS_DOCUMENT, search_term).desc());
     }
 
     let (limit, offset) = limit_and_offset(options.page, options.limit)?;
     query = query.limit(limit).offset(offset);
 
+    let sort = options.sort.unwrap_or(SortType::Hot);
+
     let mut query = PaginatedQueryBuilder::new(query);
 
     let page_after = options.page_after.map(|c| c.0);
@@ -521,34 +377,35 @@ fn queries<'a>() -> Queries<
       query.then_desc(key::featured_community)
     };
 
-    let time = |interval| post_aggregates::published.gt(now() - interval);
-
     // then use the main sort
-    query = match options.sort.unwrap_or(SortType::Hot) {
+    query = match sort {
       SortType::Active => query.then_desc(key::hot_rank_active),
-      SortType::Hot => query.then_desc(key::hot_rank),
+      SortType::Hot | SortType::Relevance => query.then_desc(key::hot_rank),
       SortType::Scaled => query.then_desc(key::scaled_rank),
-      SortType::Controversial => query.then_desc(key::controversy_rank),
+      // The order of posts must not reveal hidden scores, so posts with hidden scores are sorted
+      // by their publish time instead, for all users. The hot ranks already leave them out.
+      SortType::Controversial => query.then_desc(HiddenScoresKey(key::controversy_rank)),
       SortType::New => query.then_desc(key::published),
       SortType::Old => query.then_desc(ReverseTimestampKey(key::published)),
       SortType::NewComments => query.then_desc(key::newest_comment_time),
       SortType::MostComments => query.then_desc(key::comments),
-      SortType::TopAll => query.then_desc(key::score),
-      SortType::TopYear => query.then_desc(key::score).filter(time(1.years())),
-      SortType::TopMonth => query.then_desc(key::score).filter(time(1.months())),
-      SortType::TopWeek => query.then_desc(key::score).filter(time(1.weeks())),
-      SortType::TopDay => query.then_desc(key::score).filter(time(1.days())),
-      SortType::TopHour => query.then_desc(key::score).filter(time(1.hours())),
-      SortType::TopSixHour => query.then_desc(key::score).filter(time(6.hours())),
-      SortType::TopTwelveHour => query.then_desc(key::score).filter(time(12.hours())),
-      SortType::TopThreeMonths => query.then_desc(key::score).filter(time(3.months())),
-      SortType::TopSixMonths => query.then_desc(key::score).filter(time(6.months())),
-      SortType::TopNineMonths => query.then_desc(key::score).filter(time(9.months())),
+      // The time frame of the top sorts is filtered in filtered_query()
+      SortType::TopAll
+      | SortType::TopYear
+      | SortType::TopMonth
+      | SortType::TopWeek
+      | SortType::TopDay
+      | SortType::TopHour
+      | SortType::TopSixHour
+      | SortType::TopTwelveHour
+      | SortType::TopThreeMonths
+      | SortType::TopSixMonths
+      | SortType::TopNineMonths => query.then_desc(HiddenScoresKey(key::score)),
     };
 
     // use publish as fallback. especially useful for hot rank which reaches zero after some days.
     // necessary because old posts can be fetched over federation and inserted with high post id
-    query = match options.sort.unwrap_or(SortType::Hot) {
+    query = match sort {
       // A second time-based sort would not be very useful
       SortType::New | SortType::Old | SortType::NewComments => query,
       _ => query.then_desc(key::published),
@@ -582,10 +439,51 @@ impl PostView {
     my_person_id: Option<PersonId>,
     is_mod_or_admin: bool,
   ) -> Result<Option<Self>, Error> {
-    queries()
+    let mut res = queries()
       .read(pool, (post_id, my_person_id, is_mod_or_admin))
-      .await
+      .await?;
+    if let Some(post_view) = res.as_mut() {
+      if !is_mod_or_admin && post_view.community.hides_scores(post_view.post.published) {
+        post_view.hide_scores();
+      }
+    }
+    Ok(res)
+  }
+
+  fn hide_scores(&mut self) {
+    self.counts.score = 0;
+    self.counts.upvotes = 0;
+    self.counts.downvotes = 0;
+    self.scores_hidden = true;
+  }
+}
+
+/// Hides the scores of posts which are too new according to their community, unless the user is
+/// allowed to see them.
+async fn hide_new_scores(
+  pool: &mut DbPool<'_>,
+  posts: &mut [PostView],
+  my_person_id: Option<PersonId>,
+) -> Result<(), Error> {
+  if !posts
+    .iter()
+    .any(|p| p.community.hides_scores(p.post.published))
+  {
+    return Ok(());
+  }
+  let visible_in = match my_person_id {
+    Some(person_id) => Community::hidden_scores_visible_in(pool, person_id).await?,
+    None => Some(vec![]),
+  };
+  if let Some(visible_in) = visible_in {
+    posts
+      .iter_mut()
+      .filter(|p| {
+        p.community.hides_scores(p.post.published) && !visible_in.contains(&p.community.id)
+      })
+      .for_each(PostView::hide_scores);
   }
+  Ok(())
 }
 
 impl PaginationCursor {
@@ -628,6 +526,11 @@ pub struct PostQuery<'a> {
   pub community_id_just_for_prefetch: bool,
   pub local_user: Option<&'a LocalUserView>,
   pub search_term: Option<String>,
+  /// Also match the search term against previous titles and bodies of edited posts
+  pub search_history: bool,
+  /// Match the search term with full text search instead of by substring. Required for sorting by
+  /// relevance.
+  pub use_fts: bool,
   pub url_search: Option<String>,
   pub saved_only: bool,
   pub liked_only: bool,
@@ -638,6 +541,24 @@ pub struct PostQuery<'a> {
   pub page_before_or_equal: Option<PaginationCursorData>,
   pub page_back: bool,
   pub show_hidden: bool,
+  /// Include removed posts. Callers must check permissions before setting this.
+  pub include_removed: bool,
+  /// Include posts deleted by their creator, and posts in deleted communities. Callers must check
+  /// permissions before setting this.
+  pub include_deleted: bool,
+  /// Don't filter by the settings of the local user, their blocks or the visibility of
+  /// communities. Callers must check permissions before setting this.
+  pub skip_viewer_filters: bool,
+  pub min_score: Option<i64>,
+  pub max_score: Option<i64>,
+  pub exclude_community_ids: Option<Vec<CommunityId>>,
+  pub has_media: Option<bool>,
+  /// Only return posts published after this time.
+  pub published_after: Option<DateTime<Utc>>,
+  /// Only return posts published before this time.
+  pub published_before: Option<DateTime<Utc>>,
+  /// Overrides the nsfw setting of the local user, and the default of the site.
+  pub show_nsfw: Option<bool>,
 }
 
 impl<'a> PostQuery<'a> {
@@ -723,23 +644,360 @@ impl<'a> PostQuery<'a> {
     }
   }
 
+  /// Builds the query with all filters of this listing applied, but without ordering and
+  /// pagination. Shared by list() and count() so that both match the same posts.
+  fn filtered_query(&self, site: &Site) -> BoxedPostQuery<'a> {
+    let my_person_id = self.local_user.map(|l| l.person.id);
+    let my_local_user_id = self.local_user.map(|l| l.local_user.id);
+
+    // The left join below will return None in this case
+    let person_id_join = my_person_id.unwrap_or(PersonId(-1));
+    let local_user_id_join = my_local_user_id.unwrap_or(LocalUserId(-1));
+
+    let mut query = joined_query();
+
+    // hide posts from deleted communities
+    if !self.include_deleted {
+      query = query.filter(community::deleted.eq(false));
+    }
+
+    // only show deleted posts to creator, or when explicitly requested by an admin (checked by
+    // the caller)
+    if !self.include_deleted {
+      if let Some(person_id) = my_person_id {
+        query = query.filter(post::deleted.eq(false).or(post::creator_id.eq(person_id)));
+      } else {
+        query = query.filter(post::deleted.eq(false));
+      }
+    }
+
+    let is_admin = self.local_user.map(|l| l.local_user.admin).unwrap_or(false);
+    // only show removed posts to admin when viewing user profile, or when explicitly requested
+    // by a mod/admin (checked by the caller)
+    if !(self.include_removed || (self.creator_id.is_some() && is_admin)) {
+      query = query
+        .filter(community::removed.eq(false))
+        .filter(post::removed.eq(false));
+    }
+    if let Some(community_id) = self.community_id {
+      query = query.filter(post_aggregates::community_id.eq(community_id));
+    }
+
+    if let Some(exclude_community_ids) = self.exclude_community_ids.clone() {
+      query = query.filter(post_aggregates::community_id.ne_all(exclude_community_ids));
+    }
+
+    if let Some(creator_id) = self.creator_id {
+      query = query.filter(post_aggregates::creator_id.eq(creator_id));
+    }
+
+    if self.skip_viewer_filters {
+      // Posts in hidden communities are included as well
+    } else if let Some(listing_type) = self.listing_type {
+      if let Some(person_id) = my_person_id {
+        let is_subscribed = exists(
+          community_follower::table.filter(
+            post_aggregates::community_id
+              .eq(community_follower::community_id)
+              .and(community_follower::person_id.eq(person_id)),
+          ),
+        );
+        match listing_type {
+          ListingType::Subscribed => query = query.filter(is_subscribed),
+          ListingType::Local => {
+            query = query
+              .filter(community::local.eq(true))
+              .filter(community::hidden.eq(false).or(is_subscribed));
+          }
+          ListingType::All => query = query.filter(community::hidden.eq(false).or(is_subscribed)),
+          ListingType::ModeratorView => {
+            query = query.filter(exists(
+              community_moderator::table.filter(
+                post::community_id
+                  .eq(community_moderator::community_id)
+                  .and(community_moderator::person_id.eq(person_id)),
+              ),
+            ));
+          }
+        }
+      }
+      // If your person_id is missing, only show local
+      else {
+        match listing_type {
+          ListingType::Local => {
+            query = query
+              .filter(community::local.eq(true))
+              .filter(community::hidden.eq(false));
+          }
+          _ => query = query.filter(community::hidden.eq(false)),
+        }
+      }
+    } else {
+      query = query.filter(community::hidden.eq(false));
+    }
+
+    if let Some(url_search) = self.url_search.clone() {
+      query = query.filter(post::url.eq(url_search));
+    }
+
+    if let Some(search_term) = &self.search_term {
+      let searcher = fuzzy_search(search_term);
+      let matches: Box<
+        dyn BoxableExpression<_, Pg, SqlType = sql_types::Nullable<sql_types::Bool>>,
+      > = if self.use_fts {
+        Box::new(fts_matches(POST_FTS_DOCUMENT, search_term).nullable())
+      } else {
+        Box::new(
+          post::name
+            .ilike(searcher.clone())
+            .or(post::body.ilike(searcher.clone())),
+        )
+      };
+      if self.search_history {
+        // The edit history has no full text index, so it is always matched by substring
+        let edited_posts = post_edit_history::table
+          .filter(
+            post_edit_history::name
+              .ilike(searcher.clone())
+              .or(post_edit_history::body.ilike(searcher)),
+          )
+          .select(post_edit_history::post_id);
+        query = query.filter(matches.or(post::id.eq_any(edited_posts)));
+      } else {
+        query = query.filter(matches);
+      }
+    }
+
+    // Link posts can also have a thumbnail, taken from the og:image of the linked page, so media
+    // posts are detected by the content type of their url instead
+    let is_media = || {
+      post::url_content_type
+        .ilike("image/%")
+        .or(post::url_content_type.ilike("video/%"))
+    };
+    match self.has_media {
+      Some(true) => query = query.filter(is_media()),
+      Some(false) => {
+        query = query.filter(post::url_content_type.is_null().or(not(is_media())));
+      }
+      None => {}
+    }
+
+    // Posts with hidden scores always pass the score filters, so that the scores can't be narrowed
+    // down with them
+    if let Some(min_score) = self.min_score {
+      query = query.filter(
+        post_aggregates::score
+          .ge(min_score)
+          .or(post_aggregates::scores_hidden),
+      );
+    }
+
+    if let Some(max_score) = self.max_score {
+      query = query.filter(
+        post_aggregates::score
+          .le(max_score)
+          .or(post_aggregates::scores_hidden),
+      );
+    }
+
+    if let Some(published_after) = self.published_after {
+      query = query.filter(post_aggregates::published.gt(published_after));
+    }
+
+    if let Some(published_before) = self.published_before {
+      query = query.filter(post_aggregates::published.lt(published_before));
+    }
+
+    // If there is a content warning, show nsfw content by default.
+    let has_content_warning = site.content_warning.is_some();
+    if !self.skip_viewer_filters
+      && !self
+        .show_nsfw
+        .or(self.local_user.map(|l| l.local_user.show_nsfw))
+        .unwrap_or(has_content_warning)
+    {
+      query = query
+        .filter(post::nsfw.eq(false))
+        .filter(community::nsfw.eq(false));
+    };
+
+    if !self.skip_viewer_filters
+      && !self
+        .local_user
+        .map(|l| l.local_user.show_bot_accounts)
+        .unwrap_or(true)
+    {
+      query = query.filter(person::bot_account.eq(false));
+    };
+
+    // If its saved only, then filter. The list is ordered by the saved time in this case.
+    if let (true, Some(person_id)) = (self.saved_only, my_person_id) {
+      query = query.filter(exists(
+        post_saved::table.filter(
+          post_aggregates::post_id
+            .eq(post_saved::post_id)
+            .and(post_saved::person_id.eq(person_id)),
+        ),
+      ));
+    }
+    // Only hide the read posts, if the saved_only is false. Otherwise ppl with the hide_read
+    // setting wont be able to see saved posts.
+    else if !self.skip_viewer_filters
+      && !self
+        .local_user
+        .map(|l| l.local_user.show_read_posts)
+        .unwrap_or(true)
+    {
+      // Do not hide read posts when it is a user profile view
+      // Or, only hide read posts on non-profile views
+      if let (None, Some(person_id)) = (self.creator_id, my_person_id) {
+        query = query.filter(not(exists(
+          post_read::table.filter(
+            post_aggregates::post_id
+              .eq(post_read::post_id)
+              .and(post_read::person_id.eq(person_id)),
+          ),
+        )));
+      }
+    }
+
+    if !self.show_hidden && !self.skip_viewer_filters {
+      // If a creator id isn't given (IE its on home or community pages), hide the hidden posts
+      if let (None, Some(person_id)) = (self.creator_id, my_person_id) {
+        query = query.filter(not(exists(
+          post_hide::table.filter(
+            post_aggregates::post_id
+              .eq(post_hide::post_id)
+              .and(post_hide::person_id.eq(person_id)),
+          ),
+        )));
+      }
+    }
+
+    if let Some(person_id) = my_person_id {
+      let liked = |score: i16| {
+        exists(
+          post_like::table.filter(
+            post_aggregates::post_id
+              .eq(post_like::post_id)
+              .and(post_like::person_id.eq(person_id))
+              .and(post_like::score.eq(score)),
+          ),
+        )
+      };
+      if self.liked_only {
+        query = query.filter(liked(1));
+      } else if self.disliked_only {
+        query = query.filter(liked(-1));
+      }
+    };
+
+    // Hide posts in local only communities from unauthenticated users
+    if self.local_user.is_none() && !self.skip_viewer_filters {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    }
+
+    // Dont filter blocks or missing languages for moderator view type
+    if let (Some(person_id), false) = (
+      my_person_id,
+      self.listing_type.unwrap_or_default() == ListingType::ModeratorView
+        || self.skip_viewer_filters,
+    ) {
+      // Filter out the rows with missing languages
+      query = query.filter(exists(
+        local_user_language::table.filter(
+          post::language_id
+            .eq(local_user_language::language_id)
+            .and(local_user_language::local_user_id.eq(local_user_id_join)),
+        ),
+      ));
+
+      // Don't show blocked instances, communities or persons
+      query = query.filter(not(exists(
+        community_block::table.filter(
+          post_aggregates::community_id
+            .eq(community_block::community_id)
+            .and(community_block::person_id.eq(person_id_join)),
+        ),
+      )));
+      query = query.filter(not(exists(
+        instance_block::table.filter(
+          post_aggregates::instance_id
+            .eq(instance_block::instance_id)
+            .and(instance_block::person_id.eq(person_id_join)),
+        ),
+      )));
+      query = query.filter(not(exists(
+        person_block::table.filter(
+          post_aggregates::creator_id
+            .eq(person_block::target_id)
+            .and(person_block::person_id.eq(person_id)),
+        ),
+      )));
+    }
+
+    let time = |interval| post_aggregates::published.gt(now() - interval);
+    // The top sorts are limited to posts of their time frame
+    query = match self.sort.unwrap_or(SortType::Hot) {
+      SortType::TopYear => query.filter(time(1.years())),
+      SortType::TopMonth => query.filter(time(1.months())),
+      SortType::TopWeek => query.filter(time(1.weeks())),
+      SortType::TopDay => query.filter(time(1.days())),
+      SortType::TopHour => query.filter(time(1.hours())),
+      SortType::TopSixHour => query.filter(time(6.hours())),
+      SortType::TopTwelveHour => query.filter(time(12.hours())),
+      SortType::TopThreeMonths => query.filter(time(3.months())),
+      SortType::TopSixMonths => query.filter(time(6.months())),
+      SortType::TopNineMonths => query.filter(time(9.months())),
+      _ => query,
+    };
+
+    query
+  }
+
+  /// The search term to rank posts by, if they are sorted by relevance with full text search.
+  fn relevance_search_term(&self) -> Option<&String> {
+    self
+      .search_term
+      .as_ref()
+      .filter(|_| self.sort == Some(SortType::Relevance) && self.use_fts)
+  }
+
   pub async fn list(self, site: &Site, pool: &mut DbPool<'_>) -> Result<Vec<PostView>, Error> {
-    if self.listing_type == Some(ListingType::Subscribed)
+    let ranks_by_relevance = self.relevance_search_term().is_some();
+    if ranks_by_relevance && (self.page_after.is_some() || self.page_before_or_equal.is_some()) {
+      return Err(Error::QueryBuilderError(
+        "relevance sort cannot be combined with v2 pagination".into(),
+      ));
+    }
+    let my_person_id = self.local_user.map(|l| l.person.id);
+    // The prefetched upper bound is a cursor, so it doesn't work with the relevance rank either
+    let mut posts = if self.listing_type == Some(ListingType::Subscribed)
       && self.community_id.is_none()
       && self.local_user.is_some()
       && self.page_before_or_equal.is_none()
+      && !ranks_by_relevance
     {
       if let Some(query) = self
         .prefetch_upper_bound_for_page_before(site, pool)
         .await?
       {
-        queries().list(pool, (query, site)).await
+        queries().list(pool, (query, site)).await?
       } else {
-        Ok(vec![])
+        vec![]
       }
     } else {
-      queries().list(pool, (self, site)).await
-    }
+      queries().list(pool, (self, site)).await?
+    };
+    hide_new_scores(pool, &mut posts, my_person_id).await?;
+    Ok(posts)
+  }
+
+  /// Counts the posts matching the filters of this query, ignoring pagination.
+  pub async fn count(&self, site: &Site, pool: &mut DbPool<'_>) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    self.filtered_query(site).count().get_result(conn).await
   }
 }
 
@@ -747,7 +1005,7 @@ impl<'a> PostQuery<'a> {
 mod tests {
   use crate::{
     post_view::{PaginationCursorData, PostQuery, PostView},
-    structs::LocalUserView,
+    structs::{LocalUserView, PaginationCursor},
   };
   use chrono::Utc;
   use lemmy_db_schema::{
@@ -1137,12 +1395,145 @@ mod tests {
     .await?;
     assert_eq!(read_disliked_post_listing, vec![]);
 
+    let read_min_score_post_listing = PostQuery {
+      community_id: Some(data.inserted_community.id),
+      min_score: Some(2),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(read_min_score_post_listing, vec![]);
+
+    let read_score_range_post_listing = PostQuery {
+      community_id: Some(data.inserted_community.id),
+      min_score: Some(1),
+      max_score: Some(1),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(read_post_listing, read_score_range_post_listing);
+
+    let read_excluded_community_post_listing = PostQuery {
+      exclude_community_ids: Some(vec![data.inserted_community.id]),
+      liked_only: true,
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(read_excluded_community_post_listing, vec![]);
+
+    // The test post has no url
+    let read_media_post_listing = PostQuery {
+      community_id: Some(data.inserted_community.id),
+      has_media: Some(true),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(read_media_post_listing, vec![]);
+
+    let read_published_after_post_listing = PostQuery {
+      community_id: Some(data.inserted_community.id),
+      published_after: Some(data.inserted_post.published),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(read_published_after_post_listing, vec![]);
+
+    let read_published_before_post_listing = PostQuery {
+      community_id: Some(data.inserted_community.id),
+      published_before: Some(data.inserted_post.published),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(read_published_before_post_listing, vec![]);
+
     let like_removed =
       PostLike::remove(pool, data.local_user_view.person.id, data.inserted_post.id).await?;
     assert_eq!(1, like_removed);
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_hidden_scores() -> LemmyResult<()> {
+    let pool = &build_db_pool().await?;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let post_like_form = PostLikeForm {
+      post_id: data.inserted_post.id,
+      person_id: data.local_user_view.person.id,
+      score: 1,
+    };
+    PostLike::like(pool, &post_like_form).await?;
+
+    let top_query = || PostQuery {
+      community_id: Some(data.inserted_community.id),
+      sort: Some(SortType::TopAll),
+      ..data.default_post_query()
+    };
+    let read_top = top_query().list(&data.site, pool).await?;
+    assert_eq!(vec![POST, POST_BY_BOT], names(&read_top));
+    let hot_query = || PostQuery {
+      sort: Some(SortType::Hot),
+      ..top_query()
+    };
+    let post_ids = [data.inserted_post.id, data.inserted_bot_post.id];
+    for post_id in post_ids {
+      PostAggregates::update_ranks(pool, post_id).await?;
+    }
+    let read_hot = hot_query().list(&data.site, pool).await?;
+    assert_eq!(vec![POST, POST_BY_BOT], names(&read_hot));
+
+    let form = CommunityUpdateForm {
+      hide_scores_until_hours: Some(Some(24)),
+      ..Default::default()
+    };
+    Community::update(pool, data.inserted_community.id, &form).await?;
+
+    // The hot ranks don't include hidden scores either
+    for post_id in post_ids {
+      PostAggregates::update_ranks(pool, post_id).await?;
+    }
+    let read_hot = hot_query().list(&data.site, pool).await?;
+    assert_eq!(vec![POST_BY_BOT, POST], names(&read_hot));
+
+    // Posts with hidden scores are ordered by their publish time, even for admins
+    let read_top = top_query().list(&data.site, pool).await?;
+    assert_eq!(vec![POST_BY_BOT, POST], names(&read_top));
+    let first = read_top.first().ok_or(LemmyErrorType::CouldntFindPost)?;
+    let page_after = PaginationCursor::after_post(first).read(pool).await?;
+    let read_next_page = PostQuery {
+      page_after: Some(page_after),
+      ..top_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![POST], names(&read_next_page));
+    let read_controversial = PostQuery {
+      sort: Some(SortType::Controversial),
+      ..top_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![POST_BY_BOT, POST], names(&read_controversial));
+
+    // And ignore the score filters
+    let read_min_score = PostQuery {
+      min_score: Some(2),
+      ..top_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![POST_BY_BOT, POST], names(&read_min_score));
+
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn creator_info() -> LemmyResult<()> {
@@ -1245,6 +1636,56 @@ mod tests {
     cleanup(data, pool).await
   }
 
+  #[tokio::test]
+  #[serial]
+  async fn post_listing_has_media() -> LemmyResult<()> {
+    const IMAGE_POST: &str = "image post";
+    const LINK_POST: &str = "link post";
+
+    let pool = &build_db_pool().await?;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let thumbnail = Url::parse("https://example.com/thumbnail.png")?;
+    let post_form = |name: &str, url: &str, content_type: &str| -> LemmyResult<_> {
+      Ok(
+        PostInsertForm::builder()
+          .name(name.to_string())
+          .creator_id(data.local_user_view.person.id)
+          .community_id(data.inserted_community.id)
+          .url(Some(Url::parse(url)?.into()))
+          .url_content_type(Some(content_type.to_string()))
+          .thumbnail_url(Some(thumbnail.clone().into()))
+          .build(),
+      )
+    };
+    let image_form = post_form(IMAGE_POST, "https://example.com/image.png", "image/png")?;
+    Post::create(pool, &image_form).await?;
+    // Link posts get a thumbnail from the og:image of the page, but aren't media
+    let link_form = post_form(LINK_POST, "https://example.com/article", "text/html")?;
+    Post::create(pool, &link_form).await?;
+
+    let media_posts = PostQuery {
+      community_id: Some(data.inserted_community.id),
+      has_media: Some(true),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![IMAGE_POST], names(&media_posts));
+
+    let other_posts = PostQuery {
+      community_id: Some(data.inserted_community.id),
+      has_media: Some(false),
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(vec![LINK_POST, POST_BY_BOT, POST], names(&other_posts));
+
+    cleanup(data, pool).await
+  }
+
   #[tokio::test]
   #[serial]
   async fn post_listings_removed() -> LemmyResult<()> {
@@ -1267,6 +1708,18 @@ mod tests {
     let post_listings_no_admin = data.default_post_query().list(&data.site, pool).await?;
     assert_eq!(vec![POST], names(&post_listings_no_admin));
 
+    // Removed post is shown when explicitly requested
+    let post_listings_include_removed = PostQuery {
+      include_removed: true,
+      ..data.default_post_query()
+    }
+    .list(&data.site, pool)
+    .await?;
+    assert_eq!(
+      vec![POST_BY_BOT, POST],
+      names(&post_listings_include_removed)
+    );
+
     // Removed bot post is shown to admins on its profile page
     data.local_user_view.local_user.admin = true;
     let post_listings_is_admin = PostQuery {
@@ -1277,6 +1730,26 @@ mod tests {
     .await?;
     assert_eq!(vec![POST_BY_BOT], names(&post_listings_is_admin));
 
+    // The count uses the same filters as the listing
+    let bot_post_query = PostQuery {
+      creator_id: Some(data.inserted_bot.id),
+      ..data.default_post_query()
+    };
+    assert_eq!(1, bot_post_query.count(&data.site, pool).await?);
+    data.local_user_view.local_user.admin = false;
+    let bot_post_query = PostQuery {
+      creator_id: Some(data.inserted_bot.id),
+      ..data.default_post_query()
+    };
+    assert_eq!(0, bot_post_query.count(&data.site, pool).await?);
+    let bot_post_count_include_removed = PostQuery {
+      include_removed: true,
+      ..bot_post_query
+    }
+    .count(&data.site, pool)
+    .await?;
+    assert_eq!(1, bot_post_count_include_removed);
+
     cleanup(data, pool).await
   }
 
@@ -1316,6 +1789,96 @@ mod tests {
       assert_eq!(expect_contains_deleted, contains_deleted);
     }
 
+    // Deleted post is shown to anyone when explicitly requested
+    let exclude_deleted = PostQuery {
+      local_user: None,
+      ..data.default_post_query()
+    };
+    let include_deleted = PostQuery {
+      include_deleted: true,
+      ..exclude_deleted.clone()
+    };
+    assert_eq!(
+      exclude_deleted.count(&data.site, pool).await? + 1,
+      include_deleted.count(&data.site, pool).await?
+    );
+    let post_listings_include_deleted = include_deleted.list(&data.site, pool).await?;
+    assert!(post_listings_include_deleted
+      .iter()
+      .any(|p| p.post.id == data.inserted_post.id));
+
+    cleanup(data, pool).await
+  }
+
+  #[tokio::test]
+  #[serial]
+  async fn post_listings_full_text_search() -> LemmyResult<()> {
+    const MENTIONS_ONCE: &str = "Cooking with friends";
+    const ABOUT_RUST: &str = "Programming in Rust";
+    let pool = &build_db_pool().await?;
+    let pool = &mut pool.into();
+    let data = init_data(pool).await?;
+
+    let mut mentions_once_id = None;
+    for (name, body) in [
+      (MENTIONS_ONCE, "Programming with rust on pans"),
+      (ABOUT_RUST, "Programming, more programming in rust"),
+    ] {
+      let form = PostInsertForm::builder()
+        .name(name.to_string())
+        .body(Some(body.to_string()))
+        .creator_id(data.local_user_view.person.id)
+        .community_id(data.inserted_community.id)
+        .language_id(Some(LanguageId(47)))
+        .build();
+      let post = Post::create(pool, &form).await?;
+      if name == MENTIONS_ONCE {
+        mentions_once_id = Some(post.id);
+      }
+    }
+
+    let substring_search = PostQuery {
+      search_term: Some("rust programming".to_string()),
+      ..data.default_post_query()
+    };
+    // The words are never in the same order as in the search term
+    let substring_listings = substring_search.clone().list(&data.site, pool).await?;
+    assert!(!names(&substring_listings).contains(&ABOUT_RUST));
+
+    // Full text search matches both, and ranks the post which is about the search term first
+    let fts_search = PostQuery {
+      use_fts: true,
+      sort: Some(SortType::Relevance),
+      ..substring_search
+    };
+    let fts_listings = fts_search.clone().list(&data.site, pool).await?;
+    assert_eq!(vec![ABOUT_RUST, MENTIONS_ONCE], names(&fts_listings));
+    assert_eq!(2, fts_search.count(&data.site, pool).await?);
+
+    // Featured posts still come first
+    Post::update(
+      pool,
+      mentions_once_id.ok_or(LemmyErrorType::CouldntFindPost)?,
+      &PostUpdateForm {
+        featured_local: Some(true),
+        ..Default::default()
+      },
+    )
+    .await?;
+    let fts_listings = fts_search.clone().list(&data.site, pool).await?;
+    assert_eq!(vec![MENTIONS_ONCE, ABOUT_RUST], names(&fts_listings));
+
+    // The rank can't be a pagination key, so cursors are rejected
+    let first = fts_listings
+      .first()
+      .ok_or(LemmyErrorType::CouldntFindPost)?;
+    let page_after = PaginationCursor::after_post(first).read(pool).await?;
+    let cursor_search = PostQuery {
+      page_after: Some(page_after),
+      ..fts_search
+    };
+    assert!(cursor_search.list(&data.site, pool).await.is_err());
+
     cleanup(data, pool).await
   }
 
@@ -1589,6 +2152,7 @@ mod tests {
         url: None,
         body: None,
         alt_text: None,
+        flair_id: None,
         published: inserted_post.published,
         updated: None,
         community_id: inserted_community.id,
@@ -1609,6 +2173,7 @@ mod tests {
       },
       my_vote: None,
       unread_comments: 0,
+      scores_hidden: false,
       creator: Person {
         id: inserted_person.id,
         name: inserted_person.name.clone(),
@@ -1636,6 +2201,7 @@ mod tests {
       banned_from_community: false,
       creator_is_moderator: false,
       creator_is_admin: true,
+      creator_is_new: false,
       community: Community {
         id: inserted_community.id,
         name: inserted_community.name.clone(),
@@ -1662,6 +2228,8 @@ mod tests {
         moderators_url: inserted_community.moderators_url.clone(),
         featured_url: inserted_community.featured_url.clone(),
         visibility: CommunityVisibility::Public,
+        default_sort_type: None,
+        hide_scores_until_hours: None,
       },
       counts: PostAggregates {
         post_id: inserted_post.id,
@@ -1678,6 +2246,7 @@ mod tests {
         hot_rank_active: RANK_DEFAULT,
         controversy_rank: 0.0,
         scaled_rank: RANK_DEFAULT,
+        scores_hidden: false,
         community_id: inserted_post.community_id,
         creator_id: inserted_post.creator_id,
         instance_id: data.inserted_instance.id,
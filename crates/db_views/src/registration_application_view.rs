@@ -274,6 +274,8 @@ mod tests {
         enable_keyboard_navigation: inserted_sara_local_user.enable_keyboard_navigation,
         enable_animated_images: inserted_sara_local_user.enable_animated_images,
         collapse_bot_comments: inserted_sara_local_user.collapse_bot_comments,
+        last_visit: inserted_sara_local_user.last_visit,
+        previous_visit: inserted_sara_local_user.previous_visit,
       },
       creator: Person {
         id: inserted_sara_person.id,
@@ -67,10 +67,15 @@ pub struct CommentView {
   pub banned_from_community: bool,
   pub creator_is_moderator: bool,
   pub creator_is_admin: bool,
+  /// Whether the creator account is younger than [LocalSite.new_user_badge_days].
+  pub creator_is_new: bool,
   pub subscribed: SubscribedType,
   pub saved: bool,
   pub creator_blocked: bool,
   pub my_vote: Option<i16>,
+  /// The score is too new to be shown, see [Community.hide_scores_until_hours]. The counts are
+  /// zeroed in this case.
+  pub scores_hidden: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -133,6 +138,8 @@ pub struct PostView {
   pub banned_from_community: bool,
   pub creator_is_moderator: bool,
   pub creator_is_admin: bool,
+  /// Whether the creator account is younger than [LocalSite.new_user_badge_days].
+  pub creator_is_new: bool,
   pub counts: PostAggregates,
   pub subscribed: SubscribedType,
   pub saved: bool,
@@ -141,6 +148,9 @@ pub struct PostView {
   pub creator_blocked: bool,
   pub my_vote: Option<i16>,
   pub unread_comments: i64,
+  /// The score is too new to be shown, see [Community.hide_scores_until_hours]. The counts are
+  /// zeroed in this case.
+  pub scores_hidden: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone)]
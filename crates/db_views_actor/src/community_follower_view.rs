@@ -9,7 +9,8 @@ use diesel::{
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::{CommunityId, DbUrl, InstanceId, PersonId},
-  schema::{community, community_follower, person},
+  schema::{community, community_follower, instance, person},
+  source::instance::Instance,
   utils::{functions::coalesce, get_conn, DbPool},
 };
 
@@ -58,6 +59,23 @@ impl CommunityFollowerView {
 
     Ok(res)
   }
+  /// Remote instances which have at least one follower of the given community, and so receive
+  /// its activities.
+  pub async fn get_community_follower_instances(
+    pool: &mut DbPool<'_>,
+    community_id: CommunityId,
+  ) -> Result<Vec<Instance>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    community_follower::table
+      .inner_join(person::table.inner_join(instance::table))
+      .filter(community_follower::community_id.eq(community_id))
+      .filter(not(person::local))
+      .select(instance::all_columns)
+      .distinct()
+      .order_by(instance::domain)
+      .load::<Instance>(conn)
+      .await
+  }
   pub async fn count_community_followers(
     pool: &mut DbPool<'_>,
     community_id: CommunityId,
@@ -4,7 +4,8 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::{CommunityId, PersonId},
   schema::{community, community_moderator, person},
-  utils::{get_conn, DbPool},
+  source::community::Community,
+  utils::{get_conn, limit_and_offset, DbPool},
   CommunityVisibility,
 };
 
@@ -77,6 +78,52 @@ impl CommunityModeratorView {
     query.load::<CommunityModeratorView>(conn).await
   }
 
+  /// Communities moderated by the given person, together with their rank in the moderator list
+  /// of each community. The top mod has rank 0.
+  pub async fn for_person_with_rank(
+    pool: &mut DbPool<'_>,
+    person_id: PersonId,
+    only_public: bool,
+  ) -> Result<Vec<(Community, i64)>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let moderated_community_ids = community_moderator::table
+      .filter(community_moderator::person_id.eq(person_id))
+      .select(community_moderator::community_id)
+      .load::<CommunityId>(conn)
+      .await?;
+    let mut query = community_moderator::table
+      .inner_join(community::table)
+      .filter(community_moderator::community_id.eq_any(moderated_community_ids))
+      .filter(community::deleted.eq(false))
+      .filter(community::removed.eq(false))
+      .select((community::all_columns, community_moderator::person_id))
+      .order_by((
+        community_moderator::community_id,
+        community_moderator::published,
+      ))
+      .into_boxed();
+    if only_public {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    }
+    let moderators = query.load::<(Community, PersonId)>(conn).await?;
+
+    // Moderators are ordered by community, so the rank restarts whenever the community changes
+    let mut res = vec![];
+    let mut rank = 0;
+    let mut previous_community_id = None;
+    for (community, moderator_id) in moderators {
+      if previous_community_id != Some(community.id) {
+        previous_community_id = Some(community.id);
+        rank = 0;
+      }
+      if moderator_id == person_id {
+        res.push((community, rank));
+      }
+      rank += 1;
+    }
+    Ok(res)
+  }
+
   /// Finds all communities first mods / creators
   /// Ideally this should be a group by, but diesel doesn't support it yet
   pub async fn get_community_first_mods(pool: &mut DbPool<'_>) -> Result<Vec<Self>, Error> {
@@ -95,4 +142,136 @@ impl CommunityModeratorView {
       .load::<CommunityModeratorView>(conn)
       .await
   }
+
+  /// The moderators of a page of local, public communities, ordered by community. Paginating by
+  /// community keeps all moderators of a community on the same page.
+  pub async fn for_local_communities(
+    pool: &mut DbPool<'_>,
+    page: Option<i64>,
+    limit: Option<i64>,
+  ) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let (limit, offset) = limit_and_offset(page, limit)?;
+    let community_ids = community::table
+      .into_boxed()
+      .filter(community::local.eq(true))
+      .filter(community::deleted.eq(false))
+      .filter(community::removed.eq(false))
+      .filter(community::visibility.eq(CommunityVisibility::Public))
+      .select(community::id)
+      .order_by(community::id)
+      .limit(limit)
+      .offset(offset)
+      .load::<CommunityId>(conn)
+      .await?;
+
+    community_moderator::table
+      .inner_join(community::table)
+      .inner_join(person::table)
+      .filter(community_moderator::community_id.eq_any(community_ids))
+      .select((community::all_columns, person::all_columns))
+      .order_by((
+        community_moderator::community_id,
+        community_moderator::published,
+      ))
+      .load::<CommunityModeratorView>(conn)
+      .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+
+  use crate::structs::CommunityModeratorView;
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityInsertForm, CommunityModerator, CommunityModeratorForm},
+      instance::Instance,
+      person::{Person, PersonInsertForm},
+    },
+    traits::{Crud, Joinable},
+    utils::build_db_pool_for_tests,
+    CommunityVisibility,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_for_person_with_rank() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let alice_form = PersonInsertForm::test_form(inserted_instance.id, "alice_rank");
+    let alice = Person::create(pool, &alice_form).await.unwrap();
+    let bob_form = PersonInsertForm::test_form(inserted_instance.id, "bob_rank");
+    let bob = Person::create(pool, &bob_form).await.unwrap();
+
+    let mut communities = vec![];
+    for (name, visibility) in [
+      ("rank_community_1", CommunityVisibility::Public),
+      ("rank_community_2", CommunityVisibility::Public),
+      ("rank_community_3", CommunityVisibility::LocalOnly),
+    ] {
+      let form = CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .visibility(Some(visibility))
+        .build();
+      communities.push(Community::create(pool, &form).await.unwrap());
+    }
+
+    // Alice is the top mod of the first and third community, bob is the top mod of the second
+    for (community, moderators) in
+      communities
+        .iter()
+        .zip([vec![&alice, &bob], vec![&bob, &alice], vec![&alice, &bob]])
+    {
+      for moderator in moderators {
+        let form = CommunityModeratorForm {
+          community_id: community.id,
+          person_id: moderator.id,
+        };
+        CommunityModerator::join(pool, &form).await.unwrap();
+      }
+    }
+
+    let ranks = |res: Vec<(Community, i64)>| {
+      res
+        .into_iter()
+        .map(|(c, rank)| (c.name, rank))
+        .collect::<Vec<_>>()
+    };
+    let bob_ranks = CommunityModeratorView::for_person_with_rank(pool, bob.id, false)
+      .await
+      .unwrap();
+    assert_eq!(
+      vec![
+        ("rank_community_1".to_string(), 1),
+        ("rank_community_2".to_string(), 0),
+        ("rank_community_3".to_string(), 1),
+      ],
+      ranks(bob_ranks)
+    );
+
+    // Unauthenticated users don't see the local only community
+    let alice_ranks = CommunityModeratorView::for_person_with_rank(pool, alice.id, true)
+      .await
+      .unwrap();
+    assert_eq!(
+      vec![
+        ("rank_community_1".to_string(), 0),
+        ("rank_community_2".to_string(), 1),
+      ],
+      ranks(alice_ranks)
+    );
+
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
 }
@@ -1,5 +1,6 @@
 use crate::structs::{CommunityModeratorView, CommunityView, PersonView};
 use diesel::{
+  dsl::{And, Eq, InnerJoin, IntoBoxed, LeftJoinOn},
   pg::Pg,
   result::Error,
   BoolExpressionMethods,
@@ -22,52 +23,102 @@ use lemmy_db_schema::{
     local_user,
   },
   source::{community::CommunityFollower, local_user::LocalUser, site::Site},
-  utils::{fuzzy_search, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
+  utils::{fuzzy_search, get_conn, limit_and_offset, DbConn, DbPool, ListFn, Queries, ReadFn},
   CommunityVisibility,
   ListingType,
   SortType,
 };
 
+/// A join on the rows of the given person, for example their follow of the community.
+type PersonJoinOn<Lhs, Rhs, PersonColumn> = And<Eq<Lhs, Rhs>, Eq<PersonColumn, PersonId>>;
+
+type BoxedCommunityQuery<'a> = IntoBoxed<
+  'a,
+  LeftJoinOn<
+    LeftJoinOn<
+      LeftJoinOn<
+        LeftJoinOn<
+          LeftJoinOn<
+            InnerJoin<community::table, community_aggregates::table>,
+            community_follower::table,
+            PersonJoinOn<
+              community::id,
+              community_follower::community_id,
+              community_follower::person_id,
+            >,
+          >,
+          instance_block::table,
+          PersonJoinOn<
+            community::instance_id,
+            instance_block::instance_id,
+            instance_block::person_id,
+          >,
+        >,
+        community_block::table,
+        PersonJoinOn<community::id, community_block::community_id, community_block::person_id>,
+      >,
+      community_person_ban::table,
+      PersonJoinOn<
+        community::id,
+        community_person_ban::community_id,
+        community_person_ban::person_id,
+      >,
+    >,
+    local_user::table,
+    Eq<local_user::person_id, PersonId>,
+  >,
+  Pg,
+>;
+
+/// Joins the tables which are needed for both filtering and selecting communities.
+fn joined_query<'a>(my_person_id: Option<PersonId>) -> BoxedCommunityQuery<'a> {
+  // The left join below will return None in this case
+  let person_id_join = my_person_id.unwrap_or(PersonId(-1));
+
+  community::table
+    .inner_join(community_aggregates::table)
+    .left_join(
+      community_follower::table.on(
+        community::id
+          .eq(community_follower::community_id)
+          .and(community_follower::person_id.eq(person_id_join)),
+      ),
+    )
+    .left_join(
+      instance_block::table.on(
+        community::instance_id
+          .eq(instance_block::instance_id)
+          .and(instance_block::person_id.eq(person_id_join)),
+      ),
+    )
+    .left_join(
+      community_block::table.on(
+        community::id
+          .eq(community_block::community_id)
+          .and(community_block::person_id.eq(person_id_join)),
+      ),
+    )
+    .left_join(
+      community_person_ban::table.on(
+        community::id
+          .eq(community_person_ban::community_id)
+          .and(community_person_ban::person_id.eq(person_id_join)),
+      ),
+    )
+    .left_join(local_user::table.on(local_user::person_id.eq(person_id_join)))
+    .into_boxed()
+}
+
+fn not_removed_or_deleted() -> And<Eq<community::removed, bool>, Eq<community::deleted, bool>> {
+  community::removed
+    .eq(false)
+    .and(community::deleted.eq(false))
+}
+
 fn queries<'a>() -> Queries<
   impl ReadFn<'a, CommunityView, (CommunityId, Option<PersonId>, bool)>,
   impl ListFn<'a, CommunityView, (CommunityQuery<'a>, &'a Site)>,
 > {
-  let all_joins = |query: community::BoxedQuery<'a, Pg>, my_person_id: Option<PersonId>| {
-    // The left join below will return None in this case
-    let person_id_join = my_person_id.unwrap_or(PersonId(-1));
-
-    query
-      .inner_join(community_aggregates::table)
-      .left_join(
-        community_follower::table.on(
-          community::id
-            .eq(community_follower::community_id)
-            .and(community_follower::person_id.eq(person_id_join)),
-        ),
-      )
-      .left_join(
-        instance_block::table.on(
-          community::instance_id
-            .eq(instance_block::instance_id)
-            .and(instance_block::person_id.eq(person_id_join)),
-        ),
-      )
-      .left_join(
-        community_block::table.on(
-          community::id
-            .eq(community_block::community_id)
-            .and(community_block::person_id.eq(person_id_join)),
-        ),
-      )
-      .left_join(
-        community_person_ban::table.on(
-          community::id
-            .eq(community_person_ban::community_id)
-            .and(community_person_ban::person_id.eq(person_id_join)),
-        ),
-      )
-  };
-
   let selection = (
     community::all_columns,
     CommunityFollower::select_subscribed_type(),
@@ -76,25 +127,19 @@ fn queries<'a>() -> Queries<
     community_person_ban::person_id.nullable().is_not_null(),
   );
 
-  let not_removed_or_deleted = community::removed
-    .eq(false)
-    .and(community::deleted.eq(false));
-
   let read = move |mut conn: DbConn<'a>,
                    (community_id, my_person_id, is_mod_or_admin): (
     CommunityId,
     Option<PersonId>,
     bool,
   )| async move {
-    let mut query = all_joins(
-      community::table.find(community_id).into_boxed(),
-      my_person_id,
-    )
-    .select(selection);
+    let mut query = joined_query(my_person_id)
+      .filter(community::id.eq(community_id))
+      .select(selection);
 
     // Hide deleted and removed for non-admins or mods
     if !is_mod_or_admin {
-      query = query.filter(not_removed_or_deleted);
+      query = query.filter(not_removed_or_deleted());
     }
 
     // Hide local only communities from unauthenticated users
@@ -108,33 +153,12 @@ fn queries<'a>() -> Queries<
   let list = move |mut conn: DbConn<'a>, (options, site): (CommunityQuery<'a>, &'a Site)| async move {
     use SortType::*;
 
-    let my_person_id = options.local_user.map(|l| l.person_id);
-
-    // The left join below will return None in this case
-    let person_id_join = my_person_id.unwrap_or(PersonId(-1));
-
-    let mut query = all_joins(community::table.into_boxed(), my_person_id)
-      .left_join(local_user::table.on(local_user::person_id.eq(person_id_join)))
-      .select(selection);
-
-    if let Some(search_term) = options.search_term {
-      let searcher = fuzzy_search(&search_term);
-      query = query
-        .filter(community::name.ilike(searcher.clone()))
-        .or_filter(community::title.ilike(searcher))
-    }
-
-    // Hide deleted and removed for non-admins or mods
-    if !options.is_mod_or_admin {
-      query = query.filter(not_removed_or_deleted).filter(
-        community::hidden
-          .eq(false)
-          .or(community_follower::person_id.eq(person_id_join)),
-      );
-    }
+    let mut query = options.filtered_query(site).select(selection);
 
     match options.sort.unwrap_or(Hot) {
-      Hot | Active | Scaled => query = query.order_by(community_aggregates::hot_rank.desc()),
+      Hot | Active | Scaled | Relevance => {
+        query = query.order_by(community_aggregates::hot_rank.desc())
+      }
       NewComments | TopDay | TopTwelveHour | TopSixHour | TopHour => {
         query = query.order_by(community_aggregates::users_active_day.desc())
       }
@@ -152,31 +176,6 @@ fn queries<'a>() -> Queries<
       TopWeek => query = query.order_by(community_aggregates::users_active_week.desc()),
     };
 
-    if let Some(listing_type) = options.listing_type {
-      query = match listing_type {
-        ListingType::Subscribed => query.filter(community_follower::pending.is_not_null()), // TODO could be this: and(community_follower::person_id.eq(person_id_join)),
-        ListingType::Local => query.filter(community::local.eq(true)),
-        _ => query,
-      };
-    }
-
-    // Don't show blocked communities and communities on blocked instances. nsfw communities are
-    // also hidden (based on profile setting)
-    if options.local_user.is_some() {
-      query = query.filter(instance_block::person_id.is_null());
-      query = query.filter(community_block::person_id.is_null());
-      query = query.filter(community::nsfw.eq(false).or(local_user::show_nsfw.eq(true)));
-    } else {
-      // No person in request, only show nsfw communities if show_nsfw is passed into request or if
-      // site has content warning.
-      let has_content_warning = site.content_warning.is_some();
-      if !options.show_nsfw && !has_content_warning {
-        query = query.filter(community::nsfw.eq(false));
-      }
-      // Hide local only communities from unauthenticated users
-      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
-    }
-
     let (limit, offset) = limit_and_offset(options.page, options.limit)?;
     query
       .limit(limit)
@@ -249,6 +248,68 @@ impl<'a> CommunityQuery<'a> {
   pub async fn list(self, site: &Site, pool: &mut DbPool<'_>) -> Result<Vec<CommunityView>, Error> {
     queries().list(pool, (self, site)).await
   }
+
+  /// Builds the query with all filters of this listing applied, but without ordering and
+  /// pagination. Shared by list() and count() so that both match the same communities.
+  fn filtered_query(&self, site: &Site) -> BoxedCommunityQuery<'a> {
+    let my_person_id = self.local_user.map(|l| l.person_id);
+
+    // The left join below will return None in this case
+    let person_id_join = my_person_id.unwrap_or(PersonId(-1));
+
+    let mut query = joined_query(my_person_id);
+
+    if let Some(search_term) = &self.search_term {
+      let searcher = fuzzy_search(search_term);
+      query = query.filter(
+        community::name
+          .ilike(searcher.clone())
+          .or(community::title.ilike(searcher)),
+      );
+    }
+
+    // Hide deleted and removed for non-admins or mods
+    if !self.is_mod_or_admin {
+      query = query.filter(not_removed_or_deleted()).filter(
+        community::hidden
+          .eq(false)
+          .or(community_follower::person_id.eq(person_id_join)),
+      );
+    }
+
+    if let Some(listing_type) = self.listing_type {
+      query = match listing_type {
+        ListingType::Subscribed => query.filter(community_follower::pending.is_not_null()), // TODO could be this: and(community_follower::person_id.eq(person_id_join)),
+        ListingType::Local => query.filter(community::local.eq(true)),
+        _ => query,
+      };
+    }
+
+    // Don't show blocked communities and communities on blocked instances. nsfw communities are
+    // also hidden (based on profile setting)
+    if self.local_user.is_some() {
+      query = query.filter(instance_block::person_id.is_null());
+      query = query.filter(community_block::person_id.is_null());
+      query = query.filter(community::nsfw.eq(false).or(local_user::show_nsfw.eq(true)));
+    } else {
+      // No person in request, only show nsfw communities if show_nsfw is passed into request or if
+      // site has content warning.
+      let has_content_warning = site.content_warning.is_some();
+      if !self.show_nsfw && !has_content_warning {
+        query = query.filter(community::nsfw.eq(false));
+      }
+      // Hide local only communities from unauthenticated users
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    }
+
+    query
+  }
+
+  /// Counts the communities matching the filters of this query, ignoring pagination.
+  pub async fn count(&self, site: &Site, pool: &mut DbPool<'_>) -> Result<i64, Error> {
+    let conn = &mut get_conn(pool).await?;
+    self.filtered_query(site).count().get_result(conn).await
+  }
 }
 
 #[cfg(test)]
@@ -375,6 +436,11 @@ mod tests {
     .await
     .unwrap();
     assert_eq!(0, unauthenticated_query.len());
+    let unauthenticated_count = CommunityQuery::default()
+      .count(&data.site, pool)
+      .await
+      .unwrap();
+    assert_eq!(0, unauthenticated_count);
 
     let authenticated_query = CommunityQuery {
       local_user: Some(&data.local_user),
@@ -384,6 +450,14 @@ mod tests {
     .await
     .unwrap();
     assert_eq!(1, authenticated_query.len());
+    let authenticated_count = CommunityQuery {
+      local_user: Some(&data.local_user),
+      ..Default::default()
+    }
+    .count(&data.site, pool)
+    .await
+    .unwrap();
+    assert_eq!(1, authenticated_count);
 
     let unauthenticated_community =
       CommunityView::read(pool, data.inserted_community.id, None, false)
@@ -1,5 +1,6 @@
 use crate::structs::PersonView;
 use diesel::{
+  dsl::{InnerJoin, IntoBoxed, LeftJoin},
   pg::Pg,
   result::Error,
   BoolExpressionMethods,
@@ -15,6 +16,7 @@ use lemmy_db_schema::{
   utils::{
     functions::coalesce,
     fuzzy_search,
+    get_conn,
     limit_and_offset,
     now,
     DbConn,
@@ -56,28 +58,40 @@ fn post_to_person_sort_type(sort: SortType) -> PersonSortType {
   }
 }
 
+type BoxedPersonQuery<'a> = IntoBoxed<
+  'a,
+  LeftJoin<InnerJoin<person::table, person_aggregates::table>, local_user::table>,
+  Pg,
+>;
+
+/// Joins the tables which are needed for both filtering and selecting persons. Deleted persons
+/// are never included.
+fn joined_query<'a>() -> BoxedPersonQuery<'a> {
+  person::table
+    .inner_join(person_aggregates::table)
+    .left_join(local_user::table)
+    .filter(person::deleted.eq(false))
+    .into_boxed()
+}
+
 fn queries<'a>(
 ) -> Queries<impl ReadFn<'a, PersonView, PersonId>, impl ListFn<'a, PersonView, ListMode>> {
-  let all_joins = move |query: person::BoxedQuery<'a, Pg>| {
-    query
-      .inner_join(person_aggregates::table)
-      .left_join(local_user::table)
-      .filter(person::deleted.eq(false))
-      .select((
-        person::all_columns,
-        person_aggregates::all_columns,
-        coalesce(local_user::admin.nullable(), false),
-      ))
+  let all_joins = move |query: BoxedPersonQuery<'a>| {
+    query.select((
+      person::all_columns,
+      person_aggregates::all_columns,
+      coalesce(local_user::admin.nullable(), false),
+    ))
   };
 
   let read = move |mut conn: DbConn<'a>, person_id: PersonId| async move {
-    all_joins(person::table.find(person_id).into_boxed())
+    all_joins(joined_query().filter(person::id.eq(person_id)))
       .first(&mut conn)
       .await
   };
 
   let list = move |mut conn: DbConn<'a>, mode: ListMode| async move {
-    let mut query = all_joins(person::table.into_boxed());
+    let mut query = all_joins(joined_query());
     match mode {
       ListMode::Admins => {
         query = query
@@ -97,12 +111,7 @@ fn queries<'a>(
           .filter(person::deleted.eq(false));
       }
       ListMode::Query(options) => {
-        if let Some(search_term) = options.search_term {
-          let searcher = fuzzy_search(&search_term);
-          query = query
-            .filter(person::name.ilike(searcher.clone()))
-            .or_filter(person::display_name.ilike(searcher));
-        }
+        query = all_joins(options.filtered_query());
 
         let sort = options.sort.map(post_to_person_sort_type);
         query = match sort.unwrap_or(PersonSortType::CommentScore) {
@@ -117,13 +126,9 @@ fn queries<'a>(
         let (limit, offset) = limit_and_offset(options.page, options.limit)?;
         query = query.limit(limit).offset(offset);
 
-        if let Some(listing_type) = options.listing_type {
-          query = match listing_type {
-            // return nothing as its not possible to follow users
-            ListingType::Subscribed => query.limit(0),
-            ListingType::Local => query.filter(person::local.eq(true)),
-            _ => query,
-          };
+        // return nothing as its not possible to follow users
+        if options.listing_type == Some(ListingType::Subscribed) {
+          query = query.limit(0);
         }
       }
     }
@@ -160,6 +165,37 @@ impl PersonQuery {
   pub async fn list(self, pool: &mut DbPool<'_>) -> Result<Vec<PersonView>, Error> {
     queries().list(pool, ListMode::Query(self)).await
   }
+
+  /// Builds the query with all filters of this listing applied, but without ordering and
+  /// pagination. Shared by list() and count() so that both match the same persons.
+  fn filtered_query<'a>(&self) -> BoxedPersonQuery<'a> {
+    let mut query = joined_query();
+
+    if let Some(search_term) = &self.search_term {
+      let searcher = fuzzy_search(search_term);
+      query = query.filter(
+        person::name
+          .ilike(searcher.clone())
+          .or(person::display_name.ilike(searcher)),
+      );
+    }
+
+    if self.listing_type == Some(ListingType::Local) {
+      query = query.filter(person::local.eq(true));
+    }
+
+    query
+  }
+
+  /// Counts the persons matching the filters of this query, ignoring pagination.
+  pub async fn count(&self, pool: &mut DbPool<'_>) -> Result<i64, Error> {
+    // its not possible to follow users
+    if self.listing_type == Some(ListingType::Subscribed) {
+      return Ok(0);
+    }
+    let conn = &mut get_conn(pool).await?;
+    self.filtered_query().count().get_result(conn).await
+  }
 }
 
 #[cfg(test)]
@@ -351,6 +387,14 @@ mod tests {
     .await?;
     assert_length!(2, list);
 
+    let local_count = PersonQuery {
+      listing_type: Some(ListingType::Local),
+      ..Default::default()
+    }
+    .count(pool)
+    .await?;
+    assert_eq!(1, local_count);
+
     cleanup(data, pool).await
   }
 }
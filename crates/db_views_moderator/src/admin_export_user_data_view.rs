@@ -0,0 +1,62 @@
+use crate::{
+  structs::{AdminExportUserDataView, ModlogListParams},
+  utils::filter_and_paginate,
+};
+use diesel::{
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  IntoSql,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  schema::{admin_export_user_data, person},
+  utils::{get_conn, DbPool},
+};
+
+impl AdminExportUserDataView {
+  pub async fn list(pool: &mut DbPool<'_>, params: ModlogListParams) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+    let person_alias_1 = diesel::alias!(person as person1);
+    let admin_person_id_join = params.mod_person_id.unwrap_or(PersonId(-1));
+    let show_mod_names = !params.hide_modlog_names;
+    let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
+
+    let admin_names_join = admin_export_user_data::admin_person_id
+      .eq(person::id)
+      .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
+    let mut query = admin_export_user_data::table
+      .left_join(person::table.on(admin_names_join))
+      .inner_join(
+        person_alias_1
+          .on(admin_export_user_data::other_person_id.eq(person_alias_1.field(person::id))),
+      )
+      .select((
+        admin_export_user_data::all_columns,
+        person::all_columns.nullable(),
+        person_alias_1.fields(person::all_columns),
+      ))
+      .into_boxed();
+
+    if let Some(admin_person_id) = params.mod_person_id {
+      query = query.filter(admin_export_user_data::admin_person_id.eq(admin_person_id));
+    };
+
+    if let Some(other_person_id) = params.other_person_id {
+      query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
+    };
+
+    filter_and_paginate(
+      query,
+      admin_export_user_data::id,
+      admin_export_user_data::when_,
+      &params,
+    )?
+    .load::<AdminExportUserDataView>(conn)
+    .await
+  }
+}
@@ -1,4 +1,7 @@
-use crate::structs::{AdminPurgeCommentView, ModlogListParams};
+use crate::{
+  structs::{AdminPurgeCommentView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -11,8 +14,9 @@ use diesel::{
 use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
-  schema::{admin_purge_comment, person, post},
-  utils::{get_conn, limit_and_offset, DbPool},
+  schema::{admin_purge_comment, community, person, post},
+  utils::{get_conn, DbPool},
+  CommunityVisibility,
 };
 
 impl AdminPurgeCommentView {
@@ -36,17 +40,24 @@ impl AdminPurgeCommentView {
       ))
       .into_boxed();
 
+    if params.hide_local_only_communities {
+      let public_communities = community::table
+        .filter(community::visibility.eq(CommunityVisibility::Public))
+        .select(community::id);
+      query = query.filter(post::community_id.eq_any(public_communities));
+    };
+
     if let Some(admin_person_id) = params.mod_person_id {
       query = query.filter(admin_purge_comment::admin_person_id.eq(admin_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(admin_purge_comment::when_.desc())
-      .load::<AdminPurgeCommentView>(conn)
-      .await
+    filter_and_paginate(
+      query,
+      admin_purge_comment::id,
+      admin_purge_comment::when_,
+      &params,
+    )?
+    .load::<AdminPurgeCommentView>(conn)
+    .await
   }
 }
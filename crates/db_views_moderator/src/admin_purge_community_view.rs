@@ -1,4 +1,7 @@
-use crate::structs::{AdminPurgeCommunityView, ModlogListParams};
+use crate::{
+  structs::{AdminPurgeCommunityView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,7 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{admin_purge_community, person},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
 };
 
 impl AdminPurgeCommunityView {
@@ -38,13 +41,13 @@ impl AdminPurgeCommunityView {
       query = query.filter(admin_purge_community::admin_person_id.eq(admin_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(admin_purge_community::when_.desc())
-      .load::<AdminPurgeCommunityView>(conn)
-      .await
+    filter_and_paginate(
+      query,
+      admin_purge_community::id,
+      admin_purge_community::when_,
+      &params,
+    )?
+    .load::<AdminPurgeCommunityView>(conn)
+    .await
   }
 }
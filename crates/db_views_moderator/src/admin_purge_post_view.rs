@@ -1,4 +1,7 @@
-use crate::structs::{AdminPurgePostView, ModlogListParams};
+use crate::{
+  structs::{AdminPurgePostView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,8 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{admin_purge_post, community, person},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
+  CommunityVisibility,
 };
 
 impl AdminPurgePostView {
@@ -36,17 +40,21 @@ impl AdminPurgePostView {
       ))
       .into_boxed();
 
+    if params.hide_local_only_communities {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    };
+
     if let Some(admin_person_id) = params.mod_person_id {
       query = query.filter(admin_purge_post::admin_person_id.eq(admin_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(admin_purge_post::when_.desc())
-      .load::<AdminPurgePostView>(conn)
-      .await
+    filter_and_paginate(
+      query,
+      admin_purge_post::id,
+      admin_purge_post::when_,
+      &params,
+    )?
+    .load::<AdminPurgePostView>(conn)
+    .await
   }
 }
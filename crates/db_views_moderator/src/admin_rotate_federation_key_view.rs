@@ -0,0 +1,53 @@
+use crate::{
+  structs::{AdminRotateFederationKeyView, ModlogListParams},
+  utils::filter_and_paginate,
+};
+use diesel::{
+  result::Error,
+  BoolExpressionMethods,
+  ExpressionMethods,
+  IntoSql,
+  JoinOnDsl,
+  NullableExpressionMethods,
+  QueryDsl,
+};
+use diesel_async::RunQueryDsl;
+use lemmy_db_schema::{
+  newtypes::PersonId,
+  schema::{admin_rotate_federation_key, person},
+  utils::{get_conn, DbPool},
+};
+
+impl AdminRotateFederationKeyView {
+  pub async fn list(pool: &mut DbPool<'_>, params: ModlogListParams) -> Result<Vec<Self>, Error> {
+    let conn = &mut get_conn(pool).await?;
+
+    let admin_person_id_join = params.mod_person_id.unwrap_or(PersonId(-1));
+    let show_mod_names = !params.hide_modlog_names;
+    let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
+
+    let admin_names_join = admin_rotate_federation_key::admin_person_id
+      .eq(person::id)
+      .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
+    let mut query = admin_rotate_federation_key::table
+      .left_join(person::table.on(admin_names_join))
+      .select((
+        admin_rotate_federation_key::all_columns,
+        person::all_columns.nullable(),
+      ))
+      .into_boxed();
+
+    if let Some(admin_person_id) = params.mod_person_id {
+      query = query.filter(admin_rotate_federation_key::admin_person_id.eq(admin_person_id));
+    };
+
+    filter_and_paginate(
+      query,
+      admin_rotate_federation_key::id,
+      admin_rotate_federation_key::when_,
+      &params,
+    )?
+    .load::<AdminRotateFederationKeyView>(conn)
+    .await
+  }
+}
@@ -1,4 +1,6 @@
 #[cfg(feature = "full")]
+pub mod admin_export_user_data_view;
+#[cfg(feature = "full")]
 pub mod admin_purge_comment_view;
 #[cfg(feature = "full")]
 pub mod admin_purge_community_view;
@@ -7,6 +9,8 @@ pub mod admin_purge_person_view;
 #[cfg(feature = "full")]
 pub mod admin_purge_post_view;
 #[cfg(feature = "full")]
+pub mod admin_rotate_federation_key_view;
+#[cfg(feature = "full")]
 pub mod mod_add_community_view;
 #[cfg(feature = "full")]
 pub mod mod_add_view;
@@ -29,3 +33,5 @@ pub mod mod_remove_post_view;
 #[cfg(feature = "full")]
 pub mod mod_transfer_community_view;
 pub mod structs;
+#[cfg(feature = "full")]
+mod utils;
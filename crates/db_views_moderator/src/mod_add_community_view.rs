@@ -1,4 +1,7 @@
-use crate::structs::{ModAddCommunityView, ModlogListParams};
+use crate::{
+  structs::{ModAddCommunityView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,8 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{community, mod_add_community, person},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
+  CommunityVisibility,
 };
 
 impl ModAddCommunityView {
@@ -40,6 +44,10 @@ impl ModAddCommunityView {
       ))
       .into_boxed();
 
+    if params.hide_local_only_communities {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    };
+
     if let Some(mod_person_id) = params.mod_person_id {
       query = query.filter(mod_add_community::mod_person_id.eq(mod_person_id));
     };
@@ -52,13 +60,13 @@ impl ModAddCommunityView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_add_community::when_.desc())
-      .load::<ModAddCommunityView>(conn)
-      .await
+    filter_and_paginate(
+      query,
+      mod_add_community::id,
+      mod_add_community::when_,
+      &params,
+    )?
+    .load::<ModAddCommunityView>(conn)
+    .await
   }
 }
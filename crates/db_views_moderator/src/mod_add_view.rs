@@ -1,4 +1,7 @@
-use crate::structs::{ModAddView, ModlogListParams};
+use crate::{
+  structs::{ModAddView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,7 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{mod_add, person},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
 };
 
 impl ModAddView {
@@ -44,12 +47,7 @@ impl ModAddView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_add::when_.desc())
+    filter_and_paginate(query, mod_add::id, mod_add::when_, &params)?
       .load::<ModAddView>(conn)
       .await
   }
@@ -1,4 +1,7 @@
-use crate::structs::{ModBanFromCommunityView, ModlogListParams};
+use crate::{
+  structs::{ModBanFromCommunityView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,8 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{community, mod_ban_from_community, person},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
+  CommunityVisibility,
 };
 
 impl ModBanFromCommunityView {
@@ -42,6 +46,10 @@ impl ModBanFromCommunityView {
       ))
       .into_boxed();
 
+    if params.hide_local_only_communities {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    };
+
     if let Some(mod_person_id) = params.mod_person_id {
       query = query.filter(mod_ban_from_community::mod_person_id.eq(mod_person_id));
     };
@@ -54,13 +62,13 @@ impl ModBanFromCommunityView {
       query = query.filter(mod_ban_from_community::other_person_id.eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_ban_from_community::when_.desc())
-      .load::<ModBanFromCommunityView>(conn)
-      .await
+    filter_and_paginate(
+      query,
+      mod_ban_from_community::id,
+      mod_ban_from_community::when_,
+      &params,
+    )?
+    .load::<ModBanFromCommunityView>(conn)
+    .await
   }
 }
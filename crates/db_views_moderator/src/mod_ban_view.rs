@@ -1,4 +1,7 @@
-use crate::structs::{ModBanView, ModlogListParams};
+use crate::{
+  structs::{ModBanView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,7 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{mod_ban, person},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
 };
 
 impl ModBanView {
@@ -44,13 +47,109 @@ impl ModBanView {
       query = query.filter(person_alias_1.field(person::id).eq(other_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_ban::when_.desc())
+    filter_and_paginate(query, mod_ban::id, mod_ban::when_, &params)?
       .load::<ModBanView>(conn)
       .await
   }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::structs::{ModBanView, ModlogListParams};
+  use chrono::{TimeDelta, Utc};
+  use diesel::{ExpressionMethods, QueryDsl};
+  use diesel_async::RunQueryDsl;
+  use lemmy_db_schema::{
+    schema::mod_ban,
+    source::{
+      instance::Instance,
+      moderator::{ModBan, ModBanForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::{build_db_pool_for_tests, get_conn},
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_list_since() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let admin = Person::create(
+      pool,
+      &PersonInsertForm::test_form(inserted_instance.id, "modlog_since_admin"),
+    )
+    .await
+    .unwrap();
+    let banned = Person::create(
+      pool,
+      &PersonInsertForm::test_form(inserted_instance.id, "modlog_since_banned"),
+    )
+    .await
+    .unwrap();
+
+    let form = ModBanForm {
+      mod_person_id: admin.id,
+      other_person_id: banned.id,
+      reason: None,
+      banned: Some(true),
+      expires: None,
+    };
+    let old_ban = ModBan::create(pool, &form).await.unwrap();
+    let new_ban = ModBan::create(pool, &form).await.unwrap();
+
+    // Move the entries to known times
+    let now = Utc::now();
+    for (id, when_) in [
+      (old_ban.id, now - TimeDelta::try_days(2).unwrap()),
+      (new_ban.id, now - TimeDelta::try_hours(1).unwrap()),
+    ] {
+      diesel::update(mod_ban::table.find(id))
+        .set(mod_ban::when_.eq(when_))
+        .execute(&mut get_conn(pool).await.unwrap())
+        .await
+        .unwrap();
+    }
+
+    let list = |since| ModlogListParams {
+      community_id: None,
+      mod_person_id: Some(admin.id),
+      other_person_id: None,
+      post_id: None,
+      comment_id: None,
+      page: None,
+      limit: None,
+      page_after: None,
+      ascending: false,
+      since,
+      id: None,
+      hide_modlog_names: false,
+      hide_local_only_communities: false,
+    };
+
+    let all = ModBanView::list(pool, list(None)).await.unwrap();
+    assert_eq!(2, all.len());
+
+    let since_yesterday = ModBanView::list(pool, list(Some(now - TimeDelta::try_days(1).unwrap())))
+      .await
+      .unwrap();
+    assert_eq!(1, since_yesterday.len());
+    assert_eq!(new_ban.id, since_yesterday[0].mod_ban.id);
+
+    let since_now = ModBanView::list(pool, list(Some(now))).await.unwrap();
+    assert!(since_now.is_empty());
+
+    Person::delete(pool, admin.id).await.unwrap();
+    Person::delete(pool, banned.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
+  }
+}
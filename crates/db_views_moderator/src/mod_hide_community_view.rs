@@ -1,4 +1,7 @@
-use crate::structs::{ModHideCommunityView, ModlogListParams};
+use crate::{
+  structs::{ModHideCommunityView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,8 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{community, mod_hide_community, person},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
+  CommunityVisibility,
 };
 
 impl ModHideCommunityView {
@@ -37,6 +41,10 @@ impl ModHideCommunityView {
       ))
       .into_boxed();
 
+    if params.hide_local_only_communities {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    };
+
     if let Some(community_id) = params.community_id {
       query = query.filter(mod_hide_community::community_id.eq(community_id));
     };
@@ -45,13 +53,13 @@ impl ModHideCommunityView {
       query = query.filter(mod_hide_community::mod_person_id.eq(admin_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_hide_community::when_.desc())
-      .load::<ModHideCommunityView>(conn)
-      .await
+    filter_and_paginate(
+      query,
+      mod_hide_community::id,
+      mod_hide_community::when_,
+      &params,
+    )?
+    .load::<ModHideCommunityView>(conn)
+    .await
   }
 }
@@ -1,4 +1,7 @@
-use crate::structs::{ModLockPostView, ModlogListParams};
+use crate::{
+  structs::{ModLockPostView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,8 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{community, mod_lock_post, person, post},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
+  CommunityVisibility,
 };
 
 impl ModLockPostView {
@@ -25,7 +29,7 @@ impl ModLockPostView {
     let show_mod_names_expr = show_mod_names.as_sql::<diesel::sql_types::Bool>();
 
     let admin_names_join = mod_lock_post::mod_person_id
-      .eq(person::id)
+      .eq(person::id.nullable())
       .and(show_mod_names_expr.or(person::id.eq(admin_person_id_join)));
     let mut query = mod_lock_post::table
       .left_join(person::table.on(admin_names_join))
@@ -40,6 +44,10 @@ impl ModLockPostView {
       ))
       .into_boxed();
 
+    if params.hide_local_only_communities {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    };
+
     if let Some(community_id) = params.community_id {
       query = query.filter(post::community_id.eq(community_id));
     };
@@ -56,12 +64,7 @@ impl ModLockPostView {
       query = query.filter(post::id.eq(post_id));
     }
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_lock_post::when_.desc())
+    filter_and_paginate(query, mod_lock_post::id, mod_lock_post::when_, &params)?
       .load::<ModLockPostView>(conn)
       .await
   }
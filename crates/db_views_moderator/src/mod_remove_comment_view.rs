@@ -1,4 +1,7 @@
-use crate::structs::{ModRemoveCommentView, ModlogListParams};
+use crate::{
+  structs::{ModRemoveCommentView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,8 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{comment, community, mod_remove_comment, person, post},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
+  CommunityVisibility,
 };
 
 impl ModRemoveCommentView {
@@ -42,6 +46,10 @@ impl ModRemoveCommentView {
       ))
       .into_boxed();
 
+    if params.hide_local_only_communities {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    };
+
     if let Some(community_id) = params.community_id {
       query = query.filter(post::community_id.eq(community_id));
     };
@@ -58,13 +66,13 @@ impl ModRemoveCommentView {
       query = query.filter(comment::id.eq(comment_id));
     }
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_remove_comment::when_.desc())
-      .load::<ModRemoveCommentView>(conn)
-      .await
+    filter_and_paginate(
+      query,
+      mod_remove_comment::id,
+      mod_remove_comment::when_,
+      &params,
+    )?
+    .load::<ModRemoveCommentView>(conn)
+    .await
   }
 }
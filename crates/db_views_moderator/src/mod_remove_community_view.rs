@@ -1,4 +1,7 @@
-use crate::structs::{ModRemoveCommunityView, ModlogListParams};
+use crate::{
+  structs::{ModRemoveCommunityView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,8 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{community, mod_remove_community, person},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
+  CommunityVisibility,
 };
 
 impl ModRemoveCommunityView {
@@ -35,17 +39,115 @@ impl ModRemoveCommunityView {
       ))
       .into_boxed();
 
+    if params.hide_local_only_communities {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    };
+
     if let Some(mod_person_id) = params.mod_person_id {
       query = query.filter(mod_remove_community::mod_person_id.eq(mod_person_id));
     };
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+    filter_and_paginate(
+      query,
+      mod_remove_community::id,
+      mod_remove_community::when_,
+      &params,
+    )?
+    .load::<ModRemoveCommunityView>(conn)
+    .await
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+#[allow(clippy::indexing_slicing)]
+mod tests {
+
+  use crate::structs::{ModRemoveCommunityView, ModlogListParams};
+  use lemmy_db_schema::{
+    source::{
+      community::{Community, CommunityInsertForm},
+      instance::Instance,
+      moderator::{ModRemoveCommunity, ModRemoveCommunityForm},
+      person::{Person, PersonInsertForm},
+    },
+    traits::Crud,
+    utils::build_db_pool_for_tests,
+    CommunityVisibility,
+  };
+  use pretty_assertions::assert_eq;
+  use serial_test::serial;
+
+  #[tokio::test]
+  #[serial]
+  async fn test_hide_local_only_communities() {
+    let pool = &build_db_pool_for_tests().await;
+    let pool = &mut pool.into();
+
+    let inserted_instance = Instance::read_or_create(pool, "my_domain.tld".to_string())
+      .await
+      .unwrap();
+    let admin = Person::create(
+      pool,
+      &PersonInsertForm::test_form(inserted_instance.id, "modlog_visibility_admin"),
+    )
+    .await
+    .unwrap();
+
+    let mut community_ids = vec![];
+    for (name, visibility) in [
+      ("modlog_public", CommunityVisibility::Public),
+      ("modlog_local_only", CommunityVisibility::LocalOnly),
+    ] {
+      let form = CommunityInsertForm::builder()
+        .name(name.to_string())
+        .title("nada".to_owned())
+        .public_key("pubkey".to_string())
+        .instance_id(inserted_instance.id)
+        .visibility(Some(visibility))
+        .build();
+      let community = Community::create(pool, &form).await.unwrap();
+      let form = ModRemoveCommunityForm {
+        mod_person_id: admin.id,
+        community_id: community.id,
+        reason: None,
+        removed: Some(true),
+      };
+      ModRemoveCommunity::create(pool, &form).await.unwrap();
+      community_ids.push(community.id);
+    }
+
+    let list = |hide_local_only_communities| ModlogListParams {
+      community_id: None,
+      mod_person_id: Some(admin.id),
+      other_person_id: None,
+      post_id: None,
+      comment_id: None,
+      page: None,
+      limit: None,
+      page_after: None,
+      ascending: false,
+      since: None,
+      id: None,
+      hide_modlog_names: false,
+      hide_local_only_communities,
+    };
+
+    let all = ModRemoveCommunityView::list(pool, list(false))
+      .await
+      .unwrap();
+    assert_eq!(2, all.len());
 
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_remove_community::when_.desc())
-      .load::<ModRemoveCommunityView>(conn)
+    let public = ModRemoveCommunityView::list(pool, list(true))
       .await
+      .unwrap();
+    assert_eq!(1, public.len());
+    assert_eq!(community_ids[0], public[0].community.id);
+
+    for community_id in community_ids {
+      Community::delete(pool, community_id).await.unwrap();
+    }
+    Person::delete(pool, admin.id).await.unwrap();
+    Instance::delete(pool, inserted_instance.id).await.unwrap();
   }
 }
@@ -1,4 +1,7 @@
-use crate::structs::{ModRemovePostView, ModlogListParams};
+use crate::{
+  structs::{ModRemovePostView, ModlogListParams},
+  utils::filter_and_paginate,
+};
 use diesel::{
   result::Error,
   BoolExpressionMethods,
@@ -12,7 +15,8 @@ use diesel_async::RunQueryDsl;
 use lemmy_db_schema::{
   newtypes::PersonId,
   schema::{community, mod_remove_post, person, post},
-  utils::{get_conn, limit_and_offset, DbPool},
+  utils::{get_conn, DbPool},
+  CommunityVisibility,
 };
 
 impl ModRemovePostView {
@@ -40,6 +44,10 @@ impl ModRemovePostView {
       ))
       .into_boxed();
 
+    if params.hide_local_only_communities {
+      query = query.filter(community::visibility.eq(CommunityVisibility::Public));
+    };
+
     if let Some(community_id) = params.community_id {
       query = query.filter(post::community_id.eq(community_id));
     };
@@ -56,12 +64,7 @@ impl ModRemovePostView {
       query = query.filter(post::id.eq(post_id));
     }
 
-    let (limit, offset) = limit_and_offset(params.page, params.limit)?;
-
-    query
-      .limit(limit)
-      .offset(offset)
-      .order_by(mod_remove_post::when_.desc())
+    filter_and_paginate(query, mod_remove_post::id, mod_remove_post::when_, &params)?
       .load::<ModRemovePostView>(conn)
       .await
   }
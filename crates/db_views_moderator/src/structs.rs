@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 #[cfg(feature = "full")]
 use diesel::Queryable;
 use lemmy_db_schema::{
@@ -6,10 +7,12 @@ use lemmy_db_schema::{
     comment::Comment,
     community::Community,
     moderator::{
+      AdminExportUserData,
       AdminPurgeComment,
       AdminPurgeCommunity,
       AdminPurgePerson,
       AdminPurgePost,
+      AdminRotateFederationKey,
       ModAdd,
       ModAddCommunity,
       ModBan,
@@ -219,7 +222,30 @@ pub struct AdminPurgePostView {
 }
 
 #[skip_serializing_none]
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS, Queryable))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin replaces the federation keypair of the instance.
+pub struct AdminRotateFederationKeyView {
+  pub admin_rotate_federation_key: AdminRotateFederationKey,
+  pub admin: Option<Person>,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[cfg_attr(feature = "full", derive(TS, Queryable))]
+#[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
+#[cfg_attr(feature = "full", ts(export))]
+/// When an admin exports the data of another user.
+pub struct AdminExportUserDataView {
+  pub admin_export_user_data: AdminExportUserData,
+  pub admin: Option<Person>,
+  pub exported_person: Person,
+}
+
+#[skip_serializing_none]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
 #[cfg_attr(feature = "full", derive(TS, Queryable))]
 #[cfg_attr(feature = "full", diesel(check_for_backend(diesel::pg::Pg)))]
 #[cfg_attr(feature = "full", ts(export))]
@@ -232,5 +258,16 @@ pub struct ModlogListParams {
   pub comment_id: Option<CommentId>,
   pub page: Option<i64>,
   pub limit: Option<i64>,
+  /// Only return entries with a lower id than this (higher if ascending), instead of using the
+  /// page offset.
+  pub page_after: Option<i32>,
+  /// Return the oldest entries first.
+  pub ascending: bool,
+  /// Only return entries which were created after this time.
+  pub since: Option<DateTime<Utc>>,
+  /// Only return the entry with this id. Requires listing a single entry type.
+  pub id: Option<i32>,
   pub hide_modlog_names: bool,
+  /// Hide entries in local only communities, for callers who aren't logged in.
+  pub hide_local_only_communities: bool,
 }
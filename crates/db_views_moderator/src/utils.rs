@@ -0,0 +1,53 @@
+use crate::structs::ModlogListParams;
+use chrono::{DateTime, Utc};
+use diesel::{
+  dsl,
+  expression::Expression,
+  query_dsl::methods::{FilterDsl, LimitDsl, OffsetDsl, OrderDsl},
+  result::Error,
+  sql_types,
+  ExpressionMethods,
+};
+use lemmy_db_schema::utils::limit_and_offset;
+
+/// Applies the filters and the pagination which are the same for every modlog table.
+///
+/// Entries are always ordered by id, which is also the order in which they were created. This
+/// way the page offset and the page_after cursor return the entries in the same order.
+pub(crate) fn filter_and_paginate<Q, Id, When>(
+  mut query: Q,
+  id: Id,
+  when_: When,
+  params: &ModlogListParams,
+) -> Result<Q, Error>
+where
+  Id: Expression<SqlType = sql_types::Integer> + ExpressionMethods + Copy,
+  When: Expression<SqlType = sql_types::Timestamptz> + ExpressionMethods,
+  Q: FilterDsl<dsl::Gt<When, DateTime<Utc>>, Output = Q>
+    + FilterDsl<dsl::Eq<Id, i32>, Output = Q>
+    + FilterDsl<dsl::Lt<Id, i32>, Output = Q>
+    + FilterDsl<dsl::Gt<Id, i32>, Output = Q>
+    + OrderDsl<dsl::Desc<Id>, Output = Q>
+    + OrderDsl<dsl::Asc<Id>, Output = Q>
+    + OffsetDsl<Output = Q>
+    + LimitDsl<Output = Q>,
+{
+  if let Some(since) = params.since {
+    query = query.filter(when_.gt(since));
+  };
+
+  if let Some(entry_id) = params.id {
+    query = query.filter(id.eq(entry_id));
+  };
+
+  let (limit, offset) = limit_and_offset(params.page, params.limit)?;
+
+  query = match (params.page_after, params.ascending) {
+    (Some(page_after), false) => query.filter(id.lt(page_after)).order(id.desc()),
+    (Some(page_after), true) => query.filter(id.gt(page_after)).order(id.asc()),
+    (None, false) => query.offset(offset).order(id.desc()),
+    (None, true) => query.offset(offset).order(id.asc()),
+  };
+
+  Ok(query.limit(limit))
+}
@@ -4,7 +4,7 @@ use chrono::{Local, Timelike};
 use lemmy_api_common::{context::LemmyContext, federate_retry_sleep_duration};
 use lemmy_db_schema::{
   newtypes::InstanceId,
-  source::{federation_queue_state::FederationQueueState, instance::Instance},
+  source::{federation_queue_state::FederationQueueState, instance::Instance, person::Person},
   utils::{ActualDbPool, DbPool},
 };
 use std::{collections::HashMap, time::Duration};
@@ -48,12 +48,16 @@ async fn start_stop_federation_workers(
     let mut total_count = 0;
     let mut dead_count = 0;
     let mut disallowed_count = 0;
+    let mut federating_instance_ids = vec![];
     for (instance, allowed, is_dead) in
       Instance::read_federated_with_blocked_and_dead(pool2).await?
     {
       if instance.domain == local_domain {
         continue;
       }
+      if allowed && !is_dead {
+        federating_instance_ids.push(instance.id);
+      }
       if instance.id.inner() % opts.process_count != process_index {
         continue;
       }
@@ -101,6 +105,14 @@ async fn start_stop_federation_workers(
         }
       }
     }
+    // Only the first process does this, as it covers the instances of all processes
+    if process_index == 0 {
+      match Person::scrub_sent_private_keys(pool2, federating_instance_ids).await {
+        Ok(0) => {}
+        Ok(count) => tracing::info!("Removed private keys of {count} deleted users"),
+        Err(e) => tracing::error!("error removing private keys of deleted users: {e}"),
+      }
+    }
     let worker_count = workers.len();
     tracing::info!("Federating to {worker_count}/{total_count} instances ({dead_count} dead, {disallowed_count} disallowed)");
     tokio::select! {
@@ -98,15 +98,20 @@ impl CancellableTask {
   }
 }
 
-/// assuming apub priv key and ids are immutable, then we don't need to have TTL
+/// The private key of the instance actor can be rotated by admins, so actors are only cached for
+/// a short time. Otherwise activities would keep being signed with the old key.
 /// TODO: capacity should be configurable maybe based on memory use
 pub(crate) async fn get_actor_cached(
   pool: &mut DbPool<'_>,
   actor_type: ActorType,
   actor_apub_id: &Url,
 ) -> Result<Arc<SiteOrCommunityOrUser>> {
-  static CACHE: Lazy<Cache<Url, Arc<SiteOrCommunityOrUser>>> =
-    Lazy::new(|| Cache::builder().max_capacity(10000).build());
+  static CACHE: Lazy<Cache<Url, Arc<SiteOrCommunityOrUser>>> = Lazy::new(|| {
+    Cache::builder()
+      .max_capacity(10000)
+      .time_to_live(CACHE_DURATION_FEDERATION)
+      .build()
+  });
   CACHE
     .try_get_with(actor_apub_id.clone(), async {
       let url = actor_apub_id.clone().into();
@@ -1,16 +1,38 @@
-use actix_web::{error::ErrorBadRequest, web, Error, HttpResponse, Result};
+use actix_web::{
+  error::ErrorBadRequest,
+  web::{self, Json, Query},
+  Error,
+  HttpResponse,
+  Result,
+};
 use anyhow::anyhow;
-use lemmy_api_common::context::LemmyContext;
-use lemmy_db_schema::RegistrationMode;
-use lemmy_db_views::structs::SiteView;
+use lemmy_api_common::{
+  context::LemmyContext,
+  site::{GetInstanceInfo, GetInstanceInfoResponse},
+  utils::check_private_instance,
+};
+use lemmy_db_schema::{
+  source::{instance::Instance, local_site::LocalSite},
+  RegistrationMode,
+};
+use lemmy_db_views::structs::{LocalUserView, SiteView};
 use lemmy_utils::{
   cache_header::{cache_1hour, cache_3days},
-  error::{LemmyError, LemmyResult},
+  error::{LemmyError, LemmyErrorExt, LemmyErrorType, LemmyResult},
   VERSION,
 };
+use moka::future::Cache;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 use url::Url;
 
+/// How long fetched nodeinfo of other instances is cached.
+const INSTANCE_INFO_CACHE_DURATION: Duration = Duration::from_secs(60 * 60);
+
+/// Matches nodeinfo schema 2.0 and 2.1, which are compatible for the fields used here.
+const NODEINFO_SCHEMA_PREFIX: &str = "http://nodeinfo.diaspora.software/ns/schema/2.";
+
 pub fn config(cfg: &mut web::ServiceConfig) {
   cfg
     .route(
@@ -73,6 +95,85 @@ async fn node_info(context: web::Data<LemmyContext>) -> Result<HttpResponse, Err
   Ok(HttpResponse::Ok().json(json))
 }
 
+/// Returns the nodeinfo of a federated instance. This only works for instances which are already
+/// known, so that it can't be used to send requests to arbitrary hosts.
+#[tracing::instrument(skip(context))]
+pub async fn get_instance_info(
+  data: Query<GetInstanceInfo>,
+  context: web::Data<LemmyContext>,
+  local_user_view: Option<LocalUserView>,
+) -> LemmyResult<Json<GetInstanceInfoResponse>> {
+  static CACHE: Lazy<Cache<String, GetInstanceInfoResponse>> = Lazy::new(|| {
+    Cache::builder()
+      .max_capacity(1000)
+      .time_to_live(INSTANCE_INFO_CACHE_DURATION)
+      .build()
+  });
+
+  let local_site = LocalSite::read(&mut context.pool()).await?;
+  check_private_instance(&local_user_view, &local_site)?;
+
+  let instance = Instance::read_from_domain(&mut context.pool(), &data.domain)
+    .await?
+    .ok_or(LemmyErrorType::CouldntFindInstance)?;
+
+  let instance_info = CACHE
+    .try_get_with(
+      instance.domain.clone(),
+      fetch_instance_info(instance.domain, &context),
+    )
+    .await
+    .map_err(|e| anyhow!("{e}"))
+    .with_lemmy_type(LemmyErrorType::CouldntFetchNodeInfo)?;
+  Ok(Json(instance_info))
+}
+
+async fn fetch_instance_info(
+  domain: String,
+  context: &LemmyContext,
+) -> LemmyResult<GetInstanceInfoResponse> {
+  let client = context.client();
+
+  // Not all software serves nodeinfo at the same path, so prefer the advertised link
+  let well_known_url = format!("https://{domain}/.well-known/nodeinfo");
+  let well_known = match client.get(&well_known_url).send().await {
+    Ok(res) => res.json::<NodeInfoWellKnown>().await.ok(),
+    Err(_) => None,
+  };
+  let default_url = Url::parse(&format!("https://{domain}/nodeinfo/2.0.json"))?;
+  let node_info_url = well_known
+    .and_then(|w| {
+      w.links
+        .into_iter()
+        .find(|l| l.rel.as_str().starts_with(NODEINFO_SCHEMA_PREFIX))
+    })
+    .map(|l| l.href)
+    .filter(|href| href.domain() == Some(domain.as_str()))
+    .unwrap_or(default_url);
+
+  let node_info = client
+    .get(node_info_url.as_str())
+    .send()
+    .await?
+    .json::<NodeInfo>()
+    .await?;
+
+  let software = node_info.software.unwrap_or_default();
+  let usage = node_info.usage.unwrap_or_default();
+  let users = usage.users.unwrap_or_default();
+  Ok(GetInstanceInfoResponse {
+    domain,
+    software: software.name,
+    version: software.version,
+    users: users.total,
+    users_active_month: users.active_month,
+    users_active_half_year: users.active_halfyear,
+    local_posts: usage.local_posts,
+    local_comments: usage.local_comments,
+    open_registrations: node_info.open_registrations,
+  })
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct NodeInfoWellKnown {
   pub links: Vec<NodeInfoWellKnownLinks>,
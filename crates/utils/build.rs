@@ -1,3 +1,9 @@
+use std::{
+  env,
+  process::Command,
+  time::{SystemTime, UNIX_EPOCH},
+};
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
   rosetta_build::config()
     .source("en", "translations/email/en.json")
@@ -7,5 +13,34 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     .fallback("en")
     .generate()?;
 
+  // Optional build metadata, exposed to clients through GetSite
+  if let Some(commit) = git(&["rev-parse", "HEAD"]) {
+    println!("cargo:rustc-env=LEMMY_GIT_COMMIT={commit}");
+  }
+  // Rosetta only registers the translation files, so changes of the checked out commit need to
+  // trigger the build script explicitly
+  if let Some(git_dir) = git(&["rev-parse", "--absolute-git-dir"]) {
+    println!("cargo:rerun-if-changed={git_dir}/HEAD");
+    println!("cargo:rerun-if-changed={git_dir}/refs");
+    println!("cargo:rerun-if-changed={git_dir}/packed-refs");
+  }
+  println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+  let timestamp = env::var("SOURCE_DATE_EPOCH").ok().or_else(|| {
+    SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .ok()
+      .map(|d| d.as_secs().to_string())
+  });
+  if let Some(timestamp) = timestamp {
+    println!("cargo:rustc-env=LEMMY_BUILD_TIMESTAMP={timestamp}");
+  }
+
   Ok(())
 }
+
+/// Runs git with the given arguments, and returns its trimmed output if it succeeded.
+fn git(args: &[&str]) -> Option<String> {
+  let output = Command::new("git").args(args).output().ok()?;
+  let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+  (output.status.success() && !stdout.is_empty()).then_some(stdout)
+}
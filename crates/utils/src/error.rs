@@ -151,7 +151,8 @@ pub enum LemmyErrorType {
   CouldntGetPosts,
   InvalidUrl,
   EmailSendFailed,
-  Slurs,
+  /// Contains the matched words
+  Slurs(Vec<String>),
   CouldntFindObject,
   RegistrationDenied(Option<String>),
   FederationDisabled,
@@ -176,6 +177,38 @@ pub enum LemmyErrorType {
   InvalidUnixTime,
   InvalidBotAction,
   CantBlockLocalInstance,
+  NsfwNotAllowed,
+  CouldntFindInstance,
+  CouldntFetchNodeInfo,
+  /// Contains the id of the existing post with the same url
+  DuplicatePost(i32),
+  /// Contains the time when the daily quota resets, in RFC 3339 format
+  QuotaExceeded(String),
+  InsufficientKarmaToDownvote,
+  TitleTooLong,
+  BodyTooLong,
+  InvalidContentLengthLimit,
+  InvalidTimeRange,
+  InvalidDateRange,
+  CouldntFindFlair,
+  InvalidFlairName,
+  FlairAlreadyExists,
+  FlairNotInCommunity,
+  CantMergeRemoteCommunity,
+  CantMergeCommunityIntoItself,
+  CantMergeCommunityWithRemoteFollowers,
+  InvalidSearchType,
+  InvalidSortType,
+  ModlogCursorRequiresType,
+  InvalidIconUrl,
+  InvalidBannerUrl,
+  InvalidSortForType,
+  SearchQueryTooShort,
+  SearchQueryTooLong,
+  PurgeNotConfirmed,
+  CouldntFindModlogEntry,
+  /// Thrown when more objects are requested at once than can be fetched
+  TooManyObjects,
   Unknown(String),
 }
 
@@ -232,6 +265,9 @@ cfg_if! {
         if self.error_type == LemmyErrorType::IncorrectLogin {
           return http::StatusCode::UNAUTHORIZED;
         }
+        if self.error_type == LemmyErrorType::RateLimitError {
+          return http::StatusCode::TOO_MANY_REQUESTS;
+        }
         match self.inner.downcast_ref::<diesel::result::Error>() {
           Some(diesel::result::Error::NotFound) => http::StatusCode::NOT_FOUND,
           _ => http::StatusCode::BAD_REQUEST,
@@ -0,0 +1,168 @@
+//! Human readable messages for API error codes, for clients which don't ship their own
+//! translations. The error code itself stays the stable identifier, these messages are only a
+//! convenience and may change at any time.
+//!
+//! The messages are bundled from the `error_messages` directory. English covers every error code,
+//! other languages only the most common ones and fall back to English for the rest.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// Messages by error code, for each language
+type Translations = HashMap<String, HashMap<String, String>>;
+
+/// Pairs of language and JSON file content, which maps error codes to messages
+static BUNDLED_TRANSLATIONS: &[(&str, &str)] = &[
+  ("de", include_str!("../error_messages/de.json")),
+  ("en", include_str!("../error_messages/en.json")),
+  ("es", include_str!("../error_messages/es.json")),
+  ("fr", include_str!("../error_messages/fr.json")),
+  ("pt", include_str!("../error_messages/pt.json")),
+];
+
+static TRANSLATIONS: Lazy<Translations> = Lazy::new(|| {
+  BUNDLED_TRANSLATIONS
+    .iter()
+    .filter_map(|(lang, json)| Some((normalize_language(lang), serde_json::from_str(json).ok()?)))
+    .collect()
+});
+
+/// Returns a message for the given error code (eg `not_an_admin`), in the first language from an
+/// `Accept-Language` header which has a translation. Falls back to English.
+pub fn localized_error_message(code: &str, accept_language: &str) -> String {
+  message_from(&TRANSLATIONS, code, accept_language)
+}
+
+fn message_from(translations: &Translations, code: &str, accept_language: &str) -> String {
+  let message = |lang: &str| translations.get(lang)?.get(code).cloned();
+  preferred_languages(accept_language)
+    .iter()
+    .find_map(|tag| {
+      // Try the full tag first (eg `pt_br`), then only the primary subtag (eg `pt`)
+      let primary = tag.split('_').next().unwrap_or(tag);
+      message(tag).or_else(|| message(primary))
+    })
+    .or_else(|| message("en"))
+    .unwrap_or_else(|| english_message(code))
+}
+
+/// Parses an `Accept-Language` header into normalized language tags, ordered by preference.
+fn preferred_languages(accept_language: &str) -> Vec<String> {
+  let mut languages: Vec<(String, f32)> = accept_language
+    .split(',')
+    .filter_map(|entry| {
+      let mut parts = entry.trim().split(';');
+      let tag = normalize_language(parts.next()?.trim());
+      let quality = parts
+        .find_map(|p| p.trim().strip_prefix("q="))
+        .and_then(|q| q.parse().ok())
+        .unwrap_or(1.0);
+      (!tag.is_empty() && tag != "*").then_some((tag, quality))
+    })
+    .collect();
+  // stable sort, so that entries with equal quality keep the order from the header
+  languages.sort_by(|a, b| b.1.total_cmp(&a.1));
+  languages.into_iter().map(|(l, _)| l).collect()
+}
+
+/// Languages are looked up like `pt_br`, while headers use tags like `pt-BR` or `pt-br`.
+fn normalize_language(tag: &str) -> String {
+  tag.replace('-', "_").to_lowercase()
+}
+
+/// Messages for codes without any translation are derived from the error code, eg
+/// `not_an_admin` becomes `Not an admin`.
+fn english_message(code: &str) -> String {
+  let message = code.replace('_', " ");
+  let mut chars = message.chars();
+  match chars.next() {
+    Some(first) => first.to_uppercase().chain(chars).collect(),
+    None => message,
+  }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+  use super::*;
+  use crate::error::LemmyErrorType;
+  use pretty_assertions::assert_eq;
+  use strum::IntoEnumIterator;
+
+  fn translations() -> Translations {
+    let lang = |lang: &str, messages: &[(&str, &str)]| {
+      let messages = messages
+        .iter()
+        .map(|(code, message)| (code.to_string(), message.to_string()))
+        .collect();
+      (lang.to_string(), messages)
+    };
+    HashMap::from([
+      lang(
+        "en",
+        &[
+          ("not_an_admin", "Not an admin."),
+          ("not_logged_in", "Not logged in."),
+        ],
+      ),
+      lang("de", &[("not_an_admin", "Kein Administrator.")]),
+      lang(
+        "fr",
+        &[
+          ("not_an_admin", "Pas administrateur."),
+          ("not_logged_in", "Non connecté."),
+        ],
+      ),
+      lang("pt", &[("not_an_admin", "Não é administrador.")]),
+      lang("pt_br", &[("not_an_admin", "Não é um administrador.")]),
+    ])
+  }
+
+  #[test]
+  fn test_localized_error_message() {
+    let translations = translations();
+    let message = |code, accept_language| message_from(&translations, code, accept_language);
+
+    assert_eq!("Kein Administrator.", message("not_an_admin", "de-DE"));
+    assert_eq!(
+      "Pas administrateur.",
+      message("not_an_admin", "de;q=0.5, fr-CH, en;q=0.8")
+    );
+    // the full tag is preferred over the primary subtag
+    assert_eq!("Não é um administrador.", message("not_an_admin", "pt-BR"));
+    assert_eq!("Não é administrador.", message("not_an_admin", "pt-PT"));
+    // languages without translation for the code are skipped
+    assert_eq!("Non connecté.", message("not_logged_in", "de, fr"));
+    assert_eq!("Not logged in.", message("not_logged_in", "de"));
+    assert_eq!("Not an admin.", message("not_an_admin", "xx"));
+    assert_eq!("Not an admin.", message("not_an_admin", ""));
+    assert_eq!("Not an admin.", message("not_an_admin", "en-US, de;q=0.5"));
+    assert_eq!("Couldnt update user", message("couldnt_update_user", "de"));
+  }
+
+  #[test]
+  fn test_bundled_translations() {
+    // All files are valid, and English has a message for every error code
+    assert_eq!(BUNDLED_TRANSLATIONS.len(), TRANSLATIONS.len());
+    let english = TRANSLATIONS.get("en").unwrap();
+    for error in LemmyErrorType::iter() {
+      let json = serde_json::to_value(&error).unwrap();
+      let code = json.get("error").and_then(|c| c.as_str()).unwrap();
+      assert!(english.contains_key(code), "no English message for {code}");
+    }
+
+    assert_eq!(
+      "Du bist kein Admin.",
+      localized_error_message("not_an_admin", "de-DE")
+    );
+    assert_eq!(
+      "Você não é administrador.",
+      localized_error_message("not_an_admin", "pt-BR")
+    );
+    // Codes which are only translated to English
+    assert_eq!(
+      "Couldn't update the user.",
+      localized_error_message("couldnt_update_user", "de")
+    );
+  }
+}
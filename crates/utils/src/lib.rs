@@ -5,6 +5,7 @@ cfg_if! {
     pub mod apub;
     pub mod cache_header;
     pub mod email;
+    pub mod error_messages;
     pub mod rate_limit;
     pub mod request;
     pub mod response;
@@ -20,6 +21,10 @@ use std::time::Duration;
 pub type ConnectionId = usize;
 
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Commit hash of the source tree, if it was built from a git checkout.
+pub const GIT_COMMIT: Option<&str> = option_env!("LEMMY_GIT_COMMIT");
+/// Unix timestamp (in seconds) of the build.
+pub const BUILD_TIMESTAMP: Option<&str> = option_env!("LEMMY_BUILD_TIMESTAMP");
 
 pub const REQWEST_TIMEOUT: Duration = Duration::from_secs(10);
 
@@ -1,10 +1,14 @@
 use crate::error::{LemmyError, LemmyErrorType};
-use actix_web::dev::{ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::{
+  dev::{ConnectionInfo, Service, ServiceRequest, ServiceResponse, Transform},
+  http::header::{HeaderMap, HeaderValue, RETRY_AFTER},
+};
 use enum_map::{enum_map, EnumMap};
 use futures::future::{ok, Ready};
 pub use rate_limiter::{ActionType, BucketConfig};
 use rate_limiter::{InstantSecs, RateLimitState};
 use std::{
+  collections::HashSet,
   future::Future,
   net::{IpAddr, Ipv4Addr, SocketAddr},
   pin::Pin,
@@ -17,9 +21,13 @@ use std::{
 
 pub mod rate_limiter;
 
+/// Header in which trusted clients send their key to bypass rate limits.
+const RATE_LIMIT_KEY_HEADER: &str = "X-Rate-Limit-Key";
+
 #[derive(Debug, Clone)]
 pub struct RateLimitChecker {
   state: Arc<Mutex<RateLimitState>>,
+  exempt_keys: Arc<HashSet<String>>,
   action_type: ActionType,
 }
 
@@ -27,6 +35,7 @@ pub struct RateLimitChecker {
 #[derive(Clone)]
 pub struct RateLimitCell {
   state: Arc<Mutex<RateLimitState>>,
+  exempt_keys: Arc<HashSet<String>>,
 }
 
 impl RateLimitCell {
@@ -48,7 +57,16 @@ impl RateLimitCell {
       }
     });
 
-    RateLimitCell { state }
+    RateLimitCell {
+      state,
+      exempt_keys: Default::default(),
+    }
+  }
+
+  /// Requests which send one of these keys are not rate limited.
+  pub fn with_exempt_keys(mut self, keys: Vec<String>) -> Self {
+    self.exempt_keys = Arc::new(keys.into_iter().filter(|k| !k.is_empty()).collect());
+    self
   }
 
   pub fn set_config(&self, config: EnumMap<ActionType, BucketConfig>) {
@@ -87,9 +105,14 @@ impl RateLimitCell {
     self.new_checker(ActionType::ImportUserSettings)
   }
 
+  pub fn export_user_data(&self) -> RateLimitChecker {
+    self.new_checker(ActionType::ExportUserData)
+  }
+
   fn new_checker(&self, action_type: ActionType) -> RateLimitChecker {
     RateLimitChecker {
       state: self.state.clone(),
+      exempt_keys: self.exempt_keys.clone(),
       action_type,
     }
   }
@@ -124,6 +147,10 @@ impl RateLimitCell {
         capacity: 1,
         secs_to_refill: 24 * 60 * 60,
       },
+      ActionType::ExportUserData => BucketConfig {
+        capacity: 1,
+        secs_to_refill: 24 * 60 * 60,
+      },
     })
   }
 }
@@ -135,7 +162,7 @@ pub struct RateLimitedMiddleware<S> {
 
 impl RateLimitChecker {
   /// Returns true if the request passed the rate limit, false if it failed and should be rejected.
-  pub fn check(self, ip_addr: IpAddr) -> bool {
+  pub fn check(&self, ip_addr: IpAddr) -> bool {
     // Does not need to be blocking because the RwLock in settings never held across await points,
     // and the operation here locks only long enough to clone
     let mut state = self
@@ -145,6 +172,23 @@ impl RateLimitChecker {
 
     state.check(self.action_type, ip_addr, InstantSecs::now())
   }
+
+  /// Seconds after which a rejected request may be retried, for the `Retry-After` header.
+  fn retry_after(&self) -> u32 {
+    self
+      .state
+      .lock()
+      .expect("Failed to lock rate limit mutex for reading")
+      .retry_after(self.action_type)
+  }
+
+  /// Returns true if the request comes from a trusted client which bypasses rate limits.
+  fn is_exempt(&self, headers: &HeaderMap) -> bool {
+    headers
+      .get(RATE_LIMIT_KEY_HEADER)
+      .and_then(|h| h.to_str().ok())
+      .is_some_and(|key| self.exempt_keys.contains(key))
+  }
 }
 
 impl<S> Transform<S, ServiceRequest> for RateLimitChecker
@@ -183,19 +227,22 @@ where
 
   fn call(&self, req: ServiceRequest) -> Self::Future {
     let ip_addr = get_ip(&req.connection_info());
+    let exempt = self.checker.is_exempt(req.headers());
 
     let checker = self.checker.clone();
     let service = self.service.clone();
 
     Box::pin(async move {
-      if checker.check(ip_addr) {
+      if exempt || checker.check(ip_addr) {
         service.call(req).await
       } else {
         let (http_req, _) = req.into_parts();
-        Ok(ServiceResponse::from_err(
-          LemmyError::from(LemmyErrorType::RateLimitError),
-          http_req,
-        ))
+        let mut res =
+          ServiceResponse::from_err(LemmyError::from(LemmyErrorType::RateLimitError), http_req);
+        res
+          .headers_mut()
+          .insert(RETRY_AFTER, HeaderValue::from(checker.retry_after()));
+        Ok(res)
       }
     })
   }
@@ -225,6 +272,18 @@ fn parse_ip(addr: &str) -> Option<IpAddr> {
 #[allow(clippy::indexing_slicing)]
 mod tests {
 
+  use super::RateLimitCell;
+  use actix_web::{
+    http::{
+      header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER},
+      StatusCode,
+    },
+    test as actix_test,
+    web,
+    App,
+  };
+  use pretty_assertions::assert_eq;
+
   #[test]
   fn test_parse_ip() {
     let ip_addrs = [
@@ -238,4 +297,56 @@ mod tests {
       assert!(super::parse_ip(addr).is_some(), "failed to parse {addr}");
     }
   }
+
+  #[tokio::test]
+  async fn test_exempt_keys() {
+    let checker = RateLimitCell::with_test_config()
+      .with_exempt_keys(vec!["trusted".to_string(), String::new()])
+      .message();
+
+    let mut headers = HeaderMap::new();
+    assert!(!checker.is_exempt(&headers));
+    headers.insert(
+      HeaderName::from_static("x-rate-limit-key"),
+      HeaderValue::from_static(""),
+    );
+    assert!(!checker.is_exempt(&headers));
+    headers.insert(
+      HeaderName::from_static("x-rate-limit-key"),
+      HeaderValue::from_static("trusted"),
+    );
+    assert!(checker.is_exempt(&headers));
+  }
+
+  #[actix_web::test]
+  async fn test_rate_limited_response() {
+    let rate_limit = RateLimitCell::with_test_config();
+    let app = actix_test::init_service(
+      App::new().service(
+        web::resource("/register")
+          .wrap(rate_limit.register())
+          .route(web::post().to(|| async { "registered" })),
+      ),
+    )
+    .await;
+
+    // The test config allows 3 registrations per hour
+    for _ in 0..3 {
+      let req = actix_test::TestRequest::post()
+        .uri("/register")
+        .to_request();
+      let res = actix_test::call_service(&app, req).await;
+      assert_eq!(StatusCode::OK, res.status());
+    }
+
+    let req = actix_test::TestRequest::post()
+      .uri("/register")
+      .to_request();
+    let res = actix_test::call_service(&app, req).await;
+    assert_eq!(StatusCode::TOO_MANY_REQUESTS, res.status());
+    assert_eq!(
+      Some("1200"),
+      res.headers().get(RETRY_AFTER).and_then(|h| h.to_str().ok())
+    );
+  }
 }
@@ -74,6 +74,7 @@ pub enum ActionType {
   Comment,
   Search,
   ImportUserSettings,
+  ExportUserData,
 }
 
 #[derive(PartialEq, Debug, Clone)]
@@ -295,6 +296,14 @@ impl RateLimitState {
       .remove_full_buckets(now, self.bucket_configs);
   }
 
+  /// Upper bound for the number of seconds until a rate limited action can be done again, which
+  /// is the time it takes to refill a single token.
+  pub fn retry_after(&self, action_type: ActionType) -> u32 {
+    #[allow(clippy::indexing_slicing)] // `EnumMap` has no `get` function
+    let config = self.bucket_configs[action_type];
+    config.secs_to_refill.div_ceil(config.capacity.max(1))
+  }
+
   pub fn set_config(&mut self, new_configs: EnumMap<ActionType, BucketConfig>) {
     self.bucket_configs = new_configs;
   }
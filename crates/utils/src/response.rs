@@ -1,5 +1,14 @@
-use crate::error::{LemmyError, LemmyErrorType};
-use actix_web::{dev::ServiceResponse, middleware::ErrorHandlerResponse, HttpResponse};
+use crate::{
+  error::{LemmyError, LemmyErrorType},
+  error_messages::localized_error_message,
+};
+use actix_web::{
+  dev::ServiceResponse,
+  http::header::{ACCEPT_LANGUAGE, CONTENT_LENGTH, CONTENT_TYPE},
+  middleware::ErrorHandlerResponse,
+  HttpResponse,
+  HttpResponseBuilder,
+};
 
 pub fn jsonify_plain_text_errors<BODY>(
   res: ServiceResponse<BODY>,
@@ -10,20 +19,45 @@ pub fn jsonify_plain_text_errors<BODY>(
   if maybe_error.is_none() {
     return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
   }
-  // We're assuming that any LemmyError is already in JSON format, so we don't need to do anything
-  if maybe_error
+  // We're assuming that any LemmyError is already in JSON format, so we only need to add a
+  // localized message if the client asked for one
+  if let Some(lemmy_error) = maybe_error
     .expect("http responses with 400-599 statuses should have an error object")
     .as_error::<LemmyError>()
-    .is_some()
   {
-    return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    let accept_language = res
+      .request()
+      .headers()
+      .get(ACCEPT_LANGUAGE)
+      .and_then(|h| h.to_str().ok())
+      .map(ToString::to_string);
+    let Some(accept_language) = accept_language else {
+      return Ok(ErrorHandlerResponse::Response(res.map_into_left_body()));
+    };
+    let mut json = serde_json::to_value(&lemmy_error.error_type)?;
+    if let Some(obj) = json.as_object_mut() {
+      let code = obj
+        .get("error")
+        .and_then(|c| c.as_str())
+        .unwrap_or_default()
+        .to_string();
+      obj.insert(
+        "localized_message".to_string(),
+        localized_error_message(&code, &accept_language).into(),
+      );
+    }
+    let (req, res) = res.into_parts();
+    let response = rebuild_response(&res).json(json);
+    return Ok(ErrorHandlerResponse::Response(
+      ServiceResponse::new(req, response).map_into_right_body(),
+    ));
   }
 
   let (req, res) = res.into_parts();
   let error = res
     .error()
     .expect("expected an error object in the response");
-  let response = HttpResponse::build(res.status()).json(LemmyErrorType::Unknown(error.to_string()));
+  let response = rebuild_response(&res).json(LemmyErrorType::Unknown(error.to_string()));
 
   let service_response = ServiceResponse::new(req, response);
   Ok(ErrorHandlerResponse::Response(
@@ -31,12 +65,24 @@ pub fn jsonify_plain_text_errors<BODY>(
   ))
 }
 
+/// Starts a response with the status and headers of the original one, eg to keep the Retry-After
+/// header of rate limited requests. Only the headers describing the old body are left out.
+fn rebuild_response<B>(res: &HttpResponse<B>) -> HttpResponseBuilder {
+  let mut response = HttpResponse::build(res.status());
+  for (name, value) in res.headers() {
+    if name != CONTENT_TYPE && name != CONTENT_LENGTH {
+      response.append_header((name.clone(), value.clone()));
+    }
+  }
+  response
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
   use crate::error::{LemmyError, LemmyErrorType};
   use actix_web::{
-    error::ErrorInternalServerError,
+    error::{ErrorInternalServerError, InternalError},
     middleware::ErrorHandlers,
     test,
     web,
@@ -99,6 +145,67 @@ mod tests {
     .await;
   }
 
+  #[actix_web::test]
+  async fn test_lemmy_errors_are_localized() {
+    async fn lemmy_error_service() -> actix_web::Result<String, LemmyError> {
+      Err(LemmyError::from(LemmyErrorType::NotAnAdmin))
+    }
+
+    let app = test::init_service(
+      App::new()
+        .wrap(ErrorHandlers::new().default_handler(jsonify_plain_text_errors))
+        .route("/", web::get().to(lemmy_error_service)),
+    )
+    .await;
+    let req = test::TestRequest::default()
+      .insert_header((ACCEPT_LANGUAGE, "de-DE,de;q=0.9"))
+      .to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body = test::read_body(res).await;
+    let expected = serde_json::json!({
+      "error": "not_an_admin",
+      "localized_message": "Du bist kein Admin.",
+    });
+    assert_eq!(
+      expected,
+      serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+    );
+  }
+
+  #[actix_web::test]
+  async fn test_headers_are_kept() {
+    async fn error_with_header_service() -> actix_web::Result<String, Error> {
+      let response = HttpResponse::InternalServerError()
+        .insert_header(("x-custom", "kept"))
+        .finish();
+      Err(InternalError::from_response("This is not a LemmyError", response).into())
+    }
+
+    let app = test::init_service(
+      App::new()
+        .wrap(ErrorHandlers::new().default_handler(jsonify_plain_text_errors))
+        .route("/", web::get().to(error_with_header_service)),
+    )
+    .await;
+    let req = test::TestRequest::default().to_request();
+    let res = test::call_service(&app, req).await;
+
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(
+      Some("kept"),
+      res.headers().get("x-custom").and_then(|h| h.to_str().ok())
+    );
+    assert_eq!(
+      Some("application/json"),
+      res
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+    );
+  }
+
   async fn check_for_jsonification(
     service: impl Handler<(), Output = impl Responder + 'static>,
     expected_status_code: StatusCode,
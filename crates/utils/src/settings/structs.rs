@@ -52,6 +52,14 @@ pub struct Settings {
   #[default(None)]
   #[doku(example = "Some(Default::default())")]
   pub prometheus: Option<PrometheusConfig>,
+  /// Keys for trusted clients like official apps or archival bots, which are exempt from rate
+  /// limits. Clients send the key in the `X-Rate-Limit-Key` header.
+  #[default(Vec::new())]
+  pub rate_limit_exempt_keys: Vec<String>,
+  /// Number of seconds for which the site data returned by GetSite is cached. Editing the site
+  /// clears the cache immediately.
+  #[default(1)]
+  pub site_cache_duration: u64,
   /// Sets a response Access-Control-Allow-Origin CORS header
   /// https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Access-Control-Allow-Origin
   #[default(None)]
@@ -92,6 +100,22 @@ pub struct PictrsConfig {
   /// Timeout for uploading images to pictrs (in seconds)
   #[default(30)]
   pub upload_timeout: u64,
+
+  /// Maximum width and height (in pixels) of thumbnails which are generated for post urls and
+  /// image posts. By default the original size is kept.
+  #[default(0)]
+  pub thumbnail_size: u32,
+
+  /// Image format for generated thumbnails, eg "webp", "jpg" or "png". By default the original
+  /// format is kept.
+  #[default(None)]
+  #[doku(example = "webp")]
+  pub thumbnail_format: Option<String>,
+
+  /// Maximum size of remote images (in bytes) which are fetched to generate a thumbnail. Larger
+  /// images are not stored, and the original url is used instead.
+  #[default(10_000_000)]
+  pub thumbnail_max_fetch_size: u64,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, SmartDefault, Document, PartialEq)]
@@ -31,6 +31,23 @@ pub fn sanitize_html(text: &str) -> String {
     .replace('\'', "&#x27;")
 }
 
+/// Removes any characters disallowed by the XML grammar.
+/// See https://www.w3.org/TR/xml/#NT-Char for details.
+pub fn sanitize_xml(input: String) -> String {
+  input
+    .chars()
+    .filter(|&c| {
+      matches!(c,
+        '\u{09}'
+        | '\u{0A}'
+        | '\u{0D}'
+        | '\u{20}'..='\u{D7FF}'
+        | '\u{E000}'..='\u{FFFD}'
+        | '\u{10000}'..='\u{10FFFF}')
+    })
+    .collect()
+}
+
 pub fn markdown_to_html(text: &str) -> String {
   MARKDOWN_PARSER.parse(text).xrender()
 }
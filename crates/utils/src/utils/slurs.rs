@@ -30,8 +30,9 @@ pub(crate) fn slur_check<'a>(
   }
 }
 
+/// An empty regex disables the slur filter.
 pub fn build_slur_regex(regex_str: Option<&str>) -> Option<Regex> {
-  regex_str.map(|slurs| {
+  regex_str.filter(|slurs| !slurs.is_empty()).map(|slurs| {
     RegexBuilder::new(slurs)
       .case_insensitive(true)
       .build()
@@ -41,7 +42,9 @@ pub fn build_slur_regex(regex_str: Option<&str>) -> Option<Regex> {
 
 pub fn check_slurs(text: &str, slur_regex: &Option<Regex>) -> LemmyResult<()> {
   if let Err(slurs) = slur_check(text, slur_regex) {
-    Err(anyhow::anyhow!("{}", slurs_vec_to_str(&slurs))).with_lemmy_type(LemmyErrorType::Slurs)
+    let matches = slurs.iter().map(ToString::to_string).collect();
+    Err(anyhow::anyhow!("{}", slurs_vec_to_str(&slurs)))
+      .with_lemmy_type(LemmyErrorType::Slurs(matches))
   } else {
     Ok(())
   }
@@ -65,7 +68,10 @@ pub(crate) fn slurs_vec_to_str(slurs: &[&str]) -> String {
 #[allow(clippy::indexing_slicing)]
 mod test {
 
-  use crate::utils::slurs::{remove_slurs, slur_check, slurs_vec_to_str};
+  use crate::{
+    error::LemmyErrorType,
+    utils::slurs::{build_slur_regex, check_slurs, remove_slurs, slur_check, slurs_vec_to_str},
+  };
   use pretty_assertions::assert_eq;
   use regex::RegexBuilder;
 
@@ -98,6 +104,23 @@ mod test {
     }
   }
 
+  #[test]
+  fn test_check_slurs() {
+    let slur_regex = build_slur_regex(Some("(foo|bar)"));
+    assert!(check_slurs("baz", &slur_regex).is_ok());
+    assert_eq!(
+      check_slurs("bar and foo and bar", &slur_regex)
+        .unwrap_err()
+        .error_type,
+      LemmyErrorType::Slurs(vec!["bar".to_string(), "foo".to_string()])
+    );
+
+    // An empty filter is disabled
+    let empty_regex = build_slur_regex(Some(""));
+    assert!(empty_regex.is_none());
+    assert!(check_slurs("foo", &empty_regex).is_ok());
+  }
+
   // These helped with testing
   // #[test]
   // fn test_send_email() {
@@ -16,10 +16,14 @@ static CLEAN_URL_PARAMS_REGEX: Lazy<Regex> = Lazy::new(|| {
 });
 const ALLOWED_POST_URL_SCHEMES: [&str; 3] = ["http", "https", "magnet"];
 
+const POST_TITLE_MIN_LENGTH: usize = 3;
+const POST_TITLE_MAX_LENGTH: usize = 200;
+const CONTENT_MAX_LENGTH_LIMIT: usize = 100_000;
 const BODY_MAX_LENGTH: usize = 10000;
 const POST_BODY_MAX_LENGTH: usize = 50000;
 const BIO_MAX_LENGTH: usize = 300;
 const ALT_TEXT_MAX_LENGTH: usize = 300;
+const FLAIR_NAME_MAX_LENGTH: usize = 50;
 const SITE_NAME_MAX_LENGTH: usize = 20;
 const SITE_NAME_MIN_LENGTH: usize = 1;
 const SITE_DESCRIPTION_MAX_LENGTH: usize = 150;
@@ -147,9 +151,13 @@ pub fn is_valid_matrix_id(matrix_id: &str) -> LemmyResult<()> {
   }
 }
 
-pub fn is_valid_post_title(title: &str) -> LemmyResult<()> {
+/// The maximum length can't be higher than 200, which is the size of the db column.
+pub fn is_valid_post_title(title: &str, post_title_max_length: usize) -> LemmyResult<()> {
   let length = title.trim().chars().count();
-  let check = (3..=200).contains(&length) && !has_newline(title);
+  if length > post_title_max_length.min(POST_TITLE_MAX_LENGTH) {
+    Err(LemmyErrorType::TitleTooLong)?
+  }
+  let check = length >= POST_TITLE_MIN_LENGTH && !has_newline(title);
   if !check {
     Err(LemmyErrorType::InvalidPostTitle.into())
   } else {
@@ -169,6 +177,51 @@ pub fn is_valid_body_field(body: &Option<String>, post: bool) -> LemmyResult<()>
   Ok(())
 }
 
+/// Checks the body of a post or comment against the maximum length configured for the site.
+pub fn is_valid_content_length(body: &Option<String>, max_length: usize) -> LemmyResult<()> {
+  if let Some(body) = body {
+    max_length_check(body, max_length, LemmyErrorType::BodyTooLong)?;
+  }
+  Ok(())
+}
+
+/// Checks the content length limits an admin sets for the site. Post titles can't be shorter than
+/// the minimum title length or longer than the db column, and bodies need a sane upper bound.
+pub fn content_length_limits_check(
+  post_title_max_length: Option<i32>,
+  post_body_max_length: Option<i32>,
+  comment_max_length: Option<i32>,
+) -> LemmyResult<()> {
+  let in_range = |max_length: Option<i32>, min: usize, max: usize| {
+    max_length.map_or(true, |l| {
+      usize::try_from(l).is_ok_and(|l| (min..=max).contains(&l))
+    })
+  };
+  if !in_range(
+    post_title_max_length,
+    POST_TITLE_MIN_LENGTH,
+    POST_TITLE_MAX_LENGTH,
+  ) || !in_range(post_body_max_length, 1, CONTENT_MAX_LENGTH_LIMIT)
+    || !in_range(comment_max_length, 1, CONTENT_MAX_LENGTH_LIMIT)
+  {
+    Err(LemmyErrorType::InvalidContentLengthLimit)?
+  }
+  Ok(())
+}
+
+/// Flair names are shown next to post titles, so they need to be short and on a single line.
+pub fn is_valid_flair_name(name: &str) -> LemmyResult<()> {
+  let name = name.trim();
+  if name.is_empty() || has_newline(name) {
+    Err(LemmyErrorType::InvalidFlairName)?
+  }
+  max_length_check(
+    name,
+    FLAIR_NAME_MAX_LENGTH,
+    LemmyErrorType::InvalidFlairName,
+  )
+}
+
 pub fn is_valid_bio_field(bio: &str) -> LemmyResult<()> {
   max_length_check(bio, BIO_MAX_LENGTH, LemmyErrorType::BioLengthOverflow)
 }
@@ -299,6 +352,21 @@ pub fn check_url_scheme(url: &Option<Url>) -> LemmyResult<()> {
   }
 }
 
+/// Checks that a site icon or banner is a valid http(s) url. An empty string clears the image, so
+/// it is also allowed.
+pub fn is_valid_site_image_url(
+  url: &Option<String>,
+  error_type: LemmyErrorType,
+) -> LemmyResult<()> {
+  match url.as_deref() {
+    None | Some("") => Ok(()),
+    Some(url) => match Url::parse(url) {
+      Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Ok(()),
+      _ => Err(error_type.into()),
+    },
+  }
+}
+
 pub fn is_url_blocked(url: &Option<Url>, blocklist: &RegexSet) -> LemmyResult<()> {
   if let Some(url) = url {
     if blocklist.is_match(url.as_str()) {
@@ -339,12 +407,16 @@ mod tests {
       check_url_scheme,
       check_urls_are_valid,
       clean_url_params,
+      content_length_limits_check,
       is_url_blocked,
       is_valid_actor_name,
       is_valid_bio_field,
+      is_valid_content_length,
       is_valid_display_name,
+      is_valid_flair_name,
       is_valid_matrix_id,
       is_valid_post_title,
+      is_valid_site_image_url,
       site_description_length_check,
       site_name_length_check,
       BIO_MAX_LENGTH,
@@ -369,15 +441,15 @@ mod tests {
 
   #[test]
   fn regex_checks() {
-    assert!(is_valid_post_title("hi").is_err());
-    assert!(is_valid_post_title("him").is_ok());
-    assert!(is_valid_post_title("  him  ").is_ok());
-    assert!(is_valid_post_title("n\n\n\n\nanother").is_err());
-    assert!(is_valid_post_title("hello there!\n this is a test.").is_err());
-    assert!(is_valid_post_title("hello there! this is a test.").is_ok());
-    assert!(is_valid_post_title(("12345".repeat(40) + "x").as_str()).is_err());
-    assert!(is_valid_post_title("12345".repeat(40).as_str()).is_ok());
-    assert!(is_valid_post_title((("12345".repeat(40)) + "  ").as_str()).is_ok());
+    assert!(is_valid_post_title("hi", 200).is_err());
+    assert!(is_valid_post_title("him", 200).is_ok());
+    assert!(is_valid_post_title("  him  ", 200).is_ok());
+    assert!(is_valid_post_title("n\n\n\n\nanother", 200).is_err());
+    assert!(is_valid_post_title("hello there!\n this is a test.", 200).is_err());
+    assert!(is_valid_post_title("hello there! this is a test.", 200).is_ok());
+    assert!(is_valid_post_title(("12345".repeat(40) + "x").as_str(), 200).is_err());
+    assert!(is_valid_post_title("12345".repeat(40).as_str(), 200).is_ok());
+    assert!(is_valid_post_title((("12345".repeat(40)) + "  ").as_str(), 200).is_ok());
   }
 
   #[test]
@@ -421,13 +493,51 @@ mod tests {
 
   #[test]
   fn test_valid_post_title() {
-    assert!(is_valid_post_title("Post Title").is_ok());
+    assert!(is_valid_post_title("Post Title", 200).is_ok());
     assert!(is_valid_post_title(
-      "აშშ ითხოვს ირანს დაუყოვნებლივ გაანთავისუფლოს დაკავებული ნავთობის ტანკერი"
+      "აშშ ითხოვს ირანს დაუყოვნებლივ გაანთავისუფლოს დაკავებული ნავთობის ტანკერი",
+      200
     )
     .is_ok());
-    assert!(is_valid_post_title("   POST TITLE 😃😃😃😃😃").is_ok());
-    assert!(is_valid_post_title("\n \n \n \n    		").is_err()); // tabs/spaces/newlines
+    assert!(is_valid_post_title("   POST TITLE 😃😃😃😃😃", 200).is_ok());
+    assert!(is_valid_post_title("\n \n \n \n    		", 200).is_err()); // tabs/spaces/newlines
+  }
+
+  #[test]
+  fn test_content_length_limits() {
+    assert!(is_valid_post_title("Post Title", 5)
+      .is_err_and(|e| e.error_type == LemmyErrorType::TitleTooLong));
+    // The configured limit can't exceed the db column size
+    assert!(is_valid_post_title(&"x".repeat(201), 500).is_err());
+    assert!(is_valid_content_length(&Some("body".to_string()), 4).is_ok());
+    assert!(is_valid_content_length(&Some("body!".to_string()), 4)
+      .is_err_and(|e| e.error_type == LemmyErrorType::BodyTooLong));
+    assert!(is_valid_content_length(&None, 0).is_ok());
+  }
+
+  #[test]
+  fn test_content_length_limits_check() {
+    assert!(content_length_limits_check(None, None, None).is_ok());
+    assert!(content_length_limits_check(Some(200), Some(50_000), Some(10_000)).is_ok());
+    assert!(content_length_limits_check(Some(3), Some(1), Some(1)).is_ok());
+    for (title, body, comment) in [
+      (Some(2), None, None),
+      (Some(201), None, None),
+      (None, Some(0), None),
+      (None, Some(-1), None),
+      (None, None, Some(100_001)),
+    ] {
+      assert!(content_length_limits_check(title, body, comment)
+        .is_err_and(|e| e.error_type == LemmyErrorType::InvalidContentLengthLimit));
+    }
+  }
+
+  #[test]
+  fn test_valid_flair_name() {
+    assert!(is_valid_flair_name("Discussion").is_ok());
+    assert!(is_valid_flair_name("  ").is_err());
+    assert!(is_valid_flair_name("two\nlines").is_err());
+    assert!(is_valid_flair_name(&"x".repeat(51)).is_err());
   }
 
   #[test]
@@ -580,6 +690,18 @@ mod tests {
     assert!(check_url_scheme(&Some(Url::parse(magnet_link).unwrap())).is_ok());
   }
 
+  #[test]
+  fn test_is_valid_site_image_url() {
+    let error = LemmyErrorType::InvalidIconUrl;
+    assert!(is_valid_site_image_url(&None, error.clone()).is_ok());
+    assert!(is_valid_site_image_url(&Some(String::new()), error.clone()).is_ok());
+    assert!(
+      is_valid_site_image_url(&Some("https://example.com/icon.png".into()), error.clone()).is_ok()
+    );
+    assert!(is_valid_site_image_url(&Some("example.com/icon.png".into()), error.clone()).is_err());
+    assert!(is_valid_site_image_url(&Some("ftp://example.com/icon.png".into()), error).is_err());
+  }
+
   #[test]
   fn test_url_block() {
     let set = regex::RegexSet::new(vec![
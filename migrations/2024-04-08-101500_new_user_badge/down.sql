@@ -0,0 +1,5 @@
+DROP FUNCTION person_is_new;
+
+ALTER TABLE local_site
+    DROP COLUMN new_user_badge_days;
+
@@ -0,0 +1,18 @@
+-- Accounts younger than this many days are flagged as new in post and comment views.
+-- A value of 0 disables the flag.
+ALTER TABLE local_site
+    ADD COLUMN new_user_badge_days integer DEFAULT 0 NOT NULL;
+
+CREATE FUNCTION person_is_new (published timestamp with time zone)
+    RETURNS boolean
+    AS $$
+    SELECT
+        coalesce((
+            SELECT
+                published > now() - make_interval(days => new_user_badge_days)
+            FROM local_site
+            LIMIT 1), FALSE)
+$$
+LANGUAGE sql
+STABLE PARALLEL SAFE;
+
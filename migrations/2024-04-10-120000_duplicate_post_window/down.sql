@@ -0,0 +1,3 @@
+ALTER TABLE local_site
+    DROP COLUMN duplicate_post_window_hours;
+
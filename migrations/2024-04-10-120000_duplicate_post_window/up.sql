@@ -0,0 +1,5 @@
+-- Posts with the same url in the same community are rejected within this many hours.
+-- A value of 0 disables the check.
+ALTER TABLE local_site
+    ADD COLUMN duplicate_post_window_hours integer DEFAULT 0 NOT NULL;
+
@@ -0,0 +1,8 @@
+DELETE FROM mod_lock_post
+WHERE mod_person_id IS NULL;
+
+ALTER TABLE mod_lock_post
+    ALTER COLUMN mod_person_id SET NOT NULL;
+
+ALTER TABLE local_site
+    DROP COLUMN auto_lock_posts_after_days;
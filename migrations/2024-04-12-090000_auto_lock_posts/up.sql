@@ -0,0 +1,9 @@
+-- Posts in local communities are locked automatically after this many days.
+-- A value of 0 disables it.
+ALTER TABLE local_site
+    ADD COLUMN auto_lock_posts_after_days integer DEFAULT 0 NOT NULL;
+
+
+-- Automatic locks aren't done by a moderator
+ALTER TABLE mod_lock_post
+    ALTER COLUMN mod_person_id DROP NOT NULL;
@@ -0,0 +1,6 @@
+ALTER TABLE local_site
+    DROP COLUMN daily_post_limit,
+    DROP COLUMN daily_comment_limit,
+    DROP COLUMN trusted_account_days,
+    DROP COLUMN trusted_account_min_score;
+
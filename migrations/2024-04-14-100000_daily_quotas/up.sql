@@ -0,0 +1,8 @@
+-- Maximum number of posts and comments a user can create within 24 hours. A value of 0 disables
+-- the limit.
+ALTER TABLE local_site
+    ADD COLUMN daily_post_limit integer DEFAULT 0 NOT NULL,
+    ADD COLUMN daily_comment_limit integer DEFAULT 0 NOT NULL,
+    ADD COLUMN trusted_account_days integer DEFAULT 0 NOT NULL,
+    ADD COLUMN trusted_account_min_score integer DEFAULT 0 NOT NULL;
+
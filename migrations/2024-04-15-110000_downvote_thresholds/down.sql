@@ -0,0 +1,4 @@
+ALTER TABLE local_site
+    DROP COLUMN downvote_min_score,
+    DROP COLUMN downvote_min_account_days;
+
@@ -0,0 +1,5 @@
+-- Users need at least this score and account age to downvote. A value of 0 disables the check.
+ALTER TABLE local_site
+    ADD COLUMN downvote_min_score integer DEFAULT 0 NOT NULL,
+    ADD COLUMN downvote_min_account_days integer DEFAULT 0 NOT NULL;
+
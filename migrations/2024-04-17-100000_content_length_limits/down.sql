@@ -0,0 +1,5 @@
+ALTER TABLE local_site
+    DROP COLUMN post_title_max_length,
+    DROP COLUMN post_body_max_length,
+    DROP COLUMN comment_max_length;
+
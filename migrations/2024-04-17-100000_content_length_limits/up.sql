@@ -0,0 +1,7 @@
+-- Maximum lengths for post titles, post bodies and comments. Post titles can't be longer than the
+-- post.name column.
+ALTER TABLE local_site
+    ADD COLUMN post_title_max_length integer DEFAULT 200 NOT NULL,
+    ADD COLUMN post_body_max_length integer DEFAULT 50000 NOT NULL,
+    ADD COLUMN comment_max_length integer DEFAULT 10000 NOT NULL;
+
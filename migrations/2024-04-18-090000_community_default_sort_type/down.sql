@@ -0,0 +1,3 @@
+ALTER TABLE community
+    DROP COLUMN default_sort_type;
+
@@ -0,0 +1,3 @@
+ALTER TABLE community
+    ADD COLUMN default_sort_type sort_type_enum;
+
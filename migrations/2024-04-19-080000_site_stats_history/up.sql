@@ -0,0 +1,8 @@
+-- Number of new local users, posts and comments per day, filled by a scheduled task.
+CREATE TABLE site_stats_history (
+    day timestamptz PRIMARY KEY,
+    users bigint NOT NULL DEFAULT 0,
+    posts bigint NOT NULL DEFAULT 0,
+    comments bigint NOT NULL DEFAULT 0
+);
+
@@ -0,0 +1,5 @@
+ALTER TABLE post
+    DROP COLUMN flair_id;
+
+DROP TABLE community_flair;
+
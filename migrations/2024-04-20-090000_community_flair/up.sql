@@ -0,0 +1,13 @@
+-- Flairs which mods define for their community, and which can be assigned to posts.
+CREATE TABLE community_flair (
+    id serial PRIMARY KEY,
+    community_id int REFERENCES community ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    name varchar(50) NOT NULL,
+    published timestamptz NOT NULL DEFAULT now(),
+    updated timestamptz,
+    UNIQUE (community_id, name)
+);
+
+ALTER TABLE post
+    ADD COLUMN flair_id int REFERENCES community_flair ON UPDATE CASCADE ON DELETE SET NULL;
+
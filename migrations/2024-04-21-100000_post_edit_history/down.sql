@@ -0,0 +1,5 @@
+DROP TRIGGER post_edit_history ON post;
+
+DROP FUNCTION post_edit_history_insert ();
+
+DROP TABLE post_edit_history;
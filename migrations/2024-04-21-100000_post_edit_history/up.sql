@@ -0,0 +1,29 @@
+-- Previous titles and bodies of edited posts, so that search can find posts by their old content.
+CREATE TABLE post_edit_history (
+    id serial PRIMARY KEY,
+    post_id int REFERENCES post ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    name varchar(200) NOT NULL,
+    body text,
+    published timestamptz NOT NULL DEFAULT now()
+);
+
+CREATE INDEX idx_post_edit_history_post ON post_edit_history (post_id);
+
+CREATE FUNCTION post_edit_history_insert ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    INSERT INTO post_edit_history (post_id, name, body)
+        VALUES (OLD.id, OLD.name, OLD.body);
+    RETURN NULL;
+END
+$$;
+
+CREATE TRIGGER post_edit_history
+    AFTER UPDATE OF name, body ON post
+    FOR EACH ROW
+    WHEN ((OLD.name IS DISTINCT FROM NEW.name OR OLD.body IS DISTINCT FROM NEW.body)
+    -- Permanently deleted content must not be kept in the history
+    AND NEW.name <> '*Permanently Deleted*')
+    EXECUTE PROCEDURE post_edit_history_insert ();
@@ -0,0 +1,8 @@
+DROP FUNCTION hidden_scores_sort (bigint, timestamp with time zone, integer);
+
+DROP FUNCTION hidden_scores_sort (double precision, timestamp with time zone, integer);
+
+DROP FUNCTION scores_hidden;
+
+ALTER TABLE community
+    DROP COLUMN hide_scores_until_hours;
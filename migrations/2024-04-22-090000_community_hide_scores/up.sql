@@ -0,0 +1,53 @@
+-- Scores of posts and comments younger than this many hours are only shown to mods.
+ALTER TABLE community
+    ADD COLUMN hide_scores_until_hours integer;
+
+-- Whether a post or comment published at the given time is young enough for its community to
+-- hide the score.
+CREATE FUNCTION scores_hidden (published timestamp with time zone, hide_scores_until_hours integer)
+    RETURNS boolean
+    AS $$
+    SELECT
+        coalesce(published > now() - make_interval(hours => hide_scores_until_hours), FALSE)
+$$
+LANGUAGE sql
+STABLE PARALLEL SAFE;
+
+-- Sort key which replaces the score based value of posts and comments with hidden scores by the
+-- largest possible one. Ties are then ordered by the following sort keys, which puts all of them
+-- first in order of their publish time.
+CREATE FUNCTION hidden_scores_sort (value bigint, published timestamp with time zone, community_id integer)
+    RETURNS bigint
+    AS $$
+    SELECT
+        CASE WHEN scores_hidden (published, (
+                SELECT
+                    hide_scores_until_hours
+                FROM community
+                WHERE
+                    id = community_id)) THEN
+            9223372036854775807
+        ELSE
+            value
+        END
+$$
+LANGUAGE sql
+STABLE PARALLEL SAFE;
+
+CREATE FUNCTION hidden_scores_sort (value double precision, published timestamp with time zone, community_id integer)
+    RETURNS double precision
+    AS $$
+    SELECT
+        CASE WHEN scores_hidden (published, (
+                SELECT
+                    hide_scores_until_hours
+                FROM community
+                WHERE
+                    id = community_id)) THEN
+            'Infinity'
+        ELSE
+            value
+        END
+$$
+LANGUAGE sql
+STABLE PARALLEL SAFE;
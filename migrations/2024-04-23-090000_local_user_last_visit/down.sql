@@ -0,0 +1,4 @@
+ALTER TABLE local_user
+    DROP COLUMN last_visit,
+    DROP COLUMN previous_visit;
+
@@ -0,0 +1,6 @@
+-- Time of the user's most recent visit, and the start of the visit before it. Used to show
+-- content which is new since the last visit.
+ALTER TABLE local_user
+    ADD COLUMN last_visit timestamptz,
+    ADD COLUMN previous_visit timestamptz;
+
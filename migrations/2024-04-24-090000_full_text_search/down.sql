@@ -0,0 +1,78 @@
+DROP INDEX idx_post_fts, idx_comment_fts;
+
+-- Relevance is only useful for searches, so it can't be a stored default
+UPDATE
+    local_user
+SET
+    default_sort_type = 'Active'
+WHERE
+    default_sort_type = 'Relevance';
+
+UPDATE
+    local_site
+SET
+    default_sort_type = 'Active'
+WHERE
+    default_sort_type = 'Relevance';
+
+UPDATE
+    community
+SET
+    default_sort_type = NULL
+WHERE
+    default_sort_type = 'Relevance';
+
+-- rename the old enum
+ALTER TYPE sort_type_enum RENAME TO sort_type_enum__;
+
+-- create the new enum
+CREATE TYPE sort_type_enum AS ENUM (
+    'Active',
+    'Hot',
+    'New',
+    'Old',
+    'TopDay',
+    'TopWeek',
+    'TopMonth',
+    'TopYear',
+    'TopAll',
+    'MostComments',
+    'NewComments',
+    'TopHour',
+    'TopSixHour',
+    'TopTwelveHour',
+    'TopThreeMonths',
+    'TopSixMonths',
+    'TopNineMonths',
+    'Controversial',
+    'Scaled'
+);
+
+-- alter all your enum columns
+ALTER TABLE local_user
+    ALTER default_sort_type DROP DEFAULT;
+
+ALTER TABLE local_user
+    ALTER COLUMN default_sort_type TYPE sort_type_enum
+    USING default_sort_type::text::sort_type_enum;
+
+ALTER TABLE local_user
+    ALTER default_sort_type SET DEFAULT 'Active';
+
+ALTER TABLE local_site
+    ALTER default_sort_type DROP DEFAULT;
+
+ALTER TABLE local_site
+    ALTER COLUMN default_sort_type TYPE sort_type_enum
+    USING default_sort_type::text::sort_type_enum;
+
+ALTER TABLE local_site
+    ALTER default_sort_type SET DEFAULT 'Active';
+
+ALTER TABLE community
+    ALTER COLUMN default_sort_type TYPE sort_type_enum
+    USING default_sort_type::text::sort_type_enum;
+
+-- drop the old enum
+DROP TYPE sort_type_enum__;
+
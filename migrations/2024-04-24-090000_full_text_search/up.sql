@@ -0,0 +1,9 @@
+ALTER TYPE sort_type_enum
+    ADD VALUE 'Relevance';
+
+-- The indexed expressions must be identical to the ones used in queries, see POST_FTS_DOCUMENT
+-- and COMMENT_FTS_DOCUMENT.
+CREATE INDEX idx_post_fts ON post USING gin (to_tsvector('english', name || ' ' || coalesce(body, '')));
+
+CREATE INDEX idx_comment_fts ON comment USING gin (to_tsvector('english', content));
+
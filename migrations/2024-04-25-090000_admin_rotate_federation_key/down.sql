@@ -0,0 +1,2 @@
+DROP TABLE admin_rotate_federation_key;
+
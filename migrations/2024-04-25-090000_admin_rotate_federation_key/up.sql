@@ -0,0 +1,6 @@
+CREATE TABLE admin_rotate_federation_key (
+    id serial PRIMARY KEY,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    when_ timestamptz NOT NULL DEFAULT now()
+);
+
@@ -0,0 +1,2 @@
+DROP TABLE admin_export_user_data;
+
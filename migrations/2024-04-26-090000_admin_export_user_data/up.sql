@@ -0,0 +1,7 @@
+CREATE TABLE admin_export_user_data (
+    id serial PRIMARY KEY,
+    admin_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    other_person_id int REFERENCES person ON UPDATE CASCADE ON DELETE CASCADE NOT NULL,
+    when_ timestamptz NOT NULL DEFAULT now()
+);
+
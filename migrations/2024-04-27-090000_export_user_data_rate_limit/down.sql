@@ -0,0 +1,6 @@
+ALTER TABLE local_site_rate_limit
+    DROP COLUMN export_user_data;
+
+ALTER TABLE local_site_rate_limit
+    DROP COLUMN export_user_data_per_second;
+
@@ -0,0 +1,30 @@
+DROP INDEX idx_post_aggregates_featured_local_score, idx_post_aggregates_community_score, idx_post_aggregates_featured_community_score, idx_post_aggregates_featured_local_controversy, idx_post_aggregates_community_controversy, idx_post_aggregates_featured_community_controversy;
+
+CREATE INDEX idx_post_aggregates_featured_local_score ON post_aggregates (featured_local DESC, score DESC, published DESC);
+
+CREATE INDEX idx_post_aggregates_community_score ON post_aggregates (community_id, featured_local DESC, score DESC, published DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_score ON post_aggregates (community_id, featured_community DESC, score DESC, published DESC);
+
+CREATE INDEX idx_post_aggregates_featured_local_controversy ON post_aggregates (featured_local DESC, controversy_rank DESC);
+
+CREATE INDEX idx_post_aggregates_community_controversy ON post_aggregates (community_id, featured_local DESC, controversy_rank DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_controversy ON post_aggregates (community_id, featured_community DESC, controversy_rank DESC);
+
+DROP FUNCTION hidden_scores_sort (bigint, boolean);
+
+DROP FUNCTION hidden_scores_sort (double precision, boolean);
+
+DROP FUNCTION visible_score;
+
+DROP TRIGGER hide_scores_until_hours ON community;
+
+DROP FUNCTION community_hide_scores_until_hours;
+
+DROP TRIGGER scores_hidden ON post_aggregates;
+
+DROP FUNCTION post_aggregates_scores_hidden;
+
+ALTER TABLE post_aggregates
+    DROP COLUMN scores_hidden;
@@ -0,0 +1,117 @@
+-- Whether the score of the post is currently hidden by its community. Stored so that listings can
+-- order by it with indexes. It is set on insert and when the community changes its setting, and
+-- cleared by the scheduled hot rank updates once the post is old enough.
+ALTER TABLE post_aggregates
+    ADD COLUMN scores_hidden boolean NOT NULL DEFAULT FALSE;
+
+UPDATE
+    post_aggregates pa
+SET
+    scores_hidden = TRUE
+FROM
+    community c
+WHERE
+    c.id = pa.community_id
+    AND scores_hidden (pa.published, c.hide_scores_until_hours);
+
+CREATE INDEX idx_post_aggregates_scores_hidden ON post_aggregates (published)
+WHERE
+    scores_hidden;
+
+CREATE FUNCTION post_aggregates_scores_hidden ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    NEW.scores_hidden = scores_hidden (NEW.published, (
+            SELECT
+                hide_scores_until_hours
+            FROM community
+            WHERE
+                id = NEW.community_id));
+    RETURN NEW;
+END
+$$;
+
+CREATE TRIGGER scores_hidden
+    BEFORE INSERT ON post_aggregates
+    FOR EACH ROW
+    EXECUTE FUNCTION post_aggregates_scores_hidden ();
+
+CREATE FUNCTION community_hide_scores_until_hours ()
+    RETURNS TRIGGER
+    LANGUAGE plpgsql
+    AS $$
+BEGIN
+    UPDATE
+        post_aggregates
+    SET
+        scores_hidden = scores_hidden (published, NEW.hide_scores_until_hours)
+    WHERE
+        community_id = NEW.id
+        AND scores_hidden != scores_hidden (published, NEW.hide_scores_until_hours);
+    RETURN NULL;
+END
+$$;
+
+CREATE TRIGGER hide_scores_until_hours
+    AFTER UPDATE OF hide_scores_until_hours ON community
+    FOR EACH ROW
+    WHEN (OLD.hide_scores_until_hours IS DISTINCT FROM NEW.hide_scores_until_hours)
+    EXECUTE FUNCTION community_hide_scores_until_hours ();
+
+-- The score which the hot ranks are calculated from, so that they don't reveal hidden scores
+CREATE FUNCTION visible_score (score bigint, scores_hidden boolean)
+    RETURNS bigint
+    AS $$
+    SELECT
+        CASE WHEN scores_hidden THEN
+            0
+        ELSE
+            score
+        END
+$$
+LANGUAGE sql
+IMMUTABLE PARALLEL SAFE;
+
+-- Like hidden_scores_sort() for comments, but based on the stored flag so that it can be indexed
+CREATE FUNCTION hidden_scores_sort (value bigint, scores_hidden boolean)
+    RETURNS bigint
+    AS $$
+    SELECT
+        CASE WHEN scores_hidden THEN
+            9223372036854775807
+        ELSE
+            value
+        END
+$$
+LANGUAGE sql
+IMMUTABLE PARALLEL SAFE;
+
+CREATE FUNCTION hidden_scores_sort (value double precision, scores_hidden boolean)
+    RETURNS double precision
+    AS $$
+    SELECT
+        CASE WHEN scores_hidden THEN
+            'Infinity'
+        ELSE
+            value
+        END
+$$
+LANGUAGE sql
+IMMUTABLE PARALLEL SAFE;
+
+-- Post listings sorted by score or controversy always use these sort keys now
+DROP INDEX idx_post_aggregates_featured_local_score, idx_post_aggregates_community_score, idx_post_aggregates_featured_community_score, idx_post_aggregates_featured_local_controversy, idx_post_aggregates_community_controversy, idx_post_aggregates_featured_community_controversy;
+
+CREATE INDEX idx_post_aggregates_featured_local_score ON post_aggregates (featured_local DESC, hidden_scores_sort (score, scores_hidden) DESC, published DESC);
+
+CREATE INDEX idx_post_aggregates_community_score ON post_aggregates (community_id, featured_local DESC, hidden_scores_sort (score, scores_hidden) DESC, published DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_score ON post_aggregates (community_id, featured_community DESC, hidden_scores_sort (score, scores_hidden) DESC, published DESC);
+
+CREATE INDEX idx_post_aggregates_featured_local_controversy ON post_aggregates (featured_local DESC, hidden_scores_sort (controversy_rank, scores_hidden) DESC);
+
+CREATE INDEX idx_post_aggregates_community_controversy ON post_aggregates (community_id, featured_local DESC, hidden_scores_sort (controversy_rank, scores_hidden) DESC);
+
+CREATE INDEX idx_post_aggregates_featured_community_controversy ON post_aggregates (community_id, featured_community DESC, hidden_scores_sort (controversy_rank, scores_hidden) DESC);
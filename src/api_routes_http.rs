@@ -15,9 +15,12 @@ use lemmy_api::{
     add_mod::add_mod_to_community,
     ban::ban_from_community,
     block::block_community,
+    federation_targets::get_federation_targets,
     follow::follow_community,
     hide::hide_community,
+    merge::merge_communities,
     transfer::transfer_community,
+    validate_name::validate_community_name,
   },
   local_user::{
     add_admin::add_admin,
@@ -30,6 +33,7 @@ use lemmy_api::{
     list_banned::list_banned_users,
     list_logins::list_logins,
     list_media::list_media,
+    list_moderated::list_moderated_communities,
     login::login,
     logout::logout,
     notifications::{
@@ -44,6 +48,7 @@ use lemmy_api::{
     reset_password::reset_password,
     save_settings::save_user_settings,
     update_totp::update_totp,
+    user_content_for_mod::get_user_content_for_mod,
     validate_auth::validate_auth,
     verify_email::verify_email,
   },
@@ -73,23 +78,32 @@ use lemmy_api::{
     federated_instances::get_federated_instances,
     leave_admin::leave_admin,
     list_all_media::list_all_media,
+    metrics::get_site_metrics,
     mod_log::get_mod_log,
+    mod_log_entry::get_mod_log_entry,
+    moderation_team::get_moderation_team,
     purge::{
       comment::purge_comment,
       community::purge_community,
       person::purge_person,
       post::purge_post,
+      search::purge_by_search,
     },
+    random_tagline::get_random_tagline,
     registration_applications::{
       approve::approve_registration_application,
       list::list_registration_applications,
       unread_count::get_unread_registration_application_count,
     },
+    rotate_federation_key::rotate_federation_key,
+    slow_queries::get_slow_queries,
+    stats_history::get_site_stats_history,
   },
   sitemap::get_sitemap,
 };
 use lemmy_api_crud::{
   comment::{
+    context::get_comment_context,
     create::create_comment,
     delete::delete_comment,
     read::get_comment,
@@ -103,6 +117,12 @@ use lemmy_api_crud::{
     remove::remove_community,
     update::update_community,
   },
+  community_flair::{
+    create::create_community_flair,
+    delete::delete_community_flair,
+    list::list_community_flairs,
+    update::update_community_flair,
+  },
   custom_emoji::{
     create::create_custom_emoji,
     delete::delete_custom_emoji,
@@ -125,15 +145,17 @@ use lemmy_api_crud::{
   user::{create::register, delete::delete_account},
 };
 use lemmy_apub::api::{
+  import_subscriptions::import_subscriptions,
   list_comments::list_comments,
   list_posts::list_posts,
   read_community::get_community,
   read_person::read_person,
-  resolve_object::resolve_object,
-  search::search,
+  resolve_object::{resolve_object, resolve_objects},
+  search::{search, search_query_error},
+  user_data_export::export_user_data,
   user_settings_backup::{export_settings, import_settings},
 };
-use lemmy_routes::images::image_proxy;
+use lemmy_routes::{images::image_proxy, nodeinfo::get_instance_info};
 use lemmy_utils::rate_limit::RateLimitCell;
 
 pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
@@ -148,15 +170,24 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           // Admin Actions
           .route("", web::post().to(create_site))
           .route("", web::put().to(update_site))
-          .route("/block", web::post().to(block_instance)),
+          .route("/block", web::post().to(block_instance))
+          .route("/metrics", web::get().to(get_site_metrics))
+          .route("/moderation_team", web::get().to(get_moderation_team))
+          .route("/random_tagline", web::get().to(get_random_tagline)),
       )
       .service(
         web::resource("/modlog")
           .wrap(rate_limit.message())
           .route(web::get().to(get_mod_log)),
       )
+      .service(
+        web::resource("/modlog/entry")
+          .wrap(rate_limit.message())
+          .route(web::get().to(get_mod_log_entry)),
+      )
       .service(
         web::resource("/search")
+          .app_data(web::QueryConfig::default().error_handler(search_query_error))
           .wrap(rate_limit.search())
           .route(web::get().to(search)),
       )
@@ -165,6 +196,12 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.message())
           .route(web::get().to(resolve_object)),
       )
+      .service(
+        web::resource("/resolve_objects")
+          // Each request can fetch many objects, so it shares the stricter limit of search
+          .wrap(rate_limit.search())
+          .route(web::post().to(resolve_objects)),
+      )
       // Community
       .service(
         web::resource("/community")
@@ -172,6 +209,12 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.register())
           .route(web::post().to(create_community)),
       )
+      .service(
+        web::resource("/community/import_subscriptions")
+          // Resolves many remote communities, so it shares the strict limit of the settings import
+          .wrap(rate_limit.import_user_settings())
+          .route(web::post().to(import_subscriptions)),
+      )
       .service(
         web::scope("/community")
           .wrap(rate_limit.message())
@@ -185,13 +228,21 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           // Mod Actions
           .route("/remove", web::post().to(remove_community))
           .route("/transfer", web::post().to(transfer_community))
+          .route("/merge", web::post().to(merge_communities))
           .route("/ban_user", web::post().to(ban_from_community))
-          .route("/mod", web::post().to(add_mod_to_community)),
+          .route("/mod", web::post().to(add_mod_to_community))
+          .route("/federation_targets", web::get().to(get_federation_targets))
+          .route("/flair", web::post().to(create_community_flair))
+          .route("/flair", web::put().to(update_community_flair))
+          .route("/flair/delete", web::post().to(delete_community_flair))
+          .route("/flair/list", web::get().to(list_community_flairs))
+          .route("/validate_name", web::get().to(validate_community_name)),
       )
       .service(
         web::scope("/federated_instances")
           .wrap(rate_limit.message())
-          .route("", web::get().to(get_federated_instances)),
+          .route("", web::get().to(get_federated_instances))
+          .route("/info", web::get().to(get_instance_info)),
       )
       // Post
       .service(
@@ -234,6 +285,7 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.message())
           .route("", web::get().to(get_comment))
           .route("", web::put().to(update_comment))
+          .route("/context", web::get().to(get_comment_context))
           .route("/delete", web::post().to(delete_comment))
           .route("/remove", web::post().to(remove_comment))
           .route("/mark_as_read", web::post().to(mark_reply_as_read))
@@ -284,6 +336,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .wrap(rate_limit.import_user_settings())
           .route(web::post().to(import_settings)),
       )
+      .service(
+        web::resource("/user/export_data")
+          .wrap(rate_limit.export_user_data())
+          .route(web::get().to(export_user_data)),
+      )
       // TODO, all the current account related actions under /user need to get moved here eventually
       .service(
         web::scope("/account")
@@ -328,6 +385,11 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
           .route("/totp/generate", web::post().to(generate_totp_secret))
           .route("/totp/update", web::post().to(update_totp))
           .route("/list_logins", web::get().to(list_logins))
+          .route(
+            "/moderated_communities",
+            web::get().to(list_moderated_communities),
+          )
+          .route("/content_for_mod", web::get().to(get_user_content_for_mod))
           .route("/validate_auth", web::get().to(validate_auth)),
       )
       // Admin Actions
@@ -348,12 +410,19 @@ pub fn config(cfg: &mut web::ServiceConfig, rate_limit: &RateLimitCell) {
             web::put().to(approve_registration_application),
           )
           .route("/list_all_media", web::get().to(list_all_media))
+          .route("/slow_queries", web::get().to(get_slow_queries))
+          .route("/stats_history", web::get().to(get_site_stats_history))
+          .route(
+            "/rotate_federation_key",
+            web::post().to(rotate_federation_key),
+          )
           .service(
             web::scope("/purge")
               .route("/person", web::post().to(purge_person))
               .route("/community", web::post().to(purge_community))
               .route("/post", web::post().to(purge_post))
-              .route("/comment", web::post().to(purge_comment)),
+              .route("/comment", web::post().to(purge_comment))
+              .route("/search", web::post().to(purge_by_search)),
           ),
       )
       .service(
@@ -1,7 +1,8 @@
 // This is for db migrations that require code
 use activitypub_federation::http_signatures::generate_actor_keypair;
 use diesel::{
-  sql_types::{Nullable, Text},
+  sql_query,
+  sql_types::{BigInt, Nullable, Text},
   ExpressionMethods,
   IntoSql,
   QueryDsl,
@@ -423,6 +424,9 @@ async fn regenerate_public_keys_2022_07_05(pool: &mut DbPool<'_>) -> LemmyResult
   Ok(())
 }
 
+/// Key of the advisory lock which is held while the local site is initialized.
+const INITIALIZE_LOCAL_SITE_LOCK_KEY: i64 = 20_221_010;
+
 /// This ensures that your local site is initialized and exists.
 ///
 /// If a site already exists, the DB migration should generate a local_site row.
@@ -433,6 +437,25 @@ async fn initialize_local_site_2022_10_10(
 ) -> LemmyResult<()> {
   info!("Running initialize_local_site_2022_10_10");
 
+  // If several Lemmy processes are started at once on a new database, only one of them may create
+  // the admin and site. The others wait for the lock, and then find the existing site. The lock is
+  // released when the transaction ends.
+  let conn = &mut get_conn(pool).await?;
+  conn
+    .build_transaction()
+    .run(|conn| {
+      Box::pin(async move {
+        sql_query("SELECT pg_advisory_xact_lock($1)")
+          .bind::<BigInt, _>(INITIALIZE_LOCAL_SITE_LOCK_KEY)
+          .execute(conn)
+          .await?;
+        create_local_site(&mut conn.into(), settings).await
+      }) as _
+    })
+    .await
+}
+
+async fn create_local_site(pool: &mut DbPool<'_>, settings: &Settings) -> LemmyResult<()> {
   // Check to see if local_site exists
   if LocalSite::read(pool).await.is_ok() {
     return Ok(());
@@ -0,0 +1,141 @@
+use activitypub_federation::config::{FederationConfig, FederationConfigBuilder};
+use actix_web::{
+  body::MessageBody,
+  dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+  Error,
+  HttpMessage,
+};
+use core::future::Ready;
+use futures_util::future::LocalBoxFuture;
+use lemmy_api_common::{context::LemmyContext, lemmy_db_views::structs::SiteView};
+use lemmy_apub::objects::instance::ApubSite;
+use lemmy_db_schema::source::site::Site;
+use lemmy_utils::{
+  error::{LemmyError, LemmyErrorType, LemmyResult},
+  CACHE_DURATION_FEDERATION,
+};
+use moka::future::Cache;
+use std::{future::ready, rc::Rc};
+
+/// Builds the federation config with the current key of the instance actor for signed fetches.
+/// Admins can rotate the key while Lemmy is running, so the site is reread regularly and the
+/// config is rebuilt once the key changes.
+#[derive(Clone)]
+pub struct FederationConfigs {
+  builder: FederationConfigBuilder<LemmyContext>,
+  context: LemmyContext,
+  signed_fetch_site: Cache<(), Option<Site>>,
+  configs: Cache<Option<String>, FederationConfig<LemmyContext>>,
+}
+
+impl FederationConfigs {
+  pub fn new(builder: FederationConfigBuilder<LemmyContext>, context: LemmyContext) -> Self {
+    FederationConfigs {
+      builder,
+      context,
+      signed_fetch_site: Cache::builder()
+        .max_capacity(1)
+        .time_to_live(CACHE_DURATION_FEDERATION)
+        .build(),
+      // Building a config starts a background task, so only keep the current one instead of
+      // rebuilding it whenever the site is reread.
+      configs: Cache::builder().max_capacity(1).build(),
+    }
+  }
+
+  pub async fn current(&self) -> LemmyResult<FederationConfig<LemmyContext>> {
+    let site = self
+      .signed_fetch_site
+      .try_get_with::<_, LemmyError>((), async {
+        let site_view = SiteView::read_local(&mut self.context.pool())
+          .await?
+          .ok_or(LemmyErrorType::LocalSiteNotSetup)?;
+        Ok(
+          site_view
+            .local_site
+            .federation_signed_fetch
+            .then_some(site_view.site),
+        )
+      })
+      .await
+      .map_err(|e| anyhow::anyhow!("Failed to read local site due to `{}`", e))?;
+
+    let private_key = site.as_ref().and_then(|s| s.private_key.clone());
+    Ok(
+      self
+        .configs
+        .try_get_with(private_key, async {
+          let mut builder = self.builder.clone();
+          if let Some(site) = site {
+            let site: ApubSite = site.into();
+            builder.signed_fetch_actor(&site);
+          }
+          builder.build().await
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to build federation config due to `{}`", e))?,
+    )
+  }
+}
+
+/// Passes the current [FederationConfig] to HTTP handlers. Replaces the middleware of the
+/// federation library, which always uses the config from startup.
+#[derive(Clone)]
+pub struct FederationMiddleware {
+  configs: FederationConfigs,
+}
+
+impl FederationMiddleware {
+  pub fn new(configs: FederationConfigs) -> Self {
+    FederationMiddleware { configs }
+  }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for FederationMiddleware
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Transform = FederationService<S>;
+  type InitError = ();
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(FederationService {
+      service: Rc::new(service),
+      configs: self.configs.clone(),
+    }))
+  }
+}
+
+pub struct FederationService<S> {
+  service: Rc<S>,
+  configs: FederationConfigs,
+}
+
+impl<S, B> Service<ServiceRequest> for FederationService<S>
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let svc = self.service.clone();
+    let configs = self.configs.clone();
+
+    Box::pin(async move {
+      let config = configs.current().await?;
+      req.extensions_mut().insert(config);
+      svc.call(req).await
+    })
+  }
+}
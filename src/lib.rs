@@ -1,18 +1,22 @@
 pub mod api_routes_http;
 pub mod code_migrations;
+pub mod federation_middleware;
 pub mod prometheus_metrics;
 pub mod root_span_builder;
 pub mod scheduled_tasks;
 pub mod session_middleware;
+pub mod slow_query_middleware;
 #[cfg(feature = "console")]
 pub mod telemetry;
 
 use crate::{
   code_migrations::run_advanced_migrations,
+  federation_middleware::{FederationConfigs, FederationMiddleware},
   root_span_builder::QuieterRootSpanBuilder,
   session_middleware::SessionMiddleware,
+  slow_query_middleware::SlowQueryMiddleware,
 };
-use activitypub_federation::config::{FederationConfig, FederationMiddleware};
+use activitypub_federation::config::FederationConfig;
 use actix_cors::Cors;
 use actix_web::{
   dev::{ServerHandle, ServiceResponse},
@@ -36,7 +40,6 @@ use lemmy_api_common::{
 };
 use lemmy_apub::{
   activities::{handle_outgoing_activities, match_outgoing_activities},
-  objects::instance::ApubSite,
   VerifyUrlData,
   FEDERATION_HTTP_FETCH_LIMIT,
 };
@@ -55,7 +58,7 @@ use prometheus_metrics::serve_prometheus;
 use reqwest_middleware::ClientBuilder;
 use reqwest_tracing::TracingMiddleware;
 use serde_json::json;
-use std::{env, ops::Deref};
+use std::env;
 use tokio::signal::unix::SignalKind;
 use tracing::subscriber::set_global_default;
 use tracing_actix_web::TracingLogger;
@@ -143,7 +146,8 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
   // Set up the rate limiter
   let rate_limit_config =
     local_site_rate_limit_to_rate_limit_config(&site_view.local_site_rate_limit);
-  let rate_limit_cell = RateLimitCell::new(rate_limit_config);
+  let rate_limit_cell =
+    RateLimitCell::new(rate_limit_config).with_exempt_keys(SETTINGS.rate_limit_exempt_keys.clone());
 
   println!(
     "Starting HTTP server at {}:{}",
@@ -178,11 +182,8 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
     .debug(cfg!(debug_assertions))
     .http_signature_compat(true)
     .url_verifier(Box::new(VerifyUrlData(context.inner_pool().clone())));
-  if local_site.federation_signed_fetch {
-    let site: ApubSite = site_view.site.into();
-    federation_config.signed_fetch_actor(&site);
-  }
-  let federation_config = federation_config.build().await?;
+  let federation_configs = FederationConfigs::new(federation_config, context.clone());
+  let federation_config = federation_configs.current().await?;
 
   MATCH_OUTGOING_ACTIVITIES
     .set(Box::new(move |d, c| {
@@ -198,7 +199,8 @@ pub async fn start_lemmy_server(args: CmdArgs) -> LemmyResult<()> {
     }
 
     Some(create_http_server(
-      federation_config.clone(),
+      context.clone(),
+      federation_configs,
       SETTINGS.clone(),
       federation_enabled,
     )?)
@@ -263,7 +265,8 @@ fn create_startup_server() -> LemmyResult<ServerHandle> {
 }
 
 fn create_http_server(
-  federation_config: FederationConfig<LemmyContext>,
+  context: LemmyContext,
+  federation_configs: FederationConfigs,
   settings: Settings,
   federation_enabled: bool,
 ) -> LemmyResult<ServerHandle> {
@@ -274,8 +277,7 @@ fn create_http_server(
     .build()
     .expect("Should always be buildable");
 
-  let context: LemmyContext = federation_config.deref().clone();
-  let rate_limit_cell = federation_config.rate_limit_cell().clone();
+  let rate_limit_cell = context.rate_limit_cell().clone();
 
   // Pictrs cannot use proxy
   let pictrs_client = ClientBuilder::new(client_builder(&SETTINGS).no_proxy().build()?)
@@ -297,8 +299,9 @@ fn create_http_server(
       .wrap(ErrorHandlers::new().default_handler(jsonify_plain_text_errors))
       .app_data(Data::new(context.clone()))
       .app_data(Data::new(rate_limit_cell.clone()))
-      .wrap(FederationMiddleware::new(federation_config.clone()))
+      .wrap(FederationMiddleware::new(federation_configs.clone()))
       .wrap(SessionMiddleware::new(context.clone()))
+      .wrap(SlowQueryMiddleware)
       .wrap(Condition::new(
         SETTINGS.prometheus.is_some(),
         prom_api_metrics.clone(),
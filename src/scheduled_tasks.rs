@@ -1,4 +1,4 @@
-use chrono::{DateTime, TimeZone, Utc};
+use chrono::{DateTime, NaiveTime, TimeDelta, TimeZone, Utc};
 use clokwerk::{AsyncScheduler, TimeUnits as CTimeUnits};
 use diesel::{
   dsl::IntervalDsl,
@@ -12,6 +12,8 @@ use diesel::{
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
 use lemmy_api_common::context::LemmyContext;
 use lemmy_db_schema::{
+  aggregates::structs::PostAggregates,
+  newtypes::PostId,
   schema::{
     captcha_answer,
     comment,
@@ -19,12 +21,16 @@ use lemmy_db_schema::{
     instance,
     person,
     post,
+    post_edit_history,
     received_activity,
     sent_activity,
   },
   source::{
     instance::{Instance, InstanceForm},
+    local_site::LocalSite,
     local_user::LocalUser,
+    post::Post,
+    site_stats_history::SiteStatsHistory,
   },
   utils::{get_conn, naive_now, now, DbPool, DELETED_REPLACEMENT_TEXT},
 };
@@ -86,6 +92,8 @@ pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
   // - Overwrite deleted & removed posts and comments every day
   // - Delete old denied users
   // - Update instance software
+  // - Lock old posts
+  // - Record site stats of the previous day
   scheduler.every(CTimeUnits::days(1)).run(move || {
     let context = context_1.clone();
 
@@ -96,6 +104,11 @@ pub async fn setup(context: LemmyContext) -> LemmyResult<()> {
         .await
         .map_err(|e| warn!("Failed to update instance software: {e}"))
         .ok();
+      lock_old_posts(&mut context.pool())
+        .await
+        .map_err(|e| warn!("Failed to lock old posts: {e}"))
+        .ok();
+      record_site_stats(&mut context.pool()).await;
     }
   });
 
@@ -114,6 +127,7 @@ async fn startup_jobs(pool: &mut DbPool<'_>) {
   clear_old_activities(pool).await;
   overwrite_deleted_posts_and_comments(pool).await;
   delete_old_denied_users(pool).await;
+  record_site_stats(pool).await;
 }
 
 /// Update the hot_rank columns for the aggregates tables
@@ -121,6 +135,11 @@ async fn startup_jobs(pool: &mut DbPool<'_>) {
 async fn update_hot_ranks(pool: &mut DbPool<'_>) {
   info!("Updating hot ranks for all history...");
 
+  // Done first, so that the post ranks below include these scores again
+  if let Err(e) = PostAggregates::unhide_expired_scores(pool).await {
+    error!("Failed to unhide expired post scores: {e}");
+  }
+
   let conn = get_conn(pool).await;
 
   match conn {
@@ -216,7 +235,7 @@ async fn process_ranks_in_batches(
 }
 
 /// Post aggregates is a special case, since it needs to join to the community_aggregates
-/// table, to get the active monthly user counts.
+/// table, to get the active monthly user counts. Hidden scores are left out of the ranks.
 async fn process_post_aggregates_ranks_in_batches(conn: &mut AsyncPgConnection) {
   let process_start_time: DateTime<Utc> = Utc
     .timestamp_opt(0, 0)
@@ -236,9 +255,9 @@ async fn process_post_aggregates_ranks_in_batches(conn: &mut AsyncPgConnection)
                LIMIT $2
                FOR UPDATE SKIP LOCKED)
          UPDATE post_aggregates pa
-           SET hot_rank = hot_rank(pa.score, pa.published),
-           hot_rank_active = hot_rank(pa.score, pa.newest_comment_time_necro),
-           scaled_rank = scaled_rank(pa.score, pa.published, ca.users_active_month)
+           SET hot_rank = hot_rank(visible_score(pa.score, pa.scores_hidden), pa.published),
+           hot_rank_active = hot_rank(visible_score(pa.score, pa.scores_hidden), pa.newest_comment_time_necro),
+           scaled_rank = scaled_rank(visible_score(pa.score, pa.scores_hidden), pa.published, ca.users_active_month)
          FROM batch, community_aggregates ca
          WHERE pa.post_id = batch.post_id and pa.community_id = ca.community_id RETURNING pa.published;
     "#,
@@ -336,7 +355,7 @@ async fn overwrite_deleted_posts_and_comments(pool: &mut DbPool<'_>) {
 
   match conn {
     Ok(mut conn) => {
-      diesel::update(
+      let overwritten_post_ids = diesel::update(
         post::table
           .filter(post::deleted.eq(true))
           .filter(post::updated.lt(now().nullable() - 1.months()))
@@ -346,14 +365,30 @@ async fn overwrite_deleted_posts_and_comments(pool: &mut DbPool<'_>) {
         post::body.eq(DELETED_REPLACEMENT_TEXT),
         post::name.eq(DELETED_REPLACEMENT_TEXT),
       ))
-      .execute(&mut conn)
+      .returning(post::id)
+      .get_results::<PostId>(&mut conn)
       .await
-      .map(|_| {
+      .map(|post_ids| {
         info!("Done.");
+        post_ids
       })
       .map_err(|e| error!("Failed to overwrite deleted posts: {e}"))
       .ok();
 
+      if let Some(post_ids) = overwritten_post_ids {
+        info!("Deleting edit history of overwritten posts...");
+        diesel::delete(
+          post_edit_history::table.filter(post_edit_history::post_id.eq_any(post_ids)),
+        )
+        .execute(&mut conn)
+        .await
+        .map(|_| {
+          info!("Done.");
+        })
+        .map_err(|e| error!("Failed to delete edit history of overwritten posts: {e}"))
+        .ok();
+      }
+
       info!("Overwriting deleted comments...");
       diesel::update(
         comment::table
@@ -450,6 +485,47 @@ async fn update_banned_when_expired(pool: &mut DbPool<'_>) {
   }
 }
 
+/// Locks posts in local communities which are older than `auto_lock_posts_after_days`, so that
+/// dead threads stay read-only.
+///
+/// The locks are not federated. Lock activities need a moderator as actor, which remote instances
+/// verify, and automatic locks don't have one. Comments which remote users write on these posts
+/// are still rejected when they reach the community.
+async fn lock_old_posts(pool: &mut DbPool<'_>) -> LemmyResult<()> {
+  let days = LocalSite::read(pool).await?.auto_lock_posts_after_days;
+  if days <= 0 {
+    return Ok(());
+  }
+  info!("Locking old posts...");
+
+  let locked_post_ids = Post::lock_older_than(pool, days).await?;
+
+  info!("Locked {} old posts.", locked_post_ids.len());
+  Ok(())
+}
+
+/// Records the number of new users, posts and comments of the previous day. This also runs on
+/// startup, and continues after the last recorded day, so that days missed during downtime are
+/// filled in. Recording a day twice is harmless.
+async fn record_site_stats(pool: &mut DbPool<'_>) {
+  info!("Recording site stats history...");
+  let one_day = TimeDelta::try_days(1).expect("TimeDelta out of bounds");
+  let today = Utc::now().date_naive().and_time(NaiveTime::MIN).and_utc();
+  let yesterday = today - one_day;
+  let from = match SiteStatsHistory::latest_day(pool).await {
+    Ok(Some(latest)) => (latest + one_day).min(yesterday),
+    Ok(None) => yesterday,
+    Err(e) => {
+      error!("Failed to read site stats history: {e}");
+      return;
+    }
+  };
+  match SiteStatsHistory::record_days(pool, from, yesterday).await {
+    Ok(_) => info!("Done."),
+    Err(e) => error!("Failed to record site stats history: {e}"),
+  }
+}
+
 /// Updates the instance software and version
 ///
 /// TODO: if instance has been dead for a long time, it should be checked less frequently
@@ -0,0 +1,66 @@
+use actix_web::{
+  body::MessageBody,
+  dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+  Error,
+};
+use core::future::Ready;
+use futures_util::future::LocalBoxFuture;
+use lemmy_api_common::slow_query_log::record_request_duration;
+use std::{future::ready, rc::Rc, time::Instant};
+
+/// Measures how long each request takes, so that slow ones can be listed by admins.
+#[derive(Clone)]
+pub struct SlowQueryMiddleware;
+
+impl<S, B> Transform<S, ServiceRequest> for SlowQueryMiddleware
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: MessageBody + 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Transform = SlowQueryService<S>;
+  type InitError = ();
+  type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+  fn new_transform(&self, service: S) -> Self::Future {
+    ready(Ok(SlowQueryService {
+      service: Rc::new(service),
+    }))
+  }
+}
+
+pub struct SlowQueryService<S> {
+  service: Rc<S>,
+}
+
+impl<S, B> Service<ServiceRequest> for SlowQueryService<S>
+where
+  S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+  S::Future: 'static,
+  B: 'static,
+{
+  type Response = ServiceResponse<B>;
+  type Error = Error;
+  type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+  forward_ready!(service);
+
+  fn call(&self, req: ServiceRequest) -> Self::Future {
+    let svc = self.service.clone();
+
+    Box::pin(async move {
+      let start = Instant::now();
+      let res = svc.call(req).await?;
+
+      // Use the route pattern instead of the actual path, to avoid storing ids or names
+      let request = res.request();
+      let path = request
+        .match_pattern()
+        .unwrap_or_else(|| request.path().to_string());
+      record_request_duration(&path, start.elapsed());
+      Ok(res)
+    })
+  }
+}